@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+use crate::parse::HourlyData;
+
+const HISTORY_PATH_PREFIX: &str = "/data/forecasts-";
+pub const RAIN_FORECAST_PROBABILITY: usize = 50; // from this precipitation probability, rain counts as forecast
+pub const RAIN_PRECIPITATION: f64 = 0.1; // mm, from this much it counts as having rained
+
+// what was forecast for an hour the first time it was fetched
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedHour {
+    pub temperature: f64,
+    pub precipitation_probability: Option<usize>,
+}
+
+// how the recorded forecasts compare with what the model now says happened
+#[derive(Clone, Debug, PartialEq)]
+pub struct Accuracy {
+    pub hours: usize,
+    pub temperature_error: f64, // average, in either direction
+    pub rain_hours: usize, // the hours with both a precipitation probability and amount
+    pub rain_right: usize, // of those, the ones where rain was forecast and fell or wasn't forecast and didn't
+}
+
+// eg. "2023-11-19T14"
+pub fn hour_key(date: &str, hour: usize) -> String {
+    format!("{}T{:02}", date, hour)
+}
+
+// eg. "2023-11-19T14 9.8 40", a line for each hour, without a probability for models that don't have one
+pub fn parse_history(text: &str) -> BTreeMap<String, RecordedHour> {
+    let mut history = BTreeMap::new();
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        if let (Some(hour), Some(Ok(temperature))) = (fields.next(), fields.next().map(|t| t.parse())) {
+            let precipitation_probability = fields.next().and_then(|p| p.parse().ok());
+            history.insert(hour.to_owned(), RecordedHour { temperature, precipitation_probability });
+        }
+    }
+    history
+}
+
+pub fn format_history(history: &BTreeMap<String, RecordedHour>) -> String {
+    history
+        .iter()
+        .map(|(hour, recorded)| match recorded.precipitation_probability {
+            Some(probability) => format!("{} {} {}\n", hour, recorded.temperature, probability),
+            None => format!("{} {}\n", hour, recorded.temperature),
+        })
+        .collect()
+}
+
+// hours that were forecast before keep their earlier forecast, and hours before the oldest one are dropped
+pub fn record_forecast<'a>(history: &mut BTreeMap<String, RecordedHour>, hours: impl Iterator<Item = (String, &'a HourlyData)>, oldest: &str) {
+    for (hour, hourly_data) in hours {
+        history.entry(hour).or_insert(RecordedHour { temperature: hourly_data.temperature_2m, precipitation_probability: hourly_data.precipitation_probability });
+    }
+    history.retain(|hour, _| hour.as_str() >= oldest);
+}
+
+pub fn forecast_accuracy<'a>(history: &BTreeMap<String, RecordedHour>, happened: impl Iterator<Item = (String, &'a HourlyData)>) -> Option<Accuracy> {
+    let mut accuracy = Accuracy { hours: 0, temperature_error: 0.0, rain_hours: 0, rain_right: 0 };
+    for (hour, hourly_data) in happened {
+        let Some(recorded) = history.get(&hour) else {
+            continue;
+        };
+        accuracy.hours += 1;
+        accuracy.temperature_error += (recorded.temperature - hourly_data.temperature_2m).abs();
+        if let (Some(probability), Some(precipitation)) = (recorded.precipitation_probability, hourly_data.precipitation) {
+            accuracy.rain_hours += 1;
+            if (probability >= RAIN_FORECAST_PROBABILITY) == (precipitation >= RAIN_PRECIPITATION) {
+                accuracy.rain_right += 1;
+            }
+        }
+    }
+    if accuracy.hours == 0 {
+        return None;
+    }
+    accuracy.temperature_error /= accuracy.hours as f64;
+    Some(accuracy)
+}
+
+// a file for each place, nothing recorded yet is the same as an empty file
+fn history_path(latitude: f64, longitude: f64) -> String {
+    format!("{}{:.2}_{:.2}", HISTORY_PATH_PREFIX, latitude, longitude)
+}
+
+pub fn read_history(latitude: f64, longitude: f64) -> BTreeMap<String, RecordedHour> {
+    fs::read_to_string(history_path(latitude, longitude)).map(|text| parse_history(&text)).unwrap_or_default()
+}
+
+pub fn save_history(latitude: f64, longitude: f64, history: &BTreeMap<String, RecordedHour>) -> io::Result<()> {
+    fs::write(history_path(latitude, longitude), format_history(history))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hour(temperature: f64, precipitation_probability: Option<usize>, precipitation: Option<f64>) -> HourlyData {
+        HourlyData { temperature_2m: temperature, precipitation_probability, precipitation, ..Default::default() }
+    }
+
+    #[test]
+    fn keeps_the_first_forecast_of_each_hour() {
+        let mut history = parse_history("2023-11-18T23 4.5 10\n2023-11-19T00 4 20\nbroken\n");
+        let forecast = [hour(3.0, Some(30), None), hour(2.5, None, None)];
+        record_forecast(&mut history, vec![(hour_key("2023-11-19", 0), &forecast[0]), (hour_key("2023-11-19", 1), &forecast[1])].into_iter(), "2023-11-19T00");
+        assert_eq!(format_history(&history), "2023-11-19T00 4 20\n2023-11-19T01 2.5\n");
+        assert_eq!(parse_history(&format_history(&history)), history);
+    }
+
+    #[test]
+    fn compares_the_forecast_with_what_happened() {
+        let history = parse_history("2023-11-19T00 4 80\n2023-11-19T01 2 10\n2023-11-19T02 1\n");
+        let happened = [hour(3.0, None, Some(0.0)), hour(3.0, None, Some(0.0)), hour(0.0, None, Some(1.2)), hour(5.0, None, Some(0.0))];
+        let accuracy = forecast_accuracy(&history, happened.iter().enumerate().map(|(i, h)| (hour_key("2023-11-19", i), h))).unwrap();
+        assert_eq!(accuracy, Accuracy { hours: 3, temperature_error: 1.0, rain_hours: 2, rain_right: 1 });
+        assert_eq!(forecast_accuracy(&BTreeMap::new(), happened.iter().map(|h| (hour_key("2023-11-19", 0), h))), None);
+    }
+}
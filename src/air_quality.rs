@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::error::{RequestKind, WeatherPalError};
+use crate::wmo::Severity;
+
+pub const AIR_QUALITY_REQUEST_ID: &str = "air_quality";
+// the api name, the name to show, and the grains per m³ from which the level is moderate and high
+pub const POLLEN_SPECIES: &[(&str, &str, f64, f64)] = &[
+    ("alder_pollen", "alder", 10.0, 100.0),
+    ("birch_pollen", "birch", 10.0, 100.0),
+    ("grass_pollen", "grass", 10.0, 50.0),
+    ("mugwort_pollen", "mugwort", 5.0, 15.0),
+    ("olive_pollen", "olive", 10.0, 100.0),
+    ("ragweed_pollen", "ragweed", 5.0, 15.0),
+];
+// µg/m³, the WHO guideline and the second interim target, 24 hour mean for PM2.5 and 8 hour mean for ozone
+const PM2_5_LEVELS: (f64, f64) = (15.0, 37.5);
+const OZONE_LEVELS: (f64, f64) = (100.0, 160.0);
+const GUIDANCE_HOURS: usize = 24;
+
+// from which concentration the air is moderate and high, lower for sensitive groups
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AirQualityThresholds {
+    pub pm2_5: (f64, f64),
+    pub ozone: (f64, f64),
+}
+
+impl Default for AirQualityThresholds {
+    fn default() -> Self {
+        AirQualityThresholds { pm2_5: PM2_5_LEVELS, ozone: OZONE_LEVELS }
+    }
+}
+
+impl AirQualityThresholds {
+    pub fn from_config(thresholds: &str) -> Self {
+        // eg. "pm2_5:10,ozone:80" for the moderate level, the high level moves along with it
+        let mut air_quality_thresholds = AirQualityThresholds::default();
+        for (pollutant, threshold) in thresholds.split(',').filter_map(|t| t.split_once(':')) {
+            let Some(threshold) = threshold.trim().parse::<f64>().ok().filter(|t| *t > 0.0) else {
+                continue;
+            };
+            let scaled = |(moderate, high): (f64, f64)| (threshold, threshold * high / moderate);
+            match pollutant.trim() {
+                "pm2_5" => air_quality_thresholds.pm2_5 = scaled(PM2_5_LEVELS),
+                "ozone" => air_quality_thresholds.ozone = scaled(OZONE_LEVELS),
+                _ => {}
+            }
+        }
+        air_quality_thresholds
+    }
+}
+
+// a stretch of hours with unhealthy air, to plan around it
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnhealthyAir {
+    pub hours: Range<usize>,
+    pub level: Level, // the worst of these hours
+    pub pollutant: &'static str, // the one behind it
+    pub peak: f64, // µg/m³
+}
+
+// the air at the location for an hour, pollen is only forecast for europe
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AirQuality {
+    pub pm2_5: Option<f64>, // µg/m³
+    pub ozone: Option<f64>, // µg/m³
+    pub pollen: Vec<Option<f64>>, // grains/m³, in the order of POLLEN_SPECIES
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    None,
+    Low,
+    Moderate,
+    High,
+}
+
+impl Level {
+    pub fn from_value(value: f64, (moderate, high): (f64, f64)) -> Self {
+        if value >= high {
+            Level::High
+        } else if value >= moderate {
+            Level::Moderate
+        } else if value > 0.0 {
+            Level::Low
+        } else {
+            Level::None
+        }
+    }
+    pub fn label(&self) -> &'static str {
+        match self {
+            Level::None => "none",
+            Level::Low => "low",
+            Level::Moderate => "moderate",
+            Level::High => "high",
+        }
+    }
+    pub fn severity(&self) -> Severity {
+        match self {
+            Level::None | Level::Low => Severity::None,
+            Level::Moderate => Severity::Mild,
+            Level::High => Severity::Severe,
+        }
+    }
+}
+
+impl AirQuality {
+    // each species that is in the air, with its level
+    pub fn pollen_levels(&self) -> Vec<(&'static str, f64, Level)> {
+        POLLEN_SPECIES
+            .iter()
+            .zip(&self.pollen)
+            .filter_map(|(&(_, name, moderate, high), grains)| grains.filter(|g| *g > 0.0).map(|g| (name, g, Level::from_value(g, (moderate, high)))))
+            .collect()
+    }
+    pub fn pm2_5_level(&self, thresholds: AirQualityThresholds) -> Level {
+        self.pm2_5.map(|p| Level::from_value(p, thresholds.pm2_5)).unwrap_or(Level::None)
+    }
+    pub fn ozone_level(&self, thresholds: AirQualityThresholds) -> Level {
+        self.ozone.map(|o| Level::from_value(o, thresholds.ozone)).unwrap_or(Level::None)
+    }
+    // the worst of the pollen and the pollutants, as they add up for allergic airways
+    pub fn level(&self, thresholds: AirQualityThresholds) -> Level {
+        self.pollen_levels().into_iter().map(|(_, _, level)| level).chain([self.pm2_5_level(thresholds), self.ozone_level(thresholds)]).max().unwrap_or(Level::None)
+    }
+}
+
+#[derive(Deserialize)]
+struct AirQualityResponse {
+    hourly: AirQualityHourlyResponse,
+}
+
+#[derive(Deserialize)]
+struct AirQualityHourlyResponse {
+    #[serde(default)]
+    time: Vec<String>,
+    #[serde(default)]
+    pm2_5: Vec<Option<f64>>,
+    #[serde(default)]
+    ozone: Vec<Option<f64>>,
+    #[serde(default)]
+    alder_pollen: Vec<Option<f64>>,
+    #[serde(default)]
+    birch_pollen: Vec<Option<f64>>,
+    #[serde(default)]
+    grass_pollen: Vec<Option<f64>>,
+    #[serde(default)]
+    mugwort_pollen: Vec<Option<f64>>,
+    #[serde(default)]
+    olive_pollen: Vec<Option<f64>>,
+    #[serde(default)]
+    ragweed_pollen: Vec<Option<f64>>,
+}
+
+pub fn air_quality_hourly_variables() -> Vec<&'static str> {
+    ["pm2_5", "ozone"].iter().copied().chain(POLLEN_SPECIES.iter().map(|(variable, ..)| *variable)).collect()
+}
+
+// indexed by the hours of the forecast, so the past days are dropped the same way
+pub fn parse_air_quality(body: Vec<u8>, past_days: usize) -> Result<BTreeMap<usize, AirQuality>, WeatherPalError> {
+    let response: AirQualityResponse = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::AirQuality, e.to_string()))?;
+    let hourly = response.hourly;
+    // in the order of POLLEN_SPECIES
+    let pollen = [&hourly.alder_pollen, &hourly.birch_pollen, &hourly.grass_pollen, &hourly.mugwort_pollen, &hourly.olive_pollen, &hourly.ragweed_pollen];
+    Ok((past_days * 24..hourly.time.len())
+        .map(|i| {
+            let air_quality = AirQuality {
+                pm2_5: hourly.pm2_5.get(i).copied().flatten(),
+                ozone: hourly.ozone.get(i).copied().flatten(),
+                pollen: pollen.iter().map(|grains| grains.get(i).copied().flatten()).collect(),
+            };
+            (i - past_days * 24, air_quality)
+        })
+        .collect())
+}
+
+// the first hours in the coming day with PM2.5 or ozone from the moderate level on, pollen doesn't count here
+pub fn unhealthy_air(air_quality: &BTreeMap<usize, AirQuality>, from: usize, thresholds: AirQualityThresholds) -> Option<UnhealthyAir> {
+    let mut unhealthy_hours = vec![];
+    for (hour, air_quality) in air_quality.range(from..from + GUIDANCE_HOURS) {
+        // the pollutants are compared by how far above their threshold they are
+        let pollutants = [("PM2.5", air_quality.pm2_5, thresholds.pm2_5.0), ("ozone", air_quality.ozone, thresholds.ozone.0)];
+        let worst = pollutants
+            .iter()
+            .filter_map(|&(pollutant, value, threshold)| value.filter(|v| *v >= threshold).map(|v| (pollutant, v, v / threshold)))
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+        match worst {
+            Some(worst) => unhealthy_hours.push((*hour, worst)),
+            None if !unhealthy_hours.is_empty() => break,
+            None => {}
+        }
+    }
+    let (first, last) = (unhealthy_hours.first()?.0, unhealthy_hours.last()?.0);
+    let &(_, (pollutant, peak, _)) = unhealthy_hours.iter().max_by(|a, b| (a.1).2.total_cmp(&(b.1).2))?;
+    let level = if pollutant == "ozone" { Level::from_value(peak, thresholds.ozone) } else { Level::from_value(peak, thresholds.pm2_5) };
+    Some(UnhealthyAir { hours: first..last + 1, level, pollutant, peak })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pollen_and_pollutants() {
+        let times: Vec<String> = (0..26).map(|h| format!("\"2023-11-{}T{:02}:00\"", 18 + h / 24, h % 24)).collect();
+        let birch: Vec<String> = (0..26).map(|h| if h == 25 { "null".to_owned() } else { format!("{}", h * 5) }).collect();
+        let body = format!(r#"{{"hourly":{{"time":[{}],"birch_pollen":[{}],"pm2_5":[]}}}}"#, times.join(","), birch.join(","));
+        let air_quality = parse_air_quality(body.into_bytes(), 1).unwrap();
+        assert_eq!(air_quality.len(), 2);
+        assert_eq!(air_quality[&0].pollen, vec![None, Some(120.0), None, None, None, None]);
+        assert_eq!(air_quality[&0].pollen_levels(), vec![("birch", 120.0, Level::High)]);
+        assert_eq!(air_quality[&1].pollen_levels(), vec![]);
+        assert_eq!(air_quality[&1].pm2_5, None);
+        assert!(parse_air_quality(b"{}".to_vec(), 1).is_err());
+    }
+
+    #[test]
+    fn rates_the_air_by_its_worst_part() {
+        let air_quality = AirQuality { pm2_5: Some(20.0), ozone: Some(50.0), pollen: vec![None, Some(4.0), Some(0.0)] };
+        let thresholds = AirQualityThresholds::default();
+        assert_eq!(air_quality.pm2_5_level(thresholds), Level::Moderate);
+        assert_eq!(air_quality.ozone_level(thresholds), Level::Low);
+        assert_eq!(air_quality.pollen_levels(), vec![("birch", 4.0, Level::Low)]);
+        assert_eq!(air_quality.level(thresholds), Level::Moderate);
+        assert_eq!(AirQuality { ozone: Some(170.0), ..air_quality.clone() }.level(thresholds), Level::High);
+        assert_eq!(AirQuality::default().level(thresholds), Level::None);
+        assert_eq!(air_quality.pm2_5_level(AirQualityThresholds::from_config("pm2_5:8")), Level::High);
+    }
+
+    #[test]
+    fn parses_thresholds_for_sensitive_groups() {
+        assert_eq!(AirQualityThresholds::from_config("pm2_5:10, ozone:80"), AirQualityThresholds { pm2_5: (10.0, 25.0), ozone: (80.0, 128.0) });
+        assert_eq!(AirQualityThresholds::from_config("ozone:-5,no2:40,pm2_5:x"), AirQualityThresholds::default());
+    }
+
+    #[test]
+    fn finds_the_next_hours_with_unhealthy_air() {
+        let air_quality: BTreeMap<usize, AirQuality> = (0..48)
+            .map(|hour| {
+                let ozone = if (14..18).contains(&hour) { 100.0 + hour as f64 * 5.0 } else { 60.0 };
+                (hour, AirQuality { pm2_5: Some(10.0), ozone: Some(ozone), pollen: vec![] })
+            })
+            .collect();
+        let thresholds = AirQualityThresholds::default();
+        let expected = UnhealthyAir { hours: 14..18, level: Level::High, pollutant: "ozone", peak: 185.0 };
+        assert_eq!(unhealthy_air(&air_quality, 10, thresholds), Some(expected));
+        assert_eq!(unhealthy_air(&air_quality, 18, thresholds), None);
+        let sensitive = AirQualityThresholds::from_config("pm2_5:10");
+        assert_eq!(unhealthy_air(&air_quality, 18, sensitive).map(|u| (u.hours, u.pollutant)), Some((18..42, "PM2.5")));
+    }
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RequestKind, WeatherPalError};
+use crate::wmo::Severity;
+
+pub const NWS_ALERTS_REQUEST_ID: &str = "alerts";
+pub const METEOALARM_REQUEST_PREFIX: &str = "meteoalarm:"; // followed by the latitude, longitude and place of the location
+
+// the national weather services that take part in MeteoAlarm, by the country names of the geocoding API and their feed
+const METEOALARM_COUNTRIES: &[(&str, &str)] = &[
+    ("Austria", "austria"),
+    ("Belgium", "belgium"),
+    ("Bosnia and Herzegovina", "bosnia-herzegovina"),
+    ("Bulgaria", "bulgaria"),
+    ("Croatia", "croatia"),
+    ("Cyprus", "cyprus"),
+    ("Czechia", "czechia"),
+    ("Denmark", "denmark"),
+    ("Estonia", "estonia"),
+    ("Finland", "finland"),
+    ("France", "france"),
+    ("Germany", "germany"),
+    ("Greece", "greece"),
+    ("Hungary", "hungary"),
+    ("Iceland", "iceland"),
+    ("Ireland", "ireland"),
+    ("Israel", "israel"),
+    ("Italy", "italy"),
+    ("Latvia", "latvia"),
+    ("Lithuania", "lithuania"),
+    ("Luxembourg", "luxembourg"),
+    ("Malta", "malta"),
+    ("Moldova", "moldova"),
+    ("Montenegro", "montenegro"),
+    ("The Netherlands", "netherlands"),
+    ("Netherlands", "netherlands"),
+    ("North Macedonia", "republic-of-north-macedonia"),
+    ("Norway", "norway"),
+    ("Poland", "poland"),
+    ("Portugal", "portugal"),
+    ("Romania", "romania"),
+    ("Serbia", "serbia"),
+    ("Slovakia", "slovakia"),
+    ("Slovenia", "slovenia"),
+    ("Spain", "spain"),
+    ("Sweden", "sweden"),
+    ("Switzerland", "switzerland"),
+    ("Ukraine", "ukraine"),
+    ("United Kingdom", "united-kingdom"),
+];
+// min latitude, max latitude, min longitude, max longitude covered by the NWS, for locations given as coordinates
+const NWS_REGIONS: &[(f64, f64, f64, f64)] = &[
+    (24.5, 49.5, -125.0, -66.9), // contiguous states
+    (51.0, 71.5, -170.0, -129.9), // alaska
+    (18.9, 22.3, -160.3, -154.8), // hawaii
+];
+
+// the CAP severity of a warning, from the least to the most dangerous
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum AlertLevel {
+    Minor,
+    Moderate,
+    Severe,
+    Extreme,
+}
+
+impl AlertLevel {
+    pub fn from_cap(severity: &str) -> Self {
+        match severity {
+            "Extreme" => AlertLevel::Extreme,
+            "Severe" => AlertLevel::Severe,
+            "Moderate" => AlertLevel::Moderate,
+            _ => AlertLevel::Minor,
+        }
+    }
+    pub fn severity(&self) -> Severity {
+        match self {
+            AlertLevel::Minor => Severity::None,
+            AlertLevel::Moderate => Severity::Mild,
+            AlertLevel::Severe | AlertLevel::Extreme => Severity::Severe,
+        }
+    }
+}
+
+// an active warning of a national weather service, eg. "Flood Warning"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub event: String,
+    pub level: AlertLevel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertSource {
+    Nws,
+    MeteoAlarm(&'static str), // the country feed
+}
+
+impl AlertSource {
+    pub fn for_location(latitude: f64, longitude: f64, location: &str) -> Option<Self> {
+        // eg. "Vienna, Austria", the country is the last part of the location
+        let country = location.rsplit(", ").next().unwrap_or_default();
+        if country == "United States" {
+            Some(AlertSource::Nws)
+        } else if let Some((_, feed)) = METEOALARM_COUNTRIES.iter().find(|(name, _)| *name == country) {
+            Some(AlertSource::MeteoAlarm(feed))
+        } else if NWS_REGIONS
+            .iter()
+            .any(|(min_latitude, max_latitude, min_longitude, max_longitude)| (*min_latitude..=*max_latitude).contains(&latitude) && (*min_longitude..=*max_longitude).contains(&longitude))
+        {
+            Some(AlertSource::Nws)
+        } else {
+            None
+        }
+    }
+    pub fn url(&self, latitude: f64, longitude: f64) -> String {
+        match self {
+            AlertSource::Nws => format!("https://api.weather.gov/alerts/active?point={:.4},{:.4}", latitude, longitude),
+            AlertSource::MeteoAlarm(feed) => format!("https://feeds.meteoalarm.org/api/v1/warnings/feeds-{}", feed),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct NwsResponse {
+    #[serde(default)]
+    features: Vec<NwsFeature>,
+}
+
+#[derive(Deserialize)]
+struct NwsFeature {
+    properties: NwsProperties,
+}
+
+#[derive(Deserialize)]
+struct NwsProperties {
+    event: String,
+    #[serde(default)]
+    severity: String,
+}
+
+#[derive(Deserialize)]
+struct MeteoAlarmResponse {
+    #[serde(default)]
+    warnings: Vec<MeteoAlarmWarning>,
+}
+
+#[derive(Deserialize)]
+struct MeteoAlarmWarning {
+    alert: MeteoAlarmAlert,
+}
+
+#[derive(Deserialize)]
+struct MeteoAlarmAlert {
+    #[serde(default)]
+    info: Vec<MeteoAlarmInfo>, // the same warning in different languages
+}
+
+#[derive(Deserialize)]
+struct MeteoAlarmInfo {
+    #[serde(default)]
+    language: String,
+    event: String,
+    #[serde(default)]
+    severity: String,
+    #[serde(default)]
+    area: Vec<MeteoAlarmArea>,
+}
+
+#[derive(Deserialize)]
+struct MeteoAlarmArea {
+    #[serde(default, rename = "areaDesc")]
+    area_desc: String,
+    #[serde(default)]
+    polygon: Vec<String>,
+}
+
+pub fn parse_nws_alerts(body: Vec<u8>) -> Result<Vec<Alert>, WeatherPalError> {
+    // the API already only returns the alerts in effect at the requested point
+    let response: NwsResponse = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Alerts, e.to_string()))?;
+    Ok(response
+        .features
+        .into_iter()
+        .map(|feature| Alert { event: feature.properties.event, level: AlertLevel::from_cap(&feature.properties.severity) })
+        .collect())
+}
+
+pub fn parse_meteoalarm_alerts(body: Vec<u8>, latitude: f64, longitude: f64, place: &str) -> Result<Vec<Alert>, WeatherPalError> {
+    // the feed has the warnings of the whole country, only those for an area containing the location are kept,
+    // or named after it when the area has no outline
+    let response: MeteoAlarmResponse = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Alerts, e.to_string()))?;
+    Ok(response
+        .warnings
+        .into_iter()
+        .filter_map(|warning| {
+            let info = warning.alert.info.iter().find(|info| info.language.starts_with("en")).or_else(|| warning.alert.info.first())?;
+            let applies = info.area.iter().any(|area| {
+                if area.polygon.is_empty() {
+                    !place.is_empty() && area.area_desc.to_lowercase().contains(&place.to_lowercase())
+                } else {
+                    area.polygon.iter().any(|polygon| polygon_contains(polygon, latitude, longitude))
+                }
+            });
+            applies.then(|| Alert { event: info.event.clone(), level: AlertLevel::from_cap(&info.severity) })
+        })
+        .collect())
+}
+
+fn polygon_contains(polygon: &str, latitude: f64, longitude: f64) -> bool {
+    // eg. "48.1,16.2 48.3,16.2 48.3,16.5 48.1,16.2", CAP outlines are latitude first
+    let points: Vec<(f64, f64)> = polygon
+        .split_whitespace()
+        .filter_map(|point| point.split_once(','))
+        .filter_map(|(lat, lon)| Some((lat.parse().ok()?, lon.parse().ok()?)))
+        .collect();
+    let mut inside = false;
+    for (i, (lat_a, lon_a)) in points.iter().enumerate() {
+        let (lat_b, lon_b) = points[(i + points.len() - 1) % points.len()];
+        if (*lat_a > latitude) != (lat_b > latitude) && longitude < (lon_b - lon_a) * (latitude - lat_a) / (lat_b - lat_a) + lon_a {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+// the worker only gets the response body, so the location to look for is part of the request id
+pub fn meteoalarm_request_id(latitude: f64, longitude: f64, place: &str) -> String {
+    format!("{}{},{},{}", METEOALARM_REQUEST_PREFIX, latitude, longitude, place)
+}
+
+pub fn parse_meteoalarm_request_id(request_id: &str) -> Option<(f64, f64, &str)> {
+    let mut parts = request_id.strip_prefix(METEOALARM_REQUEST_PREFIX)?.splitn(3, ',');
+    let latitude = parts.next()?.parse().ok()?;
+    let longitude = parts.next()?.parse().ok()?;
+    Some((latitude, longitude, parts.next().unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_weather_service_of_the_location() {
+        assert_eq!(AlertSource::for_location(48.2, 16.37, "Vienna, Austria"), Some(AlertSource::MeteoAlarm("austria")));
+        assert_eq!(AlertSource::for_location(29.76, -95.36, "Houston, United States"), Some(AlertSource::Nws));
+        assert_eq!(AlertSource::for_location(40.71, -74.01, "40.71, -74.01"), Some(AlertSource::Nws));
+        assert_eq!(AlertSource::for_location(35.68, 139.69, "Tokyo, Japan"), None);
+    }
+
+    #[test]
+    fn parses_nws_alerts() {
+        let body = br#"{"features":[{"properties":{"event":"Flood Warning","severity":"Severe"}},{"properties":{"event":"Heat Advisory","severity":"Moderate"}}]}"#;
+        assert_eq!(
+            parse_nws_alerts(body.to_vec()),
+            Ok(vec![
+                Alert { event: "Flood Warning".to_owned(), level: AlertLevel::Severe },
+                Alert { event: "Heat Advisory".to_owned(), level: AlertLevel::Moderate },
+            ])
+        );
+        assert!(matches!(parse_nws_alerts(b"<html>".to_vec()), Err(WeatherPalError::Parse(RequestKind::Alerts, _))));
+    }
+
+    #[test]
+    fn keeps_meteoalarm_warnings_for_the_location() {
+        let body = br#"{"warnings":[
+            {"alert":{"info":[{"language":"de-DE","event":"Sturm","severity":"Severe","area":[{"areaDesc":"Wien","polygon":["48.1,16.2 48.4,16.2 48.4,16.6 48.1,16.6 48.1,16.2"]}]},
+                              {"language":"en-GB","event":"Storm","severity":"Severe","area":[{"areaDesc":"Wien","polygon":["48.1,16.2 48.4,16.2 48.4,16.6 48.1,16.6 48.1,16.2"]}]}]}},
+            {"alert":{"info":[{"language":"en-GB","event":"Snow","severity":"Moderate","area":[{"areaDesc":"Tirol","polygon":["47.0,10.5 47.5,10.5 47.5,12.0 47.0,12.0 47.0,10.5"]}]}]}},
+            {"alert":{"info":[{"language":"en-GB","event":"Heat","severity":"Minor","area":[{"areaDesc":"Vienna and surroundings"}]}]}}
+        ]}"#;
+        assert_eq!(
+            parse_meteoalarm_alerts(body.to_vec(), 48.2, 16.37, "Vienna"),
+            Ok(vec![
+                Alert { event: "Storm".to_owned(), level: AlertLevel::Severe },
+                Alert { event: "Heat".to_owned(), level: AlertLevel::Minor },
+            ])
+        );
+    }
+
+    #[test]
+    fn carries_the_location_in_the_request_id() {
+        let request_id = meteoalarm_request_id(48.2, 16.37, "Vienna");
+        assert_eq!(parse_meteoalarm_request_id(&request_id), Some((48.2, 16.37, "Vienna")));
+        assert_eq!(parse_meteoalarm_request_id("favorite:1"), None);
+    }
+}
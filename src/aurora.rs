@@ -0,0 +1,144 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{RequestKind, WeatherPalError};
+
+pub const AURORA_REQUEST_ID: &str = "aurora";
+pub const KP_FORECAST_URL: &str = "https://services.swpc.noaa.gov/products/noaa-planetary-k-index-forecast.json";
+// where the north pole of the earth's magnetic field is, the aurora circles around it
+const GEOMAGNETIC_POLE: (f64, f64) = (80.7, -72.7);
+// the aurora can be seen overhead from about this geomagnetic latitude when the Kp index is 0,
+// and about 2° closer to the equator for each step of the index
+const QUIET_AURORA_LATITUDE: f64 = 66.5;
+const AURORA_LATITUDE_PER_KP: f64 = 2.0;
+// below this, even the strongest storms hardly ever show, so the forecast isn't requested
+const MIN_AURORA_LATITUDE: f64 = 45.0;
+const KP_INTERVAL_SECONDS: i64 = 3 * 60 * 60;
+
+// the planetary K index of a three hour interval, from 0 (quiet) to 9 (extreme geomagnetic storm)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KpIndex {
+    pub start: i64, // unix timestamp
+    pub kp: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuroraChance {
+    Unlikely,
+    Low, // low on the horizon, towards the pole
+    Moderate,
+    High,
+}
+
+impl AuroraChance {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuroraChance::Unlikely => "unlikely",
+            AuroraChance::Low => "low",
+            AuroraChance::Moderate => "moderate",
+            AuroraChance::High => "high",
+        }
+    }
+}
+
+// a table with a header row, eg. [["time_tag","kp","observed","noaa_scale"],["2023-11-19 00:00:00","2.33","observed",null],...]
+pub fn parse_kp_forecast(body: Vec<u8>) -> Result<Vec<KpIndex>, WeatherPalError> {
+    let rows: Vec<Vec<Value>> = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Aurora, e.to_string()))?;
+    let Some((header, rows)) = rows.split_first() else {
+        return Ok(vec![]);
+    };
+    let column = |name: &str| header.iter().position(|h| h.as_str() == Some(name)).ok_or_else(|| WeatherPalError::Parse(RequestKind::Aurora, format!("no {} column", name)));
+    let (time_column, kp_column) = (column("time_tag")?, column("kp")?);
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let start = NaiveDateTime::parse_from_str(row.get(time_column)?.as_str()?, "%Y-%m-%d %H:%M:%S").ok()?.and_utc().timestamp();
+            let kp = match row.get(kp_column)? {
+                Value::String(kp) => kp.parse().ok()?,
+                kp => kp.as_f64()?,
+            };
+            Some(KpIndex { start, kp })
+        })
+        .collect())
+}
+
+// the latitude on the earth's magnetic field, with a tilted dipole, which is close enough for the aurora
+pub fn geomagnetic_latitude(latitude: f64, longitude: f64) -> f64 {
+    let (pole_latitude, pole_longitude) = (GEOMAGNETIC_POLE.0.to_radians(), GEOMAGNETIC_POLE.1.to_radians());
+    let (latitude, longitude) = (latitude.to_radians(), longitude.to_radians());
+    (latitude.sin() * pole_latitude.sin() + latitude.cos() * pole_latitude.cos() * (longitude - pole_longitude).cos()).asin().to_degrees()
+}
+
+// the aurora australis is just as far from the southern geomagnetic pole
+pub fn is_aurora_latitude(latitude: f64, longitude: f64) -> bool {
+    geomagnetic_latitude(latitude, longitude).abs() >= MIN_AURORA_LATITUDE
+}
+
+// the strongest activity expected in the next day
+pub fn max_kp(kp_forecast: &[KpIndex], now: DateTime<Utc>) -> Option<f64> {
+    let now = now.timestamp();
+    kp_forecast
+        .iter()
+        .filter(|kp_index| kp_index.start + KP_INTERVAL_SECONDS > now && kp_index.start < now + 24 * 60 * 60)
+        .map(|kp_index| kp_index.kp)
+        .fold(None, |max, kp| Some(max.map_or(kp, |max: f64| max.max(kp))))
+}
+
+// how far the aurora reaches towards the equator for the Kp index, and whether the clouds let it through
+pub fn aurora_chance(latitude: f64, longitude: f64, kp: f64, cloud_cover: f64) -> AuroraChance {
+    let margin = geomagnetic_latitude(latitude, longitude).abs() - (QUIET_AURORA_LATITUDE - AURORA_LATITUDE_PER_KP * kp);
+    let in_view: usize = if margin >= 2.0 {
+        3
+    } else if margin >= 0.0 {
+        2
+    } else if margin >= -3.0 {
+        1
+    } else {
+        0
+    };
+    let clouds = if cloud_cover >= 80.0 {
+        3
+    } else if cloud_cover >= 40.0 {
+        1
+    } else {
+        0
+    };
+    match in_view.saturating_sub(clouds) {
+        0 => AuroraChance::Unlikely,
+        1 => AuroraChance::Low,
+        2 => AuroraChance::Moderate,
+        _ => AuroraChance::High,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_kp_forecast() {
+        let body = br#"[["time_tag","kp","observed","noaa_scale"],["2023-11-19 00:00:00","2.33","observed",null],["2023-11-19 03:00:00","5.67","predicted","G2"],["2023-11-19 06:00:00",null,"predicted",null]]"#;
+        let kp_forecast = parse_kp_forecast(body.to_vec()).unwrap();
+        assert_eq!(kp_forecast, vec![KpIndex { start: 1700352000, kp: 2.33 }, KpIndex { start: 1700362800, kp: 5.67 }]);
+        assert_eq!(parse_kp_forecast(b"[]".to_vec()), Ok(vec![]));
+        assert!(parse_kp_forecast(b"[[\"time\"]]".to_vec()).is_err());
+        let at = |time: &str| NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").unwrap().and_utc();
+        assert_eq!(max_kp(&kp_forecast, at("2023-11-19T02:00")), Some(5.67));
+        assert_eq!(max_kp(&kp_forecast, at("2023-11-19T06:00")), None);
+    }
+
+    #[test]
+    fn tells_how_likely_the_aurora_is() {
+        // tromsø is under the aurora oval, vienna only sees the strongest storms and singapore never
+        assert!((geomagnetic_latitude(69.65, 18.96) - 67.0).abs() < 1.5);
+        assert!(is_aurora_latitude(69.65, 18.96));
+        assert!(is_aurora_latitude(-42.9, 147.3)); // hobart
+        assert!(!is_aurora_latitude(1.35, 103.8));
+        assert_eq!(aurora_chance(69.65, 18.96, 2.0, 10.0), AuroraChance::High);
+        assert_eq!(aurora_chance(69.65, 18.96, 2.0, 50.0), AuroraChance::Moderate);
+        assert_eq!(aurora_chance(69.65, 18.96, 2.0, 90.0), AuroraChance::Unlikely);
+        assert_eq!(aurora_chance(48.2, 16.37, 2.0, 0.0), AuroraChance::Unlikely);
+        assert_eq!(aurora_chance(48.2, 16.37, 8.0, 0.0), AuroraChance::Low);
+    }
+}
@@ -0,0 +1,274 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{RequestKind, WeatherPalError};
+use crate::format::colored_text;
+use crate::i18n::translate;
+use crate::render::{print_centered_lines, Renderer};
+use crate::state::State;
+
+// the latest observation at an airport, as decoded by aviationweather.gov
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metar {
+    pub station: String, // the ICAO code
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub raw: String,
+    pub temperature: Option<f64>, // celsius
+    pub dew_point: Option<f64>,
+    pub wind_direction: Option<usize>, // degrees, none when variable
+    pub wind_speed: Option<f64>, // knots
+    pub wind_gusts: Option<f64>,
+    pub visibility: Option<String>, // statute miles, eg. "10+"
+    pub altimeter: Option<f64>, // hPa
+    pub weather: Option<String>, // eg. "-RA BR"
+    pub clouds: Vec<(String, Option<usize>)>, // cover and base in feet, eg. ("BKN", 4000)
+}
+
+#[derive(Deserialize)]
+struct MetarResponse {
+    #[serde(rename = "icaoId")]
+    icao_id: String,
+    #[serde(default)]
+    name: String,
+    lat: f64,
+    lon: f64,
+    #[serde(default, rename = "rawOb")]
+    raw_ob: String,
+    temp: Option<f64>,
+    dewp: Option<f64>,
+    wdir: Option<Value>, // a number, or "VRB"
+    wspd: Option<f64>,
+    wgst: Option<f64>,
+    visib: Option<Value>, // a number, or eg. "10+"
+    altim: Option<f64>,
+    #[serde(rename = "wxString")]
+    wx_string: Option<String>,
+    #[serde(default)]
+    clouds: Vec<CloudResponse>,
+}
+
+#[derive(Deserialize)]
+struct CloudResponse {
+    cover: String,
+    base: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct TafResponse {
+    #[serde(rename = "rawTAF")]
+    raw_taf: String,
+}
+
+// eg. "LOWW" or "KJFK", as typed, so "Rome" is still looked up as a place
+pub fn is_airport_code(query: &str) -> bool {
+    query.len() == 4 && query.chars().all(|c| c.is_ascii_uppercase())
+}
+
+// an empty list means there is no such station, the location is then looked up as a place instead
+pub fn parse_metar(body: Vec<u8>) -> Result<Option<Metar>, WeatherPalError> {
+    let responses: Vec<MetarResponse> = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Aviation, e.to_string()))?;
+    Ok(responses.into_iter().next().map(|response| Metar {
+        station: response.icao_id,
+        // eg. "Vienna Schwechat Arpt, 09, AT"
+        name: response.name.split(',').next().unwrap_or_default().trim().to_owned(),
+        latitude: response.lat,
+        longitude: response.lon,
+        raw: response.raw_ob,
+        temperature: response.temp,
+        dew_point: response.dewp,
+        wind_direction: response.wdir.and_then(|d| d.as_u64()).map(|d| d as usize),
+        wind_speed: response.wspd,
+        wind_gusts: response.wgst,
+        visibility: response.visib.map(|v| v.as_str().map(|v| v.to_owned()).unwrap_or_else(|| v.to_string())),
+        altimeter: response.altim,
+        weather: response.wx_string.filter(|w| !w.is_empty()),
+        clouds: response.clouds.into_iter().map(|cloud| (cloud.cover, cloud.base)).collect(),
+    }))
+}
+
+pub fn parse_taf(body: Vec<u8>) -> Result<Option<String>, WeatherPalError> {
+    let responses: Vec<TafResponse> = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Aviation, e.to_string()))?;
+    Ok(responses.into_iter().next().map(|response| response.raw_taf))
+}
+
+// a line for each change group, the way TAFs are usually printed
+pub fn taf_lines(taf: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for word in taf.split_whitespace() {
+        let starts_group = ["FM", "BECMG", "TEMPO", "PROB30", "PROB40"].iter().any(|group| word.starts_with(group));
+        match lines.last_mut() {
+            Some(line) if !starts_group || line.starts_with("PROB") && word == "TEMPO" => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_owned()),
+        }
+    }
+    lines
+}
+
+pub fn cloud_cover_name(cover: &str) -> &str {
+    match cover {
+        "FEW" => "few",
+        "SCT" => "scattered",
+        "BKN" => "broken",
+        "OVC" => "overcast",
+        "CLR" | "SKC" | "CAVOK" => "clear",
+        "VV" => "vertical visibility",
+        other => other,
+    }
+}
+
+impl State {
+    pub(crate) fn render_aviation<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize) {
+        // the latest report of the airport as it was sent and decoded, then the terminal forecast, a line for each change group
+        let metar = match &self.metar {
+            Some(metar) => metar,
+            None => return,
+        };
+        let label = |text: &'static str| colored_text(translate(self.language, text).to_owned(), Some(self.palette.hour()));
+        let temperature = |t: f64| format!("{}{}", self.locale.format_temperature(t), self.locale.temperature_symbol());
+        let mut lines = vec![
+            vec![colored_text(metar.station.clone(), Some(self.palette.highlight())), colored_text(metar.name.clone(), Some(self.palette.highlight()))],
+            vec![label("METAR"), colored_text(metar.raw.clone(), None)],
+        ];
+        // aviation units, knots, feet and statute miles, whatever the locale
+        let wind = match (metar.wind_direction, metar.wind_speed) {
+            (_, Some(0.0)) => Some(translate(self.language, "calm").to_owned()),
+            (Some(direction), Some(speed)) => Some(format!("{:03}° {}kt", direction, speed)),
+            (None, Some(speed)) => Some(format!("{} {}kt", translate(self.language, "variable"), speed)),
+            _ => None,
+        };
+        if let Some(wind) = wind {
+            let gusts = metar.wind_gusts.map(|gusts| format!(", {} {}kt", translate(self.language, "gusts"), gusts)).unwrap_or_default();
+            lines.push(vec![label("Wind"), colored_text(format!("{}{}", wind, gusts), None)]);
+        }
+        if let Some(visibility) = &metar.visibility {
+            lines.push(vec![label("Visibility"), colored_text(format!("{} SM", visibility), None)]);
+        }
+        if let Some(t) = metar.temperature {
+            let dew_point = metar.dew_point.map(|d| format!(", {} {}", translate(self.language, "dew point"), temperature(d))).unwrap_or_default();
+            lines.push(vec![label("Temperature"), colored_text(format!("{}{}", temperature(t), dew_point), None)]);
+        }
+        if let Some(altimeter) = metar.altimeter {
+            lines.push(vec![label("QNH"), colored_text(format!("{:.0} hPa", altimeter), None)]);
+        }
+        if !metar.clouds.is_empty() {
+            let clouds: Vec<String> = metar
+                .clouds
+                .iter()
+                .map(|(cover, base)| match base {
+                    Some(base) => format!("{} {}ft", cloud_cover_name(cover), base),
+                    None => cloud_cover_name(cover).to_owned(),
+                })
+                .collect();
+            lines.push(vec![label("Clouds"), colored_text(clouds.join(", "), None)]);
+        }
+        if let Some(weather) = &metar.weather {
+            lines.push(vec![label("Weather"), colored_text(weather.clone(), Some(self.palette.precipitation()))]);
+        }
+        if let Some(taf) = &self.taf {
+            for (i, line) in taf_lines(taf).into_iter().enumerate() {
+                let taf_label = if i == 0 { label("TAF") } else { colored_text(" ".to_owned(), None) };
+                lines.push(vec![taf_label, colored_text(line, None)]);
+            }
+        }
+        print_centered_lines(renderer, lines, rows, cols);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::tests::{render_snapshot, state_with_forecast};
+    use crate::state::View;
+
+    #[test]
+    fn recognizes_airport_codes() {
+        assert!(is_airport_code("LOWW"));
+        assert!(is_airport_code("KJFK"));
+        assert!(!is_airport_code("Rome"));
+        assert!(!is_airport_code("JFK"));
+        assert!(!is_airport_code("LOWW2"));
+    }
+
+    #[test]
+    fn parses_a_metar() {
+        let body = br#"[{"icaoId":"LOWW","name":"Vienna Schwechat Arpt, 09, AT","lat":48.11,"lon":16.57,
+            "rawOb":"METAR LOWW 191020Z 30012G22KT 9999 -RA FEW025 BKN040 05/02 Q1013 NOSIG",
+            "temp":5,"dewp":2,"wdir":300,"wspd":12,"wgst":22,"visib":"6+","altim":1013,"wxString":"-RA",
+            "clouds":[{"cover":"FEW","base":2500},{"cover":"BKN","base":4000}]}]"#;
+        let metar = parse_metar(body.to_vec()).unwrap().unwrap();
+        assert_eq!(metar.station, "LOWW");
+        assert_eq!(metar.name, "Vienna Schwechat Arpt");
+        assert_eq!(metar.wind_direction, Some(300));
+        assert_eq!(metar.wind_gusts, Some(22.0));
+        assert_eq!(metar.visibility.as_deref(), Some("6+"));
+        assert_eq!(metar.clouds, vec![("FEW".to_owned(), Some(2500)), ("BKN".to_owned(), Some(4000))]);
+        let variable = br#"[{"icaoId":"KJFK","lat":40.6,"lon":-73.8,"wdir":"VRB","wspd":3,"visib":10}]"#;
+        let metar = parse_metar(variable.to_vec()).unwrap().unwrap();
+        assert_eq!(metar.wind_direction, None);
+        assert_eq!(metar.visibility.as_deref(), Some("10"));
+        assert_eq!(parse_metar(b"[]".to_vec()), Ok(None));
+    }
+
+    #[test]
+    fn splits_a_taf_into_change_groups() {
+        let taf = "TAF LOWW 191100Z 1912/2018 30012KT 9999 FEW030 TEMPO 1912/1916 -RA BKN025 PROB30 TEMPO 1918/1922 SHRA FM200600 27008KT CAVOK";
+        assert_eq!(
+            taf_lines(taf),
+            vec![
+                "TAF LOWW 191100Z 1912/2018 30012KT 9999 FEW030",
+                "TEMPO 1912/1916 -RA BKN025",
+                "PROB30 TEMPO 1918/1922 SHRA",
+                "FM200600 27008KT CAVOK",
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_the_metar_and_taf_of_an_airport() {
+        let state = State {
+            view: View::Aviation,
+            metar: Some(Metar {
+                station: "LOWW".to_owned(),
+                name: "Vienna Schwechat Arpt".to_owned(),
+                latitude: 48.11,
+                longitude: 16.57,
+                raw: "METAR LOWW 191020Z 30012G22KT 9999 -RA FEW025 BKN040 05/02 Q1013 NOSIG".to_owned(),
+                temperature: Some(5.0),
+                dew_point: Some(2.0),
+                wind_direction: Some(300),
+                wind_speed: Some(12.0),
+                wind_gusts: Some(22.0),
+                visibility: Some("6+".to_owned()),
+                altimeter: Some(1013.0),
+                weather: Some("-RA".to_owned()),
+                clouds: vec![("FEW".to_owned(), Some(2500)), ("BKN".to_owned(), Some(4000))],
+            }),
+            taf: Some("TAF LOWW 191100Z 1912/2018 30012KT 9999 FEW030 TEMPO 1912/1916 -RA BKN025 FM200600 27008KT CAVOK".to_owned()),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "  Hourly    Daily    Week    Chart  [ METAR ]",
+            "",
+            "",
+            "    LOWW        Vienna Schwechat Arpt",
+            "    METAR       METAR LOWW 191020Z 30012G22KT 9999 -RA FEW025 BKN040 05/02 Q1013 NOSIG",
+            "    Wind        300° 12kt, gusts 22kt",
+            "    Visibility  6+ SM",
+            "    Temperature 5°C, dew point 2°C",
+            "    QNH         1013 hPa",
+            "    Clouds      few 2500ft, broken 4000ft",
+            "    Weather     -RA",
+            "    TAF         TAF LOWW 191100Z 1912/2018 30012KT 9999 FEW030",
+            "                TEMPO 1912/1916 -RA BKN025",
+            "                FM200600 27008KT CAVOK",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 90, 10), expected.join("\n"));
+    }
+}
@@ -0,0 +1,40 @@
+pub const OPEN_COMMAND_ID: &str = "OPEN_COMMAND_ID";
+pub const DEFAULT_FORECAST_SITE: &str = "windy"; // with maps and radar
+// xdg-open on linux, open on macOS, with the url as $0
+const DEFAULT_OPEN_COMMAND: &[&str] = &["bash", "-c", "if command -v xdg-open >/dev/null; then xdg-open \"$0\"; else open \"$0\"; fi"];
+
+// the detailed forecast of a place on a website, one of the known ones or a url with {lat} and {lon} in it
+pub fn forecast_site_url(site: &str, latitude: f64, longitude: f64) -> Option<String> {
+    let template = match site.trim() {
+        "windy" => "https://www.windy.com/?{lat},{lon},10",
+        "open-meteo" => "https://open-meteo.com/en/docs?latitude={lat}&longitude={lon}",
+        "nws" => "https://forecast.weather.gov/MapClick.php?lat={lat}&lon={lon}",
+        template if template.contains("{lat}") && template.contains("{lon}") => template,
+        _ => return None,
+    };
+    Some(template.replace("{lat}", &format!("{:.3}", latitude)).replace("{lon}", &format!("{:.3}", longitude)))
+}
+
+// eg. "firefox --new-window", the url is added at the end
+pub fn open_command(command: Option<&str>, url: &str) -> Vec<String> {
+    let mut open_command: Vec<String> = match command.map(|c| c.split_whitespace().collect::<Vec<_>>()).filter(|c| !c.is_empty()) {
+        Some(command) => command.iter().map(|c| c.to_string()).collect(),
+        None => DEFAULT_OPEN_COMMAND.iter().map(|c| c.to_string()).collect(),
+    };
+    open_command.push(url.to_owned());
+    open_command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_the_forecast_site_url() {
+        assert_eq!(forecast_site_url("windy", 48.2082, 16.3738).as_deref(), Some("https://www.windy.com/?48.208,16.374,10"));
+        assert_eq!(forecast_site_url("https://example.com/{lat}/{lon}", 48.2, 16.37).as_deref(), Some("https://example.com/48.200/16.370"));
+        assert_eq!(forecast_site_url("https://example.com/", 48.2, 16.37), None);
+        assert_eq!(open_command(Some("firefox --new-window"), "https://example.com"), vec!["firefox", "--new-window", "https://example.com"]);
+        assert_eq!(open_command(Some(" "), "https://example.com").len(), 4);
+    }
+}
@@ -0,0 +1,159 @@
+use zellij_tile::prelude::*;
+
+const BAR_EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// a grid of colored cells that charts are drawn into before being printed line by line
+pub struct Canvas {
+    cells: Vec<Vec<(char, Option<usize>)>>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Canvas { cells: vec![vec![(' ', None); width]; height] }
+    }
+    pub fn width(&self) -> usize {
+        self.cells.first().map(|row| row.len()).unwrap_or(0)
+    }
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn set(&mut self, x: usize, y: usize, character: char, color: Option<usize>) {
+        if let Some(cell) = self.cells.get_mut(y).and_then(|row| row.get_mut(x)) {
+            *cell = (character, color);
+        }
+    }
+    pub fn write(&mut self, x: usize, y: usize, text: &str, color: Option<usize>) {
+        for (i, character) in text.chars().enumerate() {
+            self.set(x + i, y, character, color);
+        }
+    }
+    // value is between 0 and 1 of the canvas height, drawn from the bottom up in eighths of a cell
+    pub fn draw_bar(&mut self, x: usize, value: f64, color: Option<usize>) {
+        let height = self.height();
+        let eighths = (value.clamp(0.0, 1.0) * (height * 8) as f64).round() as usize;
+        for row in 0..height {
+            let filled = eighths.saturating_sub(row * 8).min(8);
+            if filled > 0 {
+                self.set(x, height - 1 - row, BAR_EIGHTHS[filled], color);
+            }
+        }
+    }
+    pub fn overlay(&mut self, braille: &BrailleCanvas) {
+        for (y, row) in braille.cells.iter().enumerate() {
+            for (x, (dots, color)) in row.iter().enumerate() {
+                if *dots != 0 {
+                    self.set(x, y, braille_character(*dots), *color);
+                }
+            }
+        }
+    }
+    pub fn lines(&self) -> Vec<Text> {
+        self.cells
+            .iter()
+            .map(|row| {
+                let line: String = row.iter().map(|(character, _)| character).collect();
+                row.iter()
+                    .enumerate()
+                    .fold(Text::new(line), |text, (i, (_, color))| match color {
+                        Some(color) => text.color_range(*color, i..=i),
+                        None => text,
+                    })
+            })
+            .collect()
+    }
+}
+
+// a bar that fits in a single cell, for values between 0 and 1
+pub fn bar_character(value: f64) -> char {
+    BAR_EIGHTHS[(value.clamp(0.0, 1.0) * 8.0).round() as usize]
+}
+
+// each cell holds 2x4 dots, giving lines a much finer resolution than whole characters
+pub struct BrailleCanvas {
+    cells: Vec<Vec<(u8, Option<usize>)>>,
+}
+
+impl BrailleCanvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        BrailleCanvas { cells: vec![vec![(0, None); width]; height] }
+    }
+    pub fn dot_width(&self) -> usize {
+        self.cells.first().map(|row| row.len() * 2).unwrap_or(0)
+    }
+    pub fn dot_height(&self) -> usize {
+        self.cells.len() * 4
+    }
+    pub fn set_dot(&mut self, x: usize, y: usize, color: Option<usize>) {
+        if let Some(cell) = self.cells.get_mut(y / 4).and_then(|row| row.get_mut(x / 2)) {
+            cell.0 |= BRAILLE_DOTS[y % 4][x % 2];
+            cell.1 = color;
+        }
+    }
+    pub fn draw_line(&mut self, from: (usize, usize), to: (usize, usize), color: Option<usize>) {
+        // bresenham
+        let (mut x, mut y) = (from.0 as isize, from.1 as isize);
+        let (to_x, to_y) = (to.0 as isize, to.1 as isize);
+        let delta_x = (to_x - x).abs();
+        let delta_y = -(to_y - y).abs();
+        let step_x = if x < to_x { 1 } else { -1 };
+        let step_y = if y < to_y { 1 } else { -1 };
+        let mut error = delta_x + delta_y;
+        loop {
+            self.set_dot(x as usize, y as usize, color);
+            if x == to_x && y == to_y {
+                break;
+            }
+            if error * 2 >= delta_y {
+                error += delta_y;
+                x += step_x;
+            }
+            if error * 2 <= delta_x {
+                error += delta_x;
+                y += step_y;
+            }
+        }
+    }
+}
+
+// bit of each dot by row and column, see https://en.wikipedia.org/wiki/Braille_Patterns
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+fn braille_character(dots: u8) -> char {
+    std::char::from_u32(0x2800 + dots as u32).unwrap_or(' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain_lines(canvas: &Canvas) -> Vec<String> {
+        canvas.cells.iter().map(|row| row.iter().map(|(character, _)| character).collect::<String>()).collect()
+    }
+
+    #[test]
+    fn draws_bars_in_eighths_of_a_cell() {
+        let mut canvas = Canvas::new(3, 2);
+        canvas.draw_bar(0, 1.0, None);
+        canvas.draw_bar(1, 0.75, None);
+        canvas.draw_bar(2, 0.0625, None);
+        assert_eq!(plain_lines(&canvas), vec!["█▄ ", "██▁"]);
+    }
+
+    #[test]
+    fn picks_single_cell_bars() {
+        assert_eq!(bar_character(0.0), ' ');
+        assert_eq!(bar_character(0.5), '▄');
+        assert_eq!(bar_character(3.0), '█');
+    }
+
+    #[test]
+    fn draws_braille_lines_onto_the_canvas() {
+        let mut braille = BrailleCanvas::new(2, 1);
+        braille.draw_line((0, 3), (3, 0), Some(2));
+        let mut canvas = Canvas::new(3, 1);
+        canvas.draw_bar(2, 1.0, None);
+        canvas.overlay(&braille);
+        assert_eq!(plain_lines(&canvas), vec!["⡠⠊█"]);
+        assert_eq!(canvas.cells[0][0], ('⡠', Some(2)));
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::error::{RequestKind, WeatherPalError};
+
+pub const CLIMATE_REQUEST_ID: &str = "climate";
+const NORMAL_YEARS: i32 = 10; // the full years before this one that make up the normal
+
+#[derive(Deserialize)]
+struct ClimateResponse {
+    daily: ClimateDaily,
+}
+
+#[derive(Deserialize)]
+struct ClimateDaily {
+    time: Vec<String>,
+    temperature_2m_max: Vec<Option<f64>>,
+}
+
+// the daily highs of the last full years, from the archive of past weather
+pub fn climate_url(latitude: f64, longitude: f64, today: NaiveDate) -> String {
+    format!(
+        "https://archive-api.open-meteo.com/v1/archive?latitude={}&longitude={}&start_date={}-01-01&end_date={}-12-31&daily=temperature_2m_max&timezone=auto",
+        latitude,
+        longitude,
+        today.year() - NORMAL_YEARS,
+        today.year() - 1
+    )
+}
+
+// the average high of each day of the year, eg. "11-19": 8.4
+pub fn parse_climate_normals(body: Vec<u8>) -> Result<BTreeMap<String, f64>, WeatherPalError> {
+    let response: ClimateResponse = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Climate, e.to_string()))?;
+    let mut highs: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for (date, high) in response.daily.time.iter().zip(response.daily.temperature_2m_max) {
+        if let (Some(day), Some(high)) = (date.get(5..10), high) {
+            highs.entry(day.to_owned()).or_default().push(high);
+        }
+    }
+    Ok(highs.into_iter().map(|(day, highs)| (day, highs.iter().sum::<f64>() / highs.len() as f64)).collect())
+}
+
+// how much warmer than normal the high of a date ("2023-11-19") is, none without a normal for the day
+pub fn anomaly(normals: &BTreeMap<String, f64>, date: &str, high: f64) -> Option<f64> {
+    normals.get(date.get(5..10)?).map(|normal| high - normal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_the_highs_of_each_day() {
+        let body = r#"{"daily":{"time":["2021-11-19","2021-11-20","2022-11-19","2022-11-20"],"temperature_2m_max":[7.0,null,9.0,4.5]}}"#;
+        let normals = parse_climate_normals(body.as_bytes().to_vec()).unwrap();
+        assert_eq!(normals, BTreeMap::from([("11-19".to_owned(), 8.0), ("11-20".to_owned(), 4.5)]));
+        assert_eq!(anomaly(&normals, "2023-11-19", 13.0), Some(5.0));
+        assert_eq!(anomaly(&normals, "2023-11-21", 13.0), None);
+        assert!(climate_url(48.2, 16.37, NaiveDate::from_ymd_opt(2023, 11, 19).unwrap()).contains("start_date=2013-01-01&end_date=2022-12-31"));
+    }
+}
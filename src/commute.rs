@@ -0,0 +1,57 @@
+// a daily time window to summarize, eg. the way to work, in minutes since midnight
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CommuteWindow {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl CommuteWindow {
+    pub fn contains_hour(&self, hour_of_day: usize) -> bool {
+        // the hour counts if any part of it falls within the window
+        hour_of_day * 60 < self.end && self.start < (hour_of_day + 1) * 60
+    }
+}
+
+// eg. "08:00-09:00,17:30-18:30", the first window is the morning one and the second the evening one
+pub fn parse_commute(windows: &str) -> Vec<CommuteWindow> {
+    windows
+        .split(',')
+        .filter_map(|window| {
+            let (start, end) = window.split_once('-')?;
+            let (start, end) = (parse_time(start)?, parse_time(end)?);
+            if start < end {
+                Some(CommuteWindow { start, end })
+            } else {
+                None
+            }
+        })
+        .take(2)
+        .collect()
+}
+
+fn parse_time(time: &str) -> Option<usize> { // minutes since midnight
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes) = (hours.parse::<usize>().ok()?, minutes.parse::<usize>().ok()?);
+    if hours * 60 + minutes <= 24 * 60 && minutes < 60 {
+        Some(hours * 60 + minutes)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commute_windows() {
+        let windows = parse_commute("08:00-09:00, 17:30-18:30");
+        assert_eq!(windows, vec![CommuteWindow { start: 480, end: 540 }, CommuteWindow { start: 1050, end: 1110 }]);
+        assert!(windows[0].contains_hour(8));
+        assert!(!windows[0].contains_hour(9));
+        assert!(windows[1].contains_hour(17));
+        assert!(windows[1].contains_hour(18));
+        assert!(parse_commute("09:00-08:00,25:00-26:00,8-9").is_empty());
+        assert_eq!(parse_commute("07:00-08:00,12:00-13:00,17:00-18:00").len(), 2);
+    }
+}
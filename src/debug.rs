@@ -0,0 +1,149 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+const DEBUG_LOG_PATH: &str = "/data/weather-pal.log";
+const MAX_RECENT_REQUESTS: usize = 10;
+
+pub struct RequestRecord {
+    pub endpoint: String,
+    pub url: String,
+    pub started_at: Instant,
+    pub status_code: Option<u16>, // None while in flight
+    pub duration: Option<Duration>,
+    pub bytes: Option<usize>,
+}
+
+// the last response to a request, as it came from the API
+pub struct RawResponse {
+    pub endpoint: String,
+    pub status_code: u16,
+    pub bytes: usize,
+    pub lines: Vec<String>, // pretty printed when it is JSON, as it is otherwise (eg. an error page)
+}
+
+impl RawResponse {
+    pub fn new(endpoint: &str, status_code: u16, body: &[u8]) -> Self {
+        let text = serde_json::from_slice::<Value>(body)
+            .ok()
+            .and_then(|json| serde_json::to_string_pretty(&json).ok())
+            .unwrap_or_else(|| String::from_utf8_lossy(body).into_owned());
+        RawResponse {
+            endpoint: endpoint.to_owned(),
+            status_code,
+            bytes: body.len(),
+            lines: text.lines().map(|line| line.to_owned()).collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RequestLog {
+    pub enabled: bool,
+    pub(crate) recent: VecDeque<RequestRecord>,
+    pub(crate) responses: BTreeMap<String, RawResponse>, // the last one of each request
+}
+
+impl RequestLog {
+    pub fn started(&mut self, endpoint: &str, url: &str) {
+        self.recent.push_back(RequestRecord {
+            endpoint: endpoint.to_owned(),
+            url: url.to_owned(),
+            started_at: Instant::now(),
+            status_code: None,
+            duration: None,
+            bytes: None,
+        });
+        if self.recent.len() > MAX_RECENT_REQUESTS {
+            self.recent.pop_front();
+        }
+        self.log(&format!("{} request started: {}", endpoint, url));
+    }
+    pub fn finished(&mut self, endpoint: &str, status_code: u16, bytes: usize) {
+        let pending_request = self
+            .recent
+            .iter_mut()
+            .rev()
+            .find(|r| r.endpoint == endpoint && r.status_code.is_none());
+        match pending_request {
+            Some(request) => {
+                let duration = request.started_at.elapsed();
+                request.status_code = Some(status_code);
+                request.duration = Some(duration);
+                request.bytes = Some(bytes);
+                let message = format!(
+                    "{} request finished with status {} in {}ms ({} bytes): {}",
+                    endpoint,
+                    status_code,
+                    duration.as_millis(),
+                    bytes,
+                    request.url
+                );
+                self.log(&message);
+            }
+            None => self.log(&format!("{} request finished with status {} ({} bytes)", endpoint, status_code, bytes)),
+        }
+    }
+    pub fn keep_response(&mut self, endpoint: &str, status_code: u16, body: &[u8]) {
+        self.responses.insert(endpoint.to_owned(), RawResponse::new(endpoint, status_code, body));
+    }
+    // the forecast first, as it's what is usually looked for, then the others by request
+    pub fn responses(&self) -> Vec<&RawResponse> {
+        let (forecast, others): (Vec<_>, Vec<_>) = self.responses.values().partition(|r| r.endpoint == "weather");
+        forecast.into_iter().chain(others).collect()
+    }
+    pub fn recent(&self) -> impl Iterator<Item = &RequestRecord> {
+        self.recent.iter()
+    }
+    pub fn log(&self, message: &str) {
+        if !self.enabled {
+            return;
+        }
+        if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(DEBUG_LOG_PATH) {
+            let _ = writeln!(file, "{} {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"), message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_responses_to_the_latest_pending_request() {
+        let mut request_log = RequestLog::default();
+        request_log.started("geocode", "https://geocoding-api.open-meteo.com/v1/search?name=Vienna");
+        request_log.started("weather", "https://api.open-meteo.com/v1/forecast");
+        request_log.finished("geocode", 200, 512);
+        let requests: Vec<_> = request_log.recent().collect();
+        assert_eq!(requests[0].status_code, Some(200));
+        assert_eq!(requests[0].bytes, Some(512));
+        assert!(requests[0].duration.is_some());
+        assert_eq!(requests[1].status_code, None);
+    }
+
+    #[test]
+    fn pretty_prints_the_last_response_of_each_request() {
+        let mut request_log = RequestLog::default();
+        request_log.keep_response("geocode", 200, br#"{"results":[{"name":"Vienna"}]}"#);
+        assert_eq!(request_log.responses()[0].lines, vec!["{", "  \"results\": [", "    {", "      \"name\": \"Vienna\"", "    }", "  ]", "}"]);
+        request_log.keep_response("weather", 502, b"Bad Gateway\nnginx");
+        request_log.keep_response("alerts", 200, b"[]");
+        let responses = request_log.responses();
+        assert_eq!(responses.iter().map(|r| r.endpoint.as_str()).collect::<Vec<_>>(), vec!["weather", "alerts", "geocode"]);
+        assert_eq!(responses[0].lines, vec!["Bad Gateway", "nginx"]);
+        assert_eq!(responses[0].bytes, 17);
+    }
+
+    #[test]
+    fn keeps_only_the_most_recent_requests() {
+        let mut request_log = RequestLog::default();
+        for i in 0..MAX_RECENT_REQUESTS + 2 {
+            request_log.started("weather", &i.to_string());
+        }
+        assert_eq!(request_log.recent().count(), MAX_RECENT_REQUESTS);
+        assert_eq!(request_log.recent().next().map(|r| r.url.as_str()), Some("2"));
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RequestKind {
+    Geocode,
+    Weather,
+    Elevation,
+    Alerts,
+    Aviation, // airport reports and forecasts
+    Aurora,
+    AirQuality,
+    Marine,
+    Map,
+    Team, // the current weather where colleagues are
+    Climate,
+}
+
+impl fmt::Display for RequestKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RequestKind::Geocode => write!(f, "geocode"),
+            RequestKind::Weather => write!(f, "weather"),
+            RequestKind::Elevation => write!(f, "elevation"),
+            RequestKind::Alerts => write!(f, "alerts"),
+            RequestKind::Aviation => write!(f, "airport report"),
+            RequestKind::Aurora => write!(f, "aurora"),
+            RequestKind::AirQuality => write!(f, "air quality"),
+            RequestKind::Marine => write!(f, "marine"),
+            RequestKind::Map => write!(f, "map"),
+            RequestKind::Team => write!(f, "team"),
+            RequestKind::Climate => write!(f, "climate"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error, Serialize, Deserialize)]
+pub enum WeatherPalError {
+    #[error("Failed to parse {0} response: {1}")]
+    Parse(RequestKind, String),
+    #[error("Failed {0} web request (status {1}): {2}")]
+    Http(RequestKind, u16, String), // the status code and an excerpt of the body
+    #[error("No matching location found")]
+    GeocodeEmpty,
+    #[error("Error fetching timezone: {0}")]
+    Command(String),
+}
+
+const MAX_EXCERPT_CHARS: usize = 80;
+
+// the part of an error response that tells what went wrong, eg. a 429 from the API or a block page in front of it
+pub fn body_excerpt(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+    let reason = serde_json::from_slice::<Value>(body).ok().and_then(|json| json.get("reason").and_then(|r| r.as_str()).map(|r| r.to_owned()));
+    let title = text.find("<title>").and_then(|start| text[start + 7..].split("</title>").next().map(|t| t.to_owned()));
+    let excerpt = reason.or(title).unwrap_or_else(|| text.lines().map(|line| line.trim()).find(|line| !line.is_empty()).unwrap_or_default().to_owned());
+    let excerpt = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+    if excerpt.chars().count() > MAX_EXCERPT_CHARS {
+        format!("{}…", excerpt.chars().take(MAX_EXCERPT_CHARS - 1).collect::<String>())
+    } else {
+        excerpt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excerpts_what_went_wrong() {
+        assert_eq!(body_excerpt(br#"{"error":true,"reason":"Too many concurrent requests"}"#), "Too many concurrent requests");
+        assert_eq!(body_excerpt(b"<!DOCTYPE html>\n<html><head><title>Attention Required! | Cloudflare</title></head>"), "Attention Required! | Cloudflare");
+        assert_eq!(body_excerpt(b"\n  Bad Gateway  \nnginx"), "Bad Gateway");
+        assert_eq!(body_excerpt(b""), "");
+        assert_eq!(body_excerpt("x".repeat(100).as_bytes()).chars().count(), MAX_EXCERPT_CHARS);
+    }
+}
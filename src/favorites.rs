@@ -0,0 +1,45 @@
+// forecasts of the favorites not on screen, only shared with the other panes until switching to them
+pub const FAVORITE_FORECAST_PREFIX: &str = "favorite_forecast:"; // followed by the index of the favorite
+
+// a location from the `locations` configuration, geocoded in the background so switching to it is instant
+#[derive(Clone, Debug, PartialEq)]
+pub struct Favorite {
+    pub query: String,
+    pub resolved: Option<(f64, f64, String)>, // lat, lon, location
+}
+
+// eg. "Berlin;Tel Aviv;52.2,21.0", coordinates don't need to be geocoded
+pub fn parse_favorites(locations: &str) -> Vec<Favorite> {
+    locations
+        .split(';')
+        .map(|location| location.trim())
+        .filter(|location| !location.is_empty())
+        .map(|location| Favorite {
+            query: location.to_owned(),
+            resolved: parse_coordinates(location).map(|(latitude, longitude)| (latitude, longitude, format!("{:.2}, {:.2}", latitude, longitude))),
+        })
+        .collect()
+}
+
+fn parse_coordinates(location: &str) -> Option<(f64, f64)> {
+    let (latitude, longitude) = location.split_once(',')?;
+    let latitude = latitude.trim().parse::<f64>().ok().filter(|l| (-90.0..=90.0).contains(l))?;
+    let longitude = longitude.trim().parse::<f64>().ok().filter(|l| (-180.0..=180.0).contains(l))?;
+    Some((latitude, longitude))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_and_coordinates() {
+        let favorites = parse_favorites("Berlin; Tel Aviv;52.2,21.0;;Paris, France;91,0");
+        let queries: Vec<&str> = favorites.iter().map(|f| f.query.as_str()).collect();
+        assert_eq!(queries, vec!["Berlin", "Tel Aviv", "52.2,21.0", "Paris, France", "91,0"]);
+        assert_eq!(favorites[0].resolved, None);
+        assert_eq!(favorites[2].resolved, Some((52.2, 21.0, "52.20, 21.00".to_owned())));
+        assert_eq!(favorites[3].resolved, None);
+        assert_eq!(favorites[4].resolved, None);
+    }
+}
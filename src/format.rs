@@ -0,0 +1,301 @@
+use zellij_tile::prelude::*;
+
+use crate::error::{RequestKind, WeatherPalError};
+use crate::i18n::{translate, Language};
+use crate::locale::Locale;
+use crate::palette::Palette;
+use crate::width::display_width;
+use crate::wmo::{Severity, WmoCode};
+
+// lower bounds in kph, roughly beaufort 3, 6 and 8
+const BREEZY_WIND: f64 = 12.0;
+const STRONG_WIND: f64 = 39.0;
+const GALE_WIND: f64 = 62.0;
+// the upper wind speed of each beaufort force in kph, anything faster is a hurricane
+const BEAUFORT_SCALE: &[f64] = &[1.0, 6.0, 12.0, 20.0, 29.0, 39.0, 50.0, 62.0, 75.0, 89.0, 103.0, 118.0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindBand {
+    Calm,
+    Breezy,
+    Strong,
+    Gale,
+}
+
+impl WindBand {
+    pub fn from_speed(wind_speed: f64) -> Self {
+        if wind_speed >= GALE_WIND {
+            WindBand::Gale
+        } else if wind_speed >= STRONG_WIND {
+            WindBand::Strong
+        } else if wind_speed >= BREEZY_WIND {
+            WindBand::Breezy
+        } else {
+            WindBand::Calm
+        }
+    }
+    pub fn severity(&self) -> Severity {
+        match self {
+            WindBand::Calm | WindBand::Breezy => Severity::None,
+            WindBand::Strong => Severity::Mild,
+            WindBand::Gale => Severity::Severe,
+        }
+    }
+}
+
+pub fn beaufort(wind_speed: f64) -> usize {
+    BEAUFORT_SCALE.iter().position(|upper| wind_speed < *upper).unwrap_or(BEAUFORT_SCALE.len())
+}
+
+// small craft advisories usually start at force 6, gales at 8
+pub fn beaufort_severity(force: usize) -> Severity {
+    match force {
+        0..=5 => Severity::None,
+        6..=7 => Severity::Mild,
+        _ => Severity::Severe,
+    }
+}
+
+pub fn temperature_gradient_color(temperature: f64, day_min: f64, day_max: f64, palette: Palette) -> usize {
+    let gradient = palette.temperature_gradient();
+    if day_max <= day_min {
+        return gradient[1.min(gradient.len() - 1)];
+    }
+    let position = ((temperature - day_min) / (day_max - day_min)).clamp(0.0, 1.0);
+    let index = (position * gradient.len() as f64) as usize;
+    gradient[index.min(gradient.len() - 1)]
+}
+
+pub fn hour_of_day(hour: usize) -> usize {
+    (hour + 1) % 24
+}
+
+pub fn time_of_day(minute: usize) -> String {
+    // on the same clock as the hourly table
+    format!("{:02}:{:02}", hour_of_day(minute / 60 % 24), minute % 60)
+}
+
+pub fn daylight_text(daylight_minutes: usize, previous_daylight_minutes: Option<usize>) -> String {
+    let change = previous_daylight_minutes.map(|previous| format!(" ({:+} min)", daylight_minutes as isize - previous as isize)).unwrap_or_default();
+    format!("☀ {}h {}m{}", daylight_minutes / 60, daylight_minutes % 60, change)
+}
+
+pub fn controls_text_with_keys_highlighted(controls_text: &str, color: usize) -> Text {
+    let mut text = Text::new(controls_text);
+    let mut key_start = None;
+    for (i, character) in controls_text.chars().enumerate() {
+        if character == '<' {
+            key_start = Some(i);
+        } else if let (Some(start), '>') = (key_start, character) {
+            text = text.color_range(color, start..=i);
+            key_start = None;
+        }
+    }
+    text
+}
+
+pub fn error_message(error: &WeatherPalError, language: Language) -> String {
+    match error {
+        WeatherPalError::Parse(kind, message) => {
+            let failed_parse = match kind {
+                RequestKind::Weather => translate(language, "Failed to parse data"),
+                RequestKind::Geocode => translate(language, "Failed to parse geocode"),
+                RequestKind::Elevation => translate(language, "Failed to parse elevation"),
+                RequestKind::Alerts => translate(language, "Failed to parse weather warnings"),
+                RequestKind::Aviation => translate(language, "Failed to parse airport report"),
+                RequestKind::Aurora => translate(language, "Failed to parse aurora forecast"),
+                RequestKind::AirQuality => translate(language, "Failed to parse air quality"),
+                RequestKind::Marine => translate(language, "Failed to parse marine forecast"),
+                RequestKind::Map => translate(language, "Failed to parse map"),
+                RequestKind::Team => translate(language, "Failed to parse team weather"),
+                RequestKind::Climate => translate(language, "Failed to parse climate normals"),
+            };
+            format!("{}: {}", failed_parse, message)
+        }
+        WeatherPalError::Http(kind, status_code, excerpt) => {
+            let failed_request = match kind {
+                RequestKind::Weather => translate(language, "Failed weather web request"),
+                RequestKind::Geocode => translate(language, "Failed geocode web request"),
+                RequestKind::Elevation => translate(language, "Failed elevation web request"),
+                RequestKind::Alerts => translate(language, "Failed weather warnings web request"),
+                RequestKind::Aviation => translate(language, "Failed airport report web request"),
+                RequestKind::Aurora => translate(language, "Failed aurora forecast web request"),
+                RequestKind::AirQuality => translate(language, "Failed air quality web request"),
+                RequestKind::Marine => translate(language, "Failed marine forecast web request"),
+                RequestKind::Map => translate(language, "Failed map web request"),
+                RequestKind::Team => translate(language, "Failed team weather web request"),
+                RequestKind::Climate => translate(language, "Failed climate normals web request"),
+            };
+            // eg. "Failed weather web request (429): Too many concurrent requests"
+            if excerpt.is_empty() {
+                format!("{} ({})", failed_request, status_code)
+            } else {
+                format!("{} ({}): {}", failed_request, status_code, excerpt)
+            }
+        }
+        WeatherPalError::GeocodeEmpty => translate(language, "No matching location found").to_owned(),
+        WeatherPalError::Command(message) => format!("{}: {}", translate(language, "Error fetching timezone"), message),
+    }
+}
+
+pub fn temperature_trend_arrow(previous_temperature: Option<f64>, temperature: f64) -> char {
+    match previous_temperature.map(|previous| temperature - previous) {
+        Some(change) if change >= 0.5 => '↑',
+        Some(change) if change <= -0.5 => '↓',
+        Some(_) => '→',
+        None => ' ',
+    }
+}
+
+pub fn wind_direction_arrow(degrees: usize) -> char {
+    if degrees < 45 || degrees == 360 {
+        '↓' // north
+    } else if degrees < 90 {
+        '↙' // north-east
+    } else if degrees < 135 {
+        '←' // east
+    } else if degrees < 180 {
+        '↖' // south-east
+    } else if degrees < 225 {
+        '↑' // south
+    } else if degrees < 270 {
+        '↗' // south-west
+    } else if degrees < 315 {
+        '→' // west
+    } else if degrees < 360 {
+        '↘' // north-west
+    } else {
+        '?'
+    }
+}
+
+pub fn wind_label(wind_direction: Option<usize>, wind_speed: Option<f64>, locale: &Locale, palette: Palette) -> (String, Option<usize>) { // text + color
+    let severity = wind_speed.map(|w| WindBand::from_speed(w).severity()).unwrap_or(Severity::None);
+    let text = format!(
+        "{}  {}{}",
+        wind_direction.map(wind_direction_arrow).unwrap_or(' '),
+        wind_speed.map(|w| locale.format_wind_speed(w)).unwrap_or_else(|| "-".to_owned()),
+        palette.severity_marker(severity)
+    );
+    (text, palette.severity_color(severity))
+}
+
+pub fn wmo_code_label(wmo_code: Option<WmoCode>, palette: Palette, language: Language) -> (String, Option<usize>) { // text + color
+    match wmo_code {
+        Some(wmo_code) => (
+            format!("{}{}", translate(language, wmo_code.description()), palette.severity_marker(wmo_code.severity())),
+            palette.severity_color(wmo_code.severity()),
+        ),
+        None => (String::new(), None),
+    }
+}
+
+pub fn wmo_code_to_text(wmo_code: Option<WmoCode>, palette: Palette, language: Language) -> (Text, usize) { // text + len
+    let (text, color) = wmo_code_label(wmo_code, palette, language);
+    colored_text(text, color)
+}
+
+pub fn colored_text(text: String, color: Option<usize>) -> (Text, usize) { // text + width
+    let text_len = display_width(&text);
+    match color {
+        Some(color) => (Text::new(text).color_range(color, ..), text_len),
+        None => (Text::new(text), text_len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colorblind_palette_marks_severity() {
+        assert_eq!(wmo_code_to_text(Some(WmoCode::ClearSky), Palette::Colorblind, Language::English).1, "CLEAR SKY".len());
+        assert_eq!(wmo_code_to_text(Some(WmoCode::SlightRain), Palette::Colorblind, Language::English).1, "SLIGHT RAIN !".len());
+        assert_eq!(wmo_code_to_text(Some(WmoCode::HeavyRain), Palette::Colorblind, Language::English).1, "HEAVY RAIN !!".len());
+        assert_eq!(wmo_code_to_text(Some(WmoCode::HeavyRain), Palette::Default, Language::English).1, "HEAVY RAIN".len());
+        assert_eq!(wmo_code_to_text(None, Palette::Default, Language::English).1, 0);
+    }
+
+    #[test]
+    fn wind_speeds_fall_into_bands() {
+        assert_eq!(WindBand::from_speed(5.3), WindBand::Calm);
+        assert_eq!(WindBand::from_speed(12.4), WindBand::Breezy);
+        assert_eq!(WindBand::from_speed(45.0), WindBand::Strong);
+        assert_eq!(WindBand::from_speed(62.0), WindBand::Gale);
+        let locale = Locale::default();
+        assert_eq!(wind_label(Some(180), Some(11.1), &locale, Palette::Default), ("↑  11.1kph".to_owned(), None));
+        assert_eq!(wind_label(Some(180), Some(70.0), &locale, Palette::Default), ("↑  70kph".to_owned(), Some(3)));
+        assert_eq!(wind_label(None, Some(45.0), &locale, Palette::Colorblind), ("   45kph !".to_owned(), Some(1)));
+        assert_eq!(wind_label(None, None, &locale, Palette::Default), ("   -".to_owned(), None));
+    }
+
+    #[test]
+    fn wmo_code_text_is_translated() {
+        assert_eq!(wmo_code_to_text(Some(WmoCode::Overcast), Palette::Default, Language::German).0.serialize(), Text::new("BEDECKT").serialize());
+    }
+
+    #[test]
+    fn error_messages_are_translated_per_variant() {
+        assert_eq!(error_message(&WeatherPalError::Http(RequestKind::Weather, 502, "Bad Gateway".to_owned()), Language::English), "Failed weather web request (502): Bad Gateway");
+        assert_eq!(error_message(&WeatherPalError::Http(RequestKind::Geocode, 404, String::new()), Language::English), "Failed geocode web request (404)");
+        assert_eq!(error_message(&WeatherPalError::Http(RequestKind::Aviation, 503, String::new()), Language::German), "Flughafenwetteranfrage fehlgeschlagen (503)");
+        assert_eq!(WeatherPalError::Http(RequestKind::Aviation, 503, String::new()).to_string(), "Failed airport report web request (status 503): ");
+        assert_eq!(error_message(&WeatherPalError::GeocodeEmpty, Language::German), "Kein passender Ort gefunden");
+        assert_eq!(
+            error_message(&WeatherPalError::Command("bash: timedatectl: command not found".to_owned()), Language::English),
+            "Error fetching timezone: bash: timedatectl: command not found"
+        );
+    }
+
+    #[test]
+    fn temperature_trend_arrows() {
+        assert_eq!(temperature_trend_arrow(Some(7.5), 8.7), '↑');
+        assert_eq!(temperature_trend_arrow(Some(9.3), 9.2), '→');
+        assert_eq!(temperature_trend_arrow(Some(9.3), 8.6), '↓');
+        assert_eq!(temperature_trend_arrow(None, 8.6), ' ');
+    }
+
+    #[test]
+    fn beaufort_forces() {
+        assert_eq!(beaufort(0.5), 0);
+        assert_eq!(beaufort(11.9), 2);
+        assert_eq!(beaufort(12.0), 3);
+        assert_eq!(beaufort(45.0), 6);
+        assert_eq!(beaufort(130.0), 12);
+        assert_eq!(beaufort_severity(beaufort(25.0)), Severity::None);
+        assert_eq!(beaufort_severity(beaufort(45.0)), Severity::Mild);
+        assert_eq!(beaufort_severity(beaufort(70.0)), Severity::Severe);
+    }
+
+    #[test]
+    fn wind_direction_arrows() {
+        assert_eq!(wind_direction_arrow(0), '↓');
+        assert_eq!(wind_direction_arrow(44), '↓');
+        assert_eq!(wind_direction_arrow(45), '↙');
+        assert_eq!(wind_direction_arrow(180), '↑');
+        assert_eq!(wind_direction_arrow(270), '→');
+        assert_eq!(wind_direction_arrow(359), '↘');
+        assert_eq!(wind_direction_arrow(360), '↓');
+        assert_eq!(wind_direction_arrow(361), '?');
+    }
+
+    #[test]
+    fn temperature_gradient_spans_the_day() {
+        assert_eq!(temperature_gradient_color(2.0, 2.0, 10.0, Palette::Default), 1);
+        assert_eq!(temperature_gradient_color(5.0, 2.0, 10.0, Palette::Default), 2);
+        assert_eq!(temperature_gradient_color(7.0, 2.0, 10.0, Palette::Default), 0);
+        assert_eq!(temperature_gradient_color(10.0, 2.0, 10.0, Palette::Default), 3);
+        assert_eq!(temperature_gradient_color(10.0, 2.0, 10.0, Palette::Colorblind), 0);
+        assert_eq!(temperature_gradient_color(4.0, 4.0, 4.0, Palette::Default), 2);
+        assert_eq!(temperature_gradient_color(4.0, 4.0, 4.0, Palette::HighContrast), 0);
+    }
+
+    #[test]
+    fn controls_text_highlights_keys() {
+        let controls_text = "Press <ENTER> to reload, <Ctrl-w> to enter a new location";
+        assert_eq!(
+            controls_text_with_keys_highlighted(controls_text, 3).serialize(),
+            Text::new(controls_text).color_range(3, 6..13).color_range(3, 25..33).serialize()
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use crate::parse::HourlyData;
+
+pub const HOOK_COMMAND_ID: &str = "HOOK_COMMAND_ID";
+pub const DEFAULT_HOOK_TEMPERATURES: &[f64] = &[0.0]; // freezing
+
+// the categories of the current hour, the hook runs when one of them changes
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conditions {
+    pub raining: bool,
+    pub temperature_band: usize, // how many of the thresholds the temperature is at or above
+}
+
+// eg. "0,25", in celsius, lowest first
+pub fn parse_hook_temperatures(temperatures: &str) -> Vec<f64> {
+    let mut temperatures: Vec<f64> = temperatures.split(',').filter_map(|t| t.trim().trim_end_matches("°C").parse().ok()).collect();
+    temperatures.sort_by(|a, b| a.total_cmp(b));
+    temperatures
+}
+
+// rain when it is falling or likely enough to be highlighted
+pub fn conditions(hourly_data: &HourlyData, temperatures: &[f64], precipitation_threshold: usize) -> Conditions {
+    Conditions {
+        raining: hourly_data.precipitation.map(|p| p > 0.0).unwrap_or(false) || hourly_data.precipitation_probability.map(|p| p >= precipitation_threshold).unwrap_or(false),
+        temperature_band: temperatures.iter().filter(|t| hourly_data.temperature_2m >= **t).count(),
+    }
+}
+
+// an event for each change, with the threshold crossed by the temperature
+pub fn transitions(before: &Conditions, after: &Conditions, temperatures: &[f64]) -> Vec<(&'static str, Option<f64>)> {
+    let mut transitions = vec![];
+    match (before.raining, after.raining) {
+        (false, true) => transitions.push(("rain_started", None)),
+        (true, false) => transitions.push(("rain_stopped", None)),
+        _ => (),
+    }
+    for threshold in temperatures.iter().take(after.temperature_band).skip(before.temperature_band) {
+        transitions.push(("temperature_rose", Some(*threshold)));
+    }
+    for threshold in temperatures.iter().take(before.temperature_band).skip(after.temperature_band).rev() {
+        transitions.push(("temperature_fell", Some(*threshold)));
+    }
+    transitions
+}
+
+// what the hook command gets to know, temperatures in celsius and precipitation in mm whatever the units
+pub fn hook_environment(event: &str, threshold: Option<f64>, hourly_data: &HourlyData, location: Option<&str>) -> BTreeMap<String, String> {
+    let mut environment = BTreeMap::new();
+    environment.insert("WEATHER_PAL_EVENT".to_owned(), event.to_owned());
+    environment.insert("WEATHER_PAL_LOCATION".to_owned(), location.unwrap_or_default().to_owned());
+    environment.insert("WEATHER_PAL_TEMPERATURE".to_owned(), hourly_data.temperature_2m.to_string());
+    if let Some(threshold) = threshold {
+        environment.insert("WEATHER_PAL_THRESHOLD".to_owned(), threshold.to_string());
+    }
+    if let Some(precipitation) = hourly_data.precipitation {
+        environment.insert("WEATHER_PAL_PRECIPITATION".to_owned(), precipitation.to_string());
+    }
+    if let Some(precipitation_probability) = hourly_data.precipitation_probability {
+        environment.insert("WEATHER_PAL_PRECIPITATION_PROBABILITY".to_owned(), precipitation_probability.to_string());
+    }
+    if let Some(wmo_code) = hourly_data.wmo_code {
+        environment.insert("WEATHER_PAL_WEATHER".to_owned(), wmo_code.description().to_owned());
+    }
+    environment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_what_changed() {
+        let temperatures = parse_hook_temperatures("25, 0°C, x");
+        assert_eq!(temperatures, vec![0.0, 25.0]);
+        let cold_and_dry = HourlyData { temperature_2m: -2.0, precipitation_probability: Some(10), precipitation: Some(0.0), ..Default::default() };
+        let warm_and_wet = HourlyData { temperature_2m: 26.0, precipitation_probability: Some(10), precipitation: Some(0.4), ..Default::default() };
+        let before = conditions(&cold_and_dry, &temperatures, 50);
+        let after = conditions(&warm_and_wet, &temperatures, 50);
+        assert_eq!(after, Conditions { raining: true, temperature_band: 2 });
+        assert_eq!(transitions(&before, &after, &temperatures), vec![("rain_started", None), ("temperature_rose", Some(0.0)), ("temperature_rose", Some(25.0))]);
+        assert_eq!(transitions(&after, &before, &temperatures), vec![("rain_stopped", None), ("temperature_fell", Some(25.0)), ("temperature_fell", Some(0.0))]);
+        assert!(transitions(&after, &after, &temperatures).is_empty());
+        let environment = hook_environment("temperature_rose", Some(25.0), &warm_and_wet, Some("Vienna, Austria"));
+        assert_eq!(environment.get("WEATHER_PAL_THRESHOLD").map(|t| t.as_str()), Some("25"));
+        assert_eq!(environment.get("WEATHER_PAL_PRECIPITATION").map(|t| t.as_str()), Some("0.4"));
+        assert_eq!(environment.len(), 6);
+    }
+}
@@ -0,0 +1,221 @@
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Spanish,
+    Hebrew,
+}
+
+impl Language {
+    pub fn from_config(language: &str) -> Self {
+        let language = language.to_lowercase();
+        match language.split(['-', '_']).next().unwrap_or("") {
+            "de" | "german" | "deutsch" => Language::German,
+            "es" | "spanish" | "español" | "espanol" => Language::Spanish,
+            "he" | "hebrew" | "עברית" => Language::Hebrew,
+            _ => Language::English,
+        }
+    }
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::Spanish => "es",
+            Language::Hebrew => "he",
+        }
+    }
+}
+
+// english, german, spanish, hebrew
+const TRANSLATIONS: &[(&str, &str, &str, &str)] = &[
+    ("CLEAR SKY", "KLARER HIMMEL", "CIELO DESPEJADO", "שמיים בהירים"),
+    ("MAINLY CLEAR", "ÜBERWIEGEND KLAR", "MAYORMENTE DESPEJADO", "בהיר ברובו"),
+    ("PARTLY CLOUDY", "TEILWEISE BEWÖLKT", "PARCIALMENTE NUBLADO", "מעונן חלקית"),
+    ("OVERCAST", "BEDECKT", "CUBIERTO", "מעונן"),
+    ("FOG", "NEBEL", "NIEBLA", "ערפל"),
+    ("LIGHT DRIZZLE", "LEICHTER NIESELREGEN", "LLOVIZNA LIGERA", "טפטוף קל"),
+    ("MODERATE DRIZZLE", "MÄSSIGER NIESELREGEN", "LLOVIZNA MODERADA", "טפטוף מתון"),
+    ("DENSE DRIZZLE", "STARKER NIESELREGEN", "LLOVIZNA DENSA", "טפטוף כבד"),
+    ("FREEZING DRIZZLE (LIGHT)", "GEFRIERENDER NIESELREGEN (LEICHT)", "LLOVIZNA HELADA (LIGERA)", "טפטוף קופא (קל)"),
+    ("FREEZING DRIZZLE (DENSE)", "GEFRIERENDER NIESELREGEN (STARK)", "LLOVIZNA HELADA (DENSA)", "טפטוף קופא (כבד)"),
+    ("SLIGHT RAIN", "LEICHTER REGEN", "LLUVIA LIGERA", "גשם קל"),
+    ("MODERATE RAIN", "MÄSSIGER REGEN", "LLUVIA MODERADA", "גשם מתון"),
+    ("HEAVY RAIN", "STARKER REGEN", "LLUVIA FUERTE", "גשם כבד"),
+    ("FREEZING RAIN (LIGHT)", "GEFRIERENDER REGEN (LEICHT)", "LLUVIA HELADA (LIGERA)", "גשם קופא (קל)"),
+    ("FREEZING RAIN (HEAVY)", "GEFRIERENDER REGEN (STARK)", "LLUVIA HELADA (FUERTE)", "גשם קופא (כבד)"),
+    ("SLIGHT SNOW", "LEICHTER SCHNEEFALL", "NEVADA LIGERA", "שלג קל"),
+    ("MODERATE SNOW", "MÄSSIGER SCHNEEFALL", "NEVADA MODERADA", "שלג מתון"),
+    ("HEAVY SNOW", "STARKER SCHNEEFALL", "NEVADA FUERTE", "שלג כבד"),
+    ("SNOW GRAINS", "SCHNEEGRIESEL", "GRANOS DE NIEVE", "גרגרי שלג"),
+    ("RAIN SHOWERS (SLIGHT)", "REGENSCHAUER (LEICHT)", "CHUBASCOS (LIGEROS)", "ממטרים (קלים)"),
+    ("RAIN SHOWERS (MODERATE)", "REGENSCHAUER (MÄSSIG)", "CHUBASCOS (MODERADOS)", "ממטרים (מתונים)"),
+    ("RAIN SHOWERS (VIOLENT)", "REGENSCHAUER (HEFTIG)", "CHUBASCOS (VIOLENTOS)", "ממטרים (עזים)"),
+    ("SNOW SHOWERS (SLIGHT)", "SCHNEESCHAUER (LEICHT)", "CHUBASCOS DE NIEVE (LIGEROS)", "ממטרי שלג (קלים)"),
+    ("SNOW SHOWERS (HEAVY)", "SCHNEESCHAUER (STARK)", "CHUBASCOS DE NIEVE (FUERTES)", "ממטרי שלג (כבדים)"),
+    ("THUNDERSTORM", "GEWITTER", "TORMENTA", "סופת רעמים"),
+    ("THUNDERSTORM (SLIGHT HAIL)", "GEWITTER (LEICHTER HAGEL)", "TORMENTA (GRANIZO LIGERO)", "סופת רעמים (ברד קל)"),
+    ("THUNDERSTORM (HEAVY HAIL)", "GEWITTER (STARKER HAGEL)", "TORMENTA (GRANIZO FUERTE)", "סופת רעמים (ברד כבד)"),
+    (
+        "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        "<ENTER> zum Neuladen, <Ctrl-w> um einen neuen Ort einzugeben",
+        "Pulsa <ENTER> para recargar, <Ctrl-w> para introducir una nueva ubicación",
+        "לחצו <ENTER> לטעינה מחדש, <Ctrl-w> להזנת מיקום חדש",
+    ),
+    (
+        "Press <ENTER> to run, <Ctrl-w> to enter a new location",
+        "<ENTER> zum Starten, <Ctrl-w> um einen neuen Ort einzugeben",
+        "Pulsa <ENTER> para empezar, <Ctrl-w> para introducir una nueva ubicación",
+        "לחצו <ENTER> להפעלה, <Ctrl-w> להזנת מיקום חדש",
+    ),
+    ("Hourly", "Stündlich", "Por horas", "שעתי"),
+    ("Daily", "Täglich", "Diario", "יומי"),
+    ("Week", "Woche", "Semana", "שבועי"),
+    ("Chart", "Diagramm", "Gráfico", "גרף"),
+    ("Map", "Karte", "Mapa", "מפה"),
+    ("Trip", "Reise", "Viaje", "טיול"),
+    ("Team", "Team", "Equipo", "צוות"),
+    ("Mon", "Mo", "Lun", "ב׳"),
+    ("Tue", "Di", "Mar", "ג׳"),
+    ("Wed", "Mi", "Mié", "ד׳"),
+    ("Thu", "Do", "Jue", "ה׳"),
+    ("Fri", "Fr", "Vie", "ו׳"),
+    ("Sat", "Sa", "Sáb", "ש׳"),
+    ("Sun", "So", "Dom", "א׳"),
+    ("High", "Höchst", "Máx", "גבוהה"),
+    ("Low", "Tiefst", "Mín", "נמוכה"),
+    ("degrees", "Grad", "grados", "מעלות"),
+    ("of precipitation", "Niederschlag", "de precipitación", "משקעים"),
+    ("percent chance of precipitation", "Prozent Niederschlagswahrscheinlichkeit", "por ciento de probabilidad de precipitación", "אחוז סיכוי למשקעים"),
+    ("wind", "Wind", "viento", "רוח"),
+    ("blowing from", "aus", "desde el", "מכיוון"),
+    ("vs yesterday", "ggü. gestern", "vs. ayer", "לעומת אתמול"),
+    ("above normal", "über normal", "sobre lo normal", "מעל הממוצע"),
+    ("below normal", "unter normal", "bajo lo normal", "מתחת לממוצע"),
+    ("Forecast vs actual", "Vorhersage vs. tatsächlich", "Pronóstico vs. real", "תחזית מול מציאות"),
+    ("rain right in {} of {} hours", "Regen in {} von {} Stunden richtig", "lluvia acertada en {} de {} horas", "גשם נכון ב-{} מתוך {} שעות"),
+    ("Rain likely now", "Regen wahrscheinlich jetzt", "Lluvia probable ahora", "גשם צפוי עכשיו"),
+    ("Rain likely in", "Regen wahrscheinlich in", "Lluvia probable en", "גשם צפוי בעוד"),
+    ("until", "bis", "hasta", "עד"),
+    ("then dry for", "danach trocken für", "luego seco durante", "ואז יבש במשך"),
+    ("then dry", "danach trocken", "luego seco", "ואז יבש"),
+    ("Best window", "Bestes Zeitfenster", "Mejor momento", "החלון הטוב ביותר"),
+    ("No suitable window in the next 48h", "Kein passendes Zeitfenster in den nächsten 48h", "Ningún momento adecuado en las próximas 48h", "אין חלון מתאים ב-48 השעות הקרובות"),
+    ("Golden hour", "Goldene Stunde", "Hora dorada", "שעת הזהב"),
+    ("Blue hour", "Blaue Stunde", "Hora azul", "השעה הכחולה"),
+    ("above the horizon", "über dem Horizont", "sobre el horizonte", "מעל האופק"),
+    ("below the horizon", "unter dem Horizont", "bajo el horizonte", "מתחת לאופק"),
+    ("sunrise", "Sonnenaufgang", "amanecer", "זריחה"),
+    ("sunset", "Sonnenuntergang", "atardecer", "שקיעה"),
+    ("high tide", "Flut", "pleamar", "גאות"),
+    ("low tide", "Ebbe", "bajamar", "שפל"),
+    ("Next 24h", "Nächste 24h", "Próximas 24h", "24 השעות הבאות"),
+    ("Tonight", "Heute Nacht", "Esta noche", "הלילה"),
+    ("excellent", "ausgezeichnet", "excelente", "מצוין"),
+    ("good", "gut", "bueno", "טוב"),
+    ("fair", "mäßig", "regular", "סביר"),
+    ("poor", "schlecht", "malo", "גרוע"),
+    ("waxing", "zunehmend", "creciente", "מתמלא"),
+    ("waning", "abnehmend", "menguante", "מתמעט"),
+    ("dark", "dunkel", "oscuro", "חשוך"),
+    ("no full darkness", "keine volle Dunkelheit", "sin oscuridad total", "ללא חושך מלא"),
+    ("Today", "Heute", "Hoy", "היום"),
+    ("pollen", "Pollen", "polen", "אבקנים"),
+    ("worst", "am schlimmsten", "peor", "הגרוע ביותר"),
+    ("alder", "Erle", "aliso", "אלמון"),
+    ("birch", "Birke", "abedul", "ליבנה"),
+    ("grass", "Gräser", "gramíneas", "עשבים"),
+    ("mugwort", "Beifuß", "artemisa", "לענה"),
+    ("olive", "Olive", "olivo", "זית"),
+    ("ragweed", "Ambrosia", "ambrosía", "אמברוסיה"),
+    ("Limit outdoor exercise", "Sport im Freien einschränken", "Limita el ejercicio al aire libre", "הגבילו פעילות גופנית בחוץ"),
+    ("Avoid outdoor exercise", "Sport im Freien vermeiden", "Evita el ejercicio al aire libre", "הימנעו מפעילות גופנית בחוץ"),
+    ("ozone", "Ozon", "ozono", "אוזון"),
+    ("up to", "bis zu", "hasta", "עד"),
+    ("Aurora tonight", "Polarlicht heute Nacht", "Aurora esta noche", "זוהר קוטבי הלילה"),
+    ("unlikely", "unwahrscheinlich", "improbable", "לא סביר"),
+    ("low", "gering", "baja", "נמוכה"),
+    ("moderate", "mittel", "moderada", "בינונית"),
+    ("high", "hoch", "alta", "גבוהה"),
+    ("Wind", "Wind", "Viento", "רוח"),
+    ("Sky", "Himmel", "Cielo", "שמיים"),
+    ("Temp", "Temp", "Temp", "טמפ׳"),
+    ("Rain", "Regen", "Lluvia", "גשם"),
+    ("Score", "Wertung", "Puntos", "ציון"),
+    ("Soil", "Boden", "Suelo", "קרקע"),
+    ("Solar", "Solar", "Solar", "סולארי"),
+    ("Wind aloft", "Höhenwind", "Viento en altura", "רוח בגובה"),
+    ("Snow", "Schnee", "Nieve", "שלג"),
+    ("Freezing", "0-Grad", "Isoterma", "קיפאון"),
+    ("Summit", "Gipfel", "Cumbre", "פסגה"),
+    ("Swell", "Dünung", "Mar de fondo", "גלי ים"),
+    ("Tide", "Gezeiten", "Marea", "גאות"),
+    ("Waves", "Wellen", "Olas", "גלים"),
+    ("Pollen", "Pollen", "Polen", "אבקנים"),
+    ("Air quality", "Luftqualität", "Calidad del aire", "איכות אוויר"),
+    ("gusts", "Böen", "ráfagas", "משבים"),
+    ("variable", "umlaufend", "variable", "משתנה"),
+    ("calm", "Windstille", "calma", "שקט"),
+    ("Visibility", "Sicht", "Visibilidad", "ראות"),
+    ("Temperature", "Temperatur", "Temperatura", "טמפרטורה"),
+    ("dew point", "Taupunkt", "punto de rocío", "נקודת טל"),
+    ("Clouds", "Wolken", "Nubes", "עננים"),
+    ("Weather", "Wetter", "Tiempo", "מזג אוויר"),
+    ("Next 2h", "Nächste 2h", "Próximas 2h", "השעתיים הקרובות"),
+    ("dry", "trocken", "seco", "יבש"),
+    ("from", "ab", "desde", "החל מ"),
+    ("Now", "Jetzt", "Ahora", "עכשיו"),
+    ("both", "beide", "ambos", "שניהם"),
+    ("Morning", "Morgens", "Mañana", "בוקר"),
+    ("Evening", "Abends", "Tarde", "ערב"),
+    ("rain", "Regen", "lluvia", "גשם"),
+    ("take the rain jacket", "Regenjacke mitnehmen", "lleva el chubasquero", "קחו מעיל גשם"),
+    ("none", "keins", "ninguno", "אין"),
+    ("No rain likely in the next 48h", "Kein Regen in den nächsten 48h erwartet", "Sin lluvia probable en las próximas 48h", "לא צפוי גשם ב-48 השעות הקרובות"),
+    ("hours", "Stunden", "horas", "שעות"),
+    ("of", "von", "de", "מתוך"),
+    ("next update in", "nächste Aktualisierung in", "próxima actualización en", "עדכון הבא בעוד"),
+    ("Enter desired location", "Gewünschten Ort eingeben", "Introduce la ubicación deseada", "הזינו את המיקום הרצוי"),
+    ("Fetching data...", "Daten werden geladen...", "Obteniendo datos...", "טוען נתונים..."),
+    ("Error fetching timezone", "Fehler beim Ermitteln der Zeitzone", "Error al obtener la zona horaria", "שגיאה באיתור אזור הזמן"),
+    ("Failed weather web request", "Wetteranfrage fehlgeschlagen", "Falló la solicitud del tiempo", "בקשת מזג האוויר נכשלה"),
+    ("Failed geocode web request", "Ortsanfrage fehlgeschlagen", "Falló la solicitud de geocodificación", "בקשת איתור המיקום נכשלה"),
+    ("Failed to parse data", "Daten konnten nicht gelesen werden", "No se pudieron leer los datos", "פענוח הנתונים נכשל"),
+    ("Failed to parse geocode", "Ort konnte nicht gelesen werden", "No se pudo leer la geocodificación", "פענוח המיקום נכשל"),
+    ("Failed elevation web request", "Höhenanfrage fehlgeschlagen", "Falló la solicitud de altitud", "בקשת הגובה נכשלה"),
+    ("Failed to parse elevation", "Höhe konnte nicht gelesen werden", "No se pudo leer la altitud", "פענוח הגובה נכשל"),
+    ("Failed weather warnings web request", "Unwetterwarnungsanfrage fehlgeschlagen", "Falló la solicitud de avisos meteorológicos", "בקשת אזהרות מזג האוויר נכשלה"),
+    ("Failed to parse weather warnings", "Unwetterwarnungen konnten nicht gelesen werden", "No se pudieron leer los avisos meteorológicos", "פענוח אזהרות מזג האוויר נכשל"),
+    ("Failed airport report web request", "Flughafenwetteranfrage fehlgeschlagen", "Falló la solicitud del informe del aeropuerto", "בקשת דיווח שדה התעופה נכשלה"),
+    ("Failed to parse airport report", "Flughafenwetter konnte nicht gelesen werden", "No se pudo leer el informe del aeropuerto", "פענוח דיווח שדה התעופה נכשל"),
+    ("Failed aurora forecast web request", "Polarlichtanfrage fehlgeschlagen", "Falló la solicitud de la previsión de auroras", "בקשת תחזית הזוהר נכשלה"),
+    ("Failed to parse aurora forecast", "Polarlichtvorhersage konnte nicht gelesen werden", "No se pudo leer la previsión de auroras", "פענוח תחזית הזוהר נכשל"),
+    ("Failed air quality web request", "Luftqualitätsanfrage fehlgeschlagen", "Falló la solicitud de calidad del aire", "בקשת איכות האוויר נכשלה"),
+    ("Failed to parse air quality", "Luftqualität konnte nicht gelesen werden", "No se pudo leer la calidad del aire", "פענוח איכות האוויר נכשל"),
+    ("Failed marine forecast web request", "Seewetteranfrage fehlgeschlagen", "Falló la solicitud de la previsión marina", "בקשת התחזית הימית נכשלה"),
+    ("Failed to parse marine forecast", "Seewetter konnte nicht gelesen werden", "No se pudo leer la previsión marina", "פענוח התחזית הימית נכשל"),
+    ("Failed map web request", "Kartenanfrage fehlgeschlagen", "Falló la solicitud del mapa", "בקשת המפה נכשלה"),
+    ("Failed to parse map", "Karte konnte nicht gelesen werden", "No se pudo leer el mapa", "פענוח המפה נכשל"),
+    ("Failed team weather web request", "Teamwetteranfrage fehlgeschlagen", "Falló la solicitud del tiempo del equipo", "בקשת מזג האוויר של הצוות נכשלה"),
+    ("Failed to parse team weather", "Teamwetter konnte nicht gelesen werden", "No se pudo leer el tiempo del equipo", "פענוח מזג האוויר של הצוות נכשל"),
+    ("Failed climate normals web request", "Klimamittelanfrage fehlgeschlagen", "Falló la solicitud de las normales climáticas", "בקשת הנורמות האקלימיות נכשלה"),
+    ("Failed to parse climate normals", "Klimamittel konnten nicht gelesen werden", "No se pudieron leer las normales climáticas", "פענוח הנורמות האקלימיות נכשל"),
+    ("No forecast for this date", "Keine Vorhersage für dieses Datum", "No hay previsión para esta fecha", "אין תחזית לתאריך זה"),
+    ("No matching location found", "Kein passender Ort gefunden", "No se encontró ninguna ubicación", "לא נמצא מיקום מתאים"),
+    ("Couldn't find '{}' — check spelling or try adding a country", "'{}' nicht gefunden — Schreibweise prüfen oder ein Land hinzufügen", "No se encontró '{}' — revisa la ortografía o añade un país", "לא נמצא '{}' — בדקו את האיות או הוסיפו מדינה"),
+    ("Jump to day:", "Springe zu Tag:", "Ir al día:", "קפיצה ליום:"),
+    ("No matching location found, did you mean:", "Kein passender Ort gefunden, meinten Sie:", "No se encontró ninguna ubicación, ¿quisiste decir?", "לא נמצא מיקום מתאים, האם התכוונת ל:"),
+];
+
+pub fn translate(language: Language, text: &'static str) -> &'static str {
+    TRANSLATIONS
+        .iter()
+        .find(|(english, ..)| *english == text)
+        .map(|(english, german, spanish, hebrew)| match language {
+            Language::English => *english,
+            Language::German => *german,
+            Language::Spanish => *spanish,
+            Language::Hebrew => *hebrew,
+        })
+        .unwrap_or(text)
+}
@@ -0,0 +1,154 @@
+use zellij_tile::prelude::*;
+
+// terminals wrap pasted text in these, the escape before them reaches the plugin as Alt-[
+const PASTE_START: &str = "[200~";
+const PASTE_END: &str = "[201~";
+
+// a single line of text being typed, with a cursor that can move around in it
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct LineInput {
+    text: String,
+    cursor: usize, // in characters, 0 is before the first one
+    pasting: bool,
+}
+
+impl LineInput {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+    pub fn into_text(self) -> String {
+        self.text.trim().to_owned()
+    }
+    pub fn is_pasting(&self) -> bool {
+        // line breaks while pasting are part of the text rather than a request to search
+        self.pasting
+    }
+    pub fn handle_key(&mut self, key: &Key) -> bool { // true if the key was used
+        match key {
+            Key::Char('~') => {
+                self.insert('~');
+                self.strip_paste_markers();
+            }
+            Key::Char(character) if !character.is_control() => self.insert(*character),
+            Key::Char('\n') | Key::Char('\r') | Key::Char('\t') if self.pasting => {
+                if !self.text.chars().nth(self.cursor.saturating_sub(1)).map(|c| c.is_whitespace()).unwrap_or(true) {
+                    self.insert(' ');
+                }
+            }
+            Key::Alt(CharOrArrow::Char('[')) => self.insert('['),
+            Key::Backspace => {
+                if self.cursor > 0 {
+                    self.cursor -= 1;
+                    self.text.remove(self.byte_index(self.cursor));
+                }
+            }
+            Key::Delete => {
+                if self.cursor < self.len() {
+                    self.text.remove(self.byte_index(self.cursor));
+                }
+            }
+            Key::Left => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            Key::Home | Key::Ctrl('a') => self.cursor = 0,
+            Key::End | Key::Ctrl('e') => self.cursor = self.len(),
+            Key::Ctrl('u') => {
+                self.text.clear();
+                self.cursor = 0;
+            }
+            _ => return false,
+        }
+        true
+    }
+    pub fn insert(&mut self, character: char) {
+        self.text.insert(self.byte_index(self.cursor), character);
+        self.cursor += 1;
+    }
+    fn strip_paste_markers(&mut self) {
+        let before_cursor = &self.text[..self.byte_index(self.cursor)];
+        for (marker, pasting) in [(PASTE_START, true), (PASTE_END, false)].iter() {
+            if before_cursor.ends_with(marker) {
+                let marker_start = before_cursor.len() - marker.len();
+                self.text.replace_range(marker_start..before_cursor.len(), "");
+                self.cursor -= marker.chars().count();
+                self.pasting = *pasting;
+                return;
+            }
+        }
+    }
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.text.char_indices().nth(cursor).map(|(i, _)| i).unwrap_or(self.text.len())
+    }
+}
+
+impl From<&str> for LineInput {
+    fn from(text: &str) -> Self {
+        LineInput { text: text.to_owned(), cursor: text.chars().count(), pasting: false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(input: &mut LineInput, text: &str) {
+        for character in text.chars() {
+            input.handle_key(&Key::Char(character));
+        }
+    }
+
+    fn paste_text(input: &mut LineInput, text: &str) {
+        input.handle_key(&Key::Alt(CharOrArrow::Char('[')));
+        type_text(input, &format!("200~{}", text));
+        input.handle_key(&Key::Alt(CharOrArrow::Char('[')));
+        type_text(input, "201~");
+    }
+
+    #[test]
+    fn edits_in_the_middle_of_the_line() {
+        let mut input = LineInput::from("Zrich");
+        for key in [Key::Home, Key::Right, Key::Char('ü'), Key::End, Key::Left, Key::Backspace] {
+            assert!(input.handle_key(&key));
+        }
+        assert_eq!(input.text(), "Zürih");
+        assert_eq!(input.cursor(), 4);
+        input.handle_key(&Key::Delete);
+        input.handle_key(&Key::Delete);
+        assert_eq!(input.text(), "Züri");
+    }
+
+    #[test]
+    fn clears_the_line() {
+        let mut input = LineInput::from("Tel Aviv");
+        input.handle_key(&Key::Ctrl('u'));
+        assert_eq!(input, LineInput::default());
+        assert!(!input.handle_key(&Key::Char('\n')));
+        assert!(!input.handle_key(&Key::PageUp));
+    }
+
+    #[test]
+    fn pastes_text_wrapped_in_bracketed_paste_markers() {
+        let mut input = LineInput::from("Rio");
+        input.handle_key(&Key::Home);
+        input.handle_key(&Key::Alt(CharOrArrow::Char('[')));
+        type_text(&mut input, "200~Ciudad de\n");
+        assert!(input.is_pasting());
+        input.handle_key(&Key::Alt(CharOrArrow::Char('[')));
+        type_text(&mut input, "201~");
+        assert!(!input.is_pasting());
+        assert_eq!(input.text(), "Ciudad de Rio");
+        assert_eq!(input.cursor(), 10);
+    }
+
+    #[test]
+    fn pastes_multiple_lines_as_one() {
+        let mut input = LineInput::default();
+        paste_text(&mut input, "Rio de\r\n\tJaneiro\n");
+        assert_eq!(input.into_text(), "Rio de Janeiro");
+    }
+}
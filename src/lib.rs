@@ -0,0 +1,38 @@
+pub mod accuracy;
+pub mod air_quality;
+pub mod alerts;
+pub mod aurora;
+pub mod aviation;
+pub mod browser;
+pub mod chart;
+pub mod climate;
+pub mod commute;
+pub mod debug;
+pub mod error;
+pub mod favorites;
+pub mod format;
+pub mod hooks;
+pub mod i18n;
+pub mod input;
+pub mod locale;
+pub mod map;
+pub mod marine;
+pub mod palette;
+pub mod parse;
+pub mod profile;
+pub mod render;
+pub mod requests;
+pub mod score;
+pub mod search;
+pub mod settings;
+pub mod shared;
+pub mod solar;
+pub mod stargazing;
+pub mod state;
+pub mod sun;
+pub mod team;
+pub mod template;
+pub mod trip;
+pub mod width;
+pub mod wmo;
+pub mod worker;
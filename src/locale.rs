@@ -0,0 +1,162 @@
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl UnitSystem {
+    pub fn from_config(units: &str) -> Option<Self> {
+        match units {
+            "metric" => Some(UnitSystem::Metric),
+            "imperial" => Some(UnitSystem::Imperial),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Locale {
+    pub decimal_separator: char,
+    pub units: UnitSystem,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale {
+            decimal_separator: '.',
+            units: UnitSystem::Metric,
+        }
+    }
+}
+
+// languages that write 12,5 rather than 12.5
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "is", "it",
+    "lt", "lv", "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr", "uk",
+    "vi",
+];
+
+// regions that still use fahrenheit and miles
+const IMPERIAL_REGIONS: &[&str] = &["us", "lr", "mm"];
+
+impl Locale {
+    pub fn from_config(locale: &str) -> Self {
+        // eg. "de_AT", "en-US" or "pt_BR.UTF-8"
+        let locale = locale.split('.').next().unwrap_or("").to_lowercase();
+        let mut parts = locale.split(['-', '_']);
+        let language = parts.next().unwrap_or("");
+        let region = parts.next().unwrap_or("");
+        Locale {
+            decimal_separator: if DECIMAL_COMMA_LANGUAGES.contains(&language) { ',' } else { '.' },
+            units: if IMPERIAL_REGIONS.contains(&region) { UnitSystem::Imperial } else { UnitSystem::Metric },
+        }
+    }
+    pub fn format_decimal(&self, value: f64) -> String {
+        self.format_decimal_places(value, 1)
+    }
+    pub fn format_decimal_places(&self, value: f64, places: i32) -> String {
+        let factor = 10f64.powi(places);
+        let rounded = (value * factor).round() / factor;
+        rounded.to_string().replace('.', &self.decimal_separator.to_string())
+    }
+    pub fn temperature(&self, celsius: f64) -> f64 {
+        match self.units {
+            UnitSystem::Metric => celsius,
+            UnitSystem::Imperial => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+    pub fn format_temperature(&self, celsius: f64) -> String {
+        self.format_decimal(self.temperature(celsius))
+    }
+    pub fn format_temperature_difference(&self, celsius: f64) -> String {
+        let difference = match self.units {
+            UnitSystem::Metric => celsius,
+            UnitSystem::Imperial => celsius * 9.0 / 5.0,
+        };
+        let sign = if difference >= 0.05 { "+" } else { "" };
+        format!("{}{}", sign, self.format_decimal(difference))
+    }
+    pub fn temperature_symbol(&self) -> &'static str {
+        match self.units {
+            UnitSystem::Metric => "°C",
+            UnitSystem::Imperial => "°F",
+        }
+    }
+    pub fn format_wind_speed(&self, kph: f64) -> String {
+        match self.units {
+            UnitSystem::Metric => format!("{}kph", self.format_decimal(kph)),
+            UnitSystem::Imperial => format!("{}mph", self.format_decimal(kph * 0.621371)),
+        }
+    }
+    pub fn format_precipitation(&self, mm: f64) -> String {
+        match self.units {
+            UnitSystem::Metric => format!("{}mm", self.format_decimal(mm)),
+            UnitSystem::Imperial => format!("{}in", self.format_decimal_places(mm / 25.4, 2)),
+        }
+    }
+    pub fn format_snow(&self, cm: f64) -> String {
+        match self.units {
+            UnitSystem::Metric => format!("{}cm", self.format_decimal(cm)),
+            UnitSystem::Imperial => format!("{}in", self.format_decimal(cm / 2.54)),
+        }
+    }
+    pub fn format_wave_height(&self, meters: f64) -> String {
+        match self.units {
+            UnitSystem::Metric => format!("{}m", self.format_decimal(meters)),
+            UnitSystem::Imperial => format!("{}ft", self.format_decimal(meters * 3.28084)),
+        }
+    }
+    pub fn format_distance(&self, meters: f64) -> String {
+        // short distances, like a visibility in fog, in meters or feet
+        match self.units {
+            UnitSystem::Metric if meters < 1000.0 => format!("{}m", meters.round()),
+            UnitSystem::Metric => format!("{}km", self.format_decimal(meters / 1000.0)),
+            UnitSystem::Imperial if meters < 1609.344 => format!("{}ft", (meters * 3.28084).round()),
+            UnitSystem::Imperial => format!("{}mi", self.format_decimal(meters / 1609.344)),
+        }
+    }
+    pub fn format_elevation(&self, meters: f64) -> String {
+        match self.units {
+            UnitSystem::Metric => format!("{}m", meters.round()),
+            UnitSystem::Imperial => format!("{}ft", (meters * 3.28084).round()),
+        }
+    }
+    pub fn format_percentage(&self, percentage: usize) -> String {
+        format!("{}%", percentage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_decimals_per_locale() {
+        assert_eq!(Locale::from_config("de_DE.UTF-8").format_temperature(12.5), "12,5");
+        assert_eq!(Locale::from_config("en_GB").format_temperature(12.5), "12.5");
+        assert_eq!(Locale::default().format_temperature(13.0), "13");
+    }
+
+    #[test]
+    fn us_locale_defaults_to_imperial_units() {
+        let locale = Locale::from_config("en-US");
+        assert_eq!(locale.format_temperature(20.0), "68");
+        assert_eq!(locale.temperature_symbol(), "°F");
+        assert_eq!(locale.format_wind_speed(10.0), "6.2mph");
+        assert_eq!(Locale::from_config("fr_FR").format_wind_speed(10.25), "10,3kph");
+        assert_eq!(locale.format_precipitation(6.35), "0.25in");
+        assert_eq!(locale.format_temperature_difference(2.0), "+3.6");
+        assert_eq!(Locale::default().format_temperature_difference(-1.25), "-1.3");
+        assert_eq!(Locale::from_config("de_AT").format_precipitation(1.25), "1,3mm");
+        assert_eq!(locale.format_elevation(2000.0), "6562ft");
+        assert_eq!(Locale::default().format_elevation(192.4), "192m");
+        assert_eq!(locale.format_snow(25.4), "10in");
+        assert_eq!(Locale::from_config("de_AT").format_snow(2.5), "2,5cm");
+        assert_eq!(Locale::default().format_distance(850.0), "850m");
+        assert_eq!(Locale::default().format_distance(24140.0), "24.1km");
+        assert_eq!(locale.format_distance(24140.0), "15mi");
+        assert_eq!(Locale::default().format_wave_height(1.25), "1.3m");
+        assert_eq!(locale.format_wave_height(1.0), "3.3ft");
+    }
+}
@@ -0,0 +1,180 @@
+use zellij_tile::prelude::*;
+
+use serde::Deserialize;
+
+use crate::error::{RequestKind, WeatherPalError};
+use crate::format::temperature_gradient_color;
+use crate::i18n::translate;
+use crate::render::{shows_tab_bar, Renderer};
+use crate::state::State;
+use crate::width::display_width;
+
+pub const MAP_GRID_SIZE: usize = 7; // points along each side, the location is the one in the middle
+const MAP_SPACING: f64 = 0.25; // degrees between points, about 25km north to south
+pub const MAP_SHADES: &[char] = &[' ', '░', '▒', '▓', '█'];
+
+// the points of the grid around the location, row by row from the north west to the south east
+pub fn grid_points(latitude: f64, longitude: f64) -> Vec<(f64, f64)> {
+    let half = (MAP_GRID_SIZE / 2) as f64;
+    (0..MAP_GRID_SIZE)
+        .flat_map(|row| (0..MAP_GRID_SIZE).map(move |column| (row, column)))
+        .map(|(row, column)| {
+            let point_latitude = (latitude + (half - row as f64) * MAP_SPACING).clamp(-90.0, 90.0);
+            let point_longitude = longitude + (column as f64 - half) * MAP_SPACING;
+            (point_latitude, point_longitude)
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct PointResponse {
+    #[serde(default)]
+    current: CurrentResponse,
+}
+
+#[derive(Default, Deserialize)]
+struct CurrentResponse {
+    temperature_2m: Option<f64>,
+}
+
+// the API answers a request for several points with a list, in the order they were asked for
+pub fn parse_temperature_grid(body: Vec<u8>) -> Result<Vec<Option<f64>>, WeatherPalError> {
+    let points: Vec<PointResponse> = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Map, e.to_string()))?;
+    if points.len() != MAP_GRID_SIZE * MAP_GRID_SIZE {
+        return Err(WeatherPalError::Parse(RequestKind::Map, format!("expected {} map points, got {}", MAP_GRID_SIZE * MAP_GRID_SIZE, points.len())));
+    }
+    Ok(points.into_iter().map(|point| point.current.temperature_2m).collect())
+}
+
+pub fn shade(temperature: f64, min: f64, max: f64) -> char {
+    if max <= min {
+        return MAP_SHADES[MAP_SHADES.len() / 2];
+    }
+    let position = ((temperature - min) / (max - min)).clamp(0.0, 1.0);
+    MAP_SHADES[((position * MAP_SHADES.len() as f64) as usize).min(MAP_SHADES.len() - 1)]
+}
+
+impl State {
+    pub(crate) fn render_map<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize) {
+        // the current temperature around the location, stretched over the pane with north up,
+        // each point shaded and colored by how warm it is compared to the others
+        let top = if shows_tab_bar(rows) { 2 } else { 0 };
+        let bottom = if self.hide_controls { 1 } else { 2 }; // the legend and the controls
+        if self.temperature_map.len() != MAP_GRID_SIZE * MAP_GRID_SIZE {
+            let fetching_data_text = translate(self.language, "Fetching data...");
+            renderer.print_text(Text::new(fetching_data_text).color_range(self.palette.highlight(), ..), (cols / 2).saturating_sub(display_width(fetching_data_text) / 2), rows / 2);
+            return;
+        }
+        let (min, max) = self.temperature_map.iter().flatten().fold((f64::MAX, f64::MIN), |(min, max), temperature| (min.min(*temperature), max.max(*temperature)));
+        let cell_width = cols / MAP_GRID_SIZE;
+        let cell_height = (rows.saturating_sub(top + bottom) / MAP_GRID_SIZE).max(1);
+        let left = (cols - cell_width * MAP_GRID_SIZE) / 2;
+        for (grid_row, temperatures) in self.temperature_map.chunks(MAP_GRID_SIZE).enumerate() {
+            for line in 0..cell_height {
+                let mut text = String::new();
+                let mut colors = vec![]; // color and range of each point in characters
+                for (grid_column, temperature) in temperatures.iter().enumerate() {
+                    let is_location = grid_row == MAP_GRID_SIZE / 2 && grid_column == MAP_GRID_SIZE / 2;
+                    let label = match temperature {
+                        Some(temperature) if is_location => format!("[{}°]", self.locale.temperature(*temperature).round() as i64),
+                        Some(temperature) => format!("{}°", self.locale.temperature(*temperature).round() as i64),
+                        None => "-".to_owned(),
+                    };
+                    let label = if label.chars().count() + 2 <= cell_width { format!(" {} ", label) } else { label };
+                    let shade = temperature.map(|t| shade(t, min, max)).unwrap_or(' ');
+                    let cell = if line == cell_height / 2 && label.chars().count() <= cell_width {
+                        let padding = cell_width - label.chars().count();
+                        format!("{}{}{}", shade.to_string().repeat(padding / 2), label, shade.to_string().repeat(padding - padding / 2))
+                    } else {
+                        shade.to_string().repeat(cell_width)
+                    };
+                    let start = text.chars().count();
+                    text.push_str(&cell);
+                    if let Some(temperature) = temperature {
+                        colors.push((temperature_gradient_color(*temperature, min, max, self.palette), start..start + cell_width));
+                    }
+                }
+                let text = colors.into_iter().fold(Text::new(&text), |text, (color, range)| text.color_range(color, range));
+                renderer.print_text(text, left, top + grid_row * cell_height + line);
+            }
+        }
+        let legend = format!(
+            "{}: {}{} {} {}{}",
+            translate(self.language, "Now"),
+            self.locale.format_temperature(min),
+            self.locale.temperature_symbol(),
+            MAP_SHADES.iter().collect::<String>().trim(),
+            self.locale.format_temperature(max),
+            self.locale.temperature_symbol(),
+        );
+        renderer.print_text(Text::new(&legend), (cols / 2).saturating_sub(display_width(&legend) / 2), top + cell_height * MAP_GRID_SIZE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::tests::{render_snapshot, state_with_forecast};
+    use crate::state::View;
+
+    #[test]
+    fn centers_the_grid_on_the_location() {
+        let points = grid_points(48.0, 16.5);
+        assert_eq!(points.len(), 49);
+        assert_eq!(points[0], (48.75, 15.75));
+        assert_eq!(points[24], (48.0, 16.5));
+        assert_eq!(points[48], (47.25, 17.25));
+        assert_eq!(grid_points(89.9, 0.0)[0].0, 90.0);
+    }
+
+    #[test]
+    fn parses_a_temperature_for_each_point() {
+        let point = r#"{"latitude":48.2,"longitude":16.4,"current":{"time":"2023-11-19T10:00","temperature_2m":7.5}}"#;
+        let body = format!("[{}]", vec![point; 49].join(","));
+        let grid = parse_temperature_grid(body.into_bytes()).unwrap();
+        assert_eq!(grid.len(), 49);
+        assert_eq!(grid[0], Some(7.5));
+        assert!(parse_temperature_grid(format!("[{}]", point).into_bytes()).is_err());
+    }
+
+    #[test]
+    fn shades_by_temperature() {
+        assert_eq!(shade(0.0, 0.0, 10.0), ' ');
+        assert_eq!(shade(5.0, 0.0, 10.0), '▒');
+        assert_eq!(shade(10.0, 0.0, 10.0), '█');
+        assert_eq!(shade(3.0, 3.0, 3.0), '▒');
+    }
+
+    #[test]
+    fn renders_a_temperature_map_around_the_location() {
+        let state = State {
+            view: View::Map,
+            map_view: true,
+            temperature_map: (0..49).map(|i| if i == 40 { None } else { Some(12.0 - (i / 7) as f64 + (i % 7) as f64 * 0.5) }).collect(),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "  Hourly    Daily    Week    Chart  [ Map ]",
+            "",
+            "  ▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓████████████████████████████████",
+            "  ▓ 12° ▓▓▓ 13° ▓▓▓ 13° ▓▓█ 14° ███ 14° ███ 15° ███ 15° ██",
+            "  ▒▒▒▒▒▒▒▒▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓████████████████",
+            "  ▒ 11° ▒▒▓ 12° ▓▓▓ 12° ▓▓▓ 13° ▓▓▓ 13° ▓▓█ 14° ███ 14° ██",
+            "  ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓",
+            "  ▒ 10° ▒▒▒ 11° ▒▒▒ 11° ▒▒▓ 12° ▓▓▓ 12° ▓▓▓ 13° ▓▓▓ 13° ▓▓",
+            "  ░░░░░░░░░░░░░░░░▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓▓",
+            "  ░░ 9° ░░░ 10° ░░▒ 10° ▒▒ [11°] ▒▒ 11° ▒▒▓ 12° ▓▓▓ 12° ▓▓",
+            "  ░░░░░░░░░░░░░░░░░░░░░░░░░░░░░░░░▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒",
+            "  ░░ 8° ░░░░ 9° ░░░░ 9° ░░░ 10° ░░▒ 10° ▒▒▒ 11° ▒▒▒ 11° ▒▒",
+            "                  ░░░░░░░░░░░░░░░░░░░░░░░░        ▒▒▒▒▒▒▒▒",
+            "     7°      8°   ░░ 8° ░░░░ 9° ░░░░ 9° ░░   -    ▒ 10° ▒▒",
+            "                                  ░░░░░░░░░░░░░░░░░░░░░░░░",
+            "     6°      7°      7°      8°   ░░ 8° ░░░░ 9° ░░░░ 9° ░░",
+            "                     Now: 6°C ░▒▓█ 15°C",
+            "",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 20, 60, 10), expected.join("\n"));
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::error::{RequestKind, WeatherPalError};
+
+pub const MARINE_HOURLY_VARIABLES: &[&str] = &["wave_height", "wave_period", "swell_wave_height", "swell_wave_period", "swell_wave_direction", "sea_level_height_msl"];
+
+// the sea at the location for an hour, from the marine API, empty on land
+#[derive(Default, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MarineData {
+    pub wave_height: Option<f64>, // meters, wind waves and swell together
+    pub wave_period: Option<f64>, // seconds
+    pub swell_wave_height: Option<f64>, // meters
+    pub swell_wave_period: Option<f64>, // seconds
+    pub swell_wave_direction: Option<usize>, // degrees, where the swell comes from
+    pub sea_level_height_msl: Option<f64>, // meters, rises and falls with the tide
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tide {
+    Rising,
+    Falling,
+    High,
+    Low,
+}
+
+#[derive(Deserialize)]
+struct MarineResponse {
+    hourly: MarineHourlyResponse,
+}
+
+#[derive(Deserialize)]
+struct MarineHourlyResponse {
+    #[serde(default)]
+    time: Vec<String>,
+    #[serde(default)]
+    wave_height: Vec<Option<f64>>,
+    #[serde(default)]
+    wave_period: Vec<Option<f64>>,
+    #[serde(default)]
+    swell_wave_height: Vec<Option<f64>>,
+    #[serde(default)]
+    swell_wave_period: Vec<Option<f64>>,
+    #[serde(default)]
+    swell_wave_direction: Vec<Option<usize>>,
+    #[serde(default)]
+    sea_level_height_msl: Vec<Option<f64>>,
+}
+
+// indexed by the hours of the forecast, so the past days are dropped the same way
+pub fn parse_marine_data(body: Vec<u8>, past_days: usize) -> Result<BTreeMap<usize, MarineData>, WeatherPalError> {
+    let response: MarineResponse = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Marine, e.to_string()))?;
+    let hourly = response.hourly;
+    Ok((past_days * 24..hourly.time.len())
+        .map(|i| {
+            let marine_data = MarineData {
+                wave_height: hourly.wave_height.get(i).copied().flatten(),
+                wave_period: hourly.wave_period.get(i).copied().flatten(),
+                swell_wave_height: hourly.swell_wave_height.get(i).copied().flatten(),
+                swell_wave_period: hourly.swell_wave_period.get(i).copied().flatten(),
+                swell_wave_direction: hourly.swell_wave_direction.get(i).copied().flatten(),
+                sea_level_height_msl: hourly.sea_level_height_msl.get(i).copied().flatten(),
+            };
+            (i - past_days * 24, marine_data)
+        })
+        .collect())
+}
+
+// high and low tide are the hours the sea level turns around
+pub fn tide(previous: Option<f64>, level: f64, next: Option<f64>) -> Option<Tide> {
+    match (previous, next) {
+        (Some(previous), Some(next)) if level > previous && level >= next => Some(Tide::High),
+        (Some(previous), Some(next)) if level < previous && level <= next => Some(Tide::Low),
+        (_, Some(next)) if next > level => Some(Tide::Rising),
+        (_, Some(next)) if next < level => Some(Tide::Falling),
+        (Some(previous), None) if level > previous => Some(Tide::Rising),
+        (Some(previous), None) if level < previous => Some(Tide::Falling),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_hours_after_the_past_days() {
+        let times: Vec<String> = (0..30).map(|h| format!("\"2023-11-{}T{:02}:00\"", 18 + h / 24, h % 24)).collect();
+        let heights: Vec<String> = (0..30).map(|h| if h == 25 { "null".to_owned() } else { format!("{}", h as f64 / 10.0) }).collect();
+        let body = format!(
+            r#"{{"hourly":{{"time":[{}],"swell_wave_height":[{}],"swell_wave_period":[],"swell_wave_direction":[]}}}}"#,
+            times.join(","),
+            heights.join(",")
+        );
+        let marine_data = parse_marine_data(body.into_bytes(), 1).unwrap();
+        assert_eq!(marine_data.len(), 6);
+        assert_eq!(marine_data[&0].swell_wave_height, Some(2.4));
+        assert_eq!(marine_data[&1].swell_wave_height, None);
+        assert_eq!(marine_data[&0].swell_wave_period, None);
+        assert!(parse_marine_data(b"{}".to_vec(), 1).is_err());
+    }
+
+    #[test]
+    fn finds_the_turn_of_the_tide() {
+        assert_eq!(tide(Some(0.5), 0.8, Some(0.6)), Some(Tide::High));
+        assert_eq!(tide(Some(-0.5), -0.9, Some(-0.9)), Some(Tide::Low));
+        assert_eq!(tide(Some(0.1), 0.3, Some(0.5)), Some(Tide::Rising));
+        assert_eq!(tide(None, 0.3, Some(0.1)), Some(Tide::Falling));
+        assert_eq!(tide(Some(0.1), 0.3, None), Some(Tide::Rising));
+        assert_eq!(tide(None, 0.3, None), None);
+    }
+}
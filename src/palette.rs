@@ -0,0 +1,114 @@
+use crate::score::Comfort;
+use crate::wmo::Severity;
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum Palette {
+    #[default]
+    Default,
+    Colorblind, // avoids green and adds shape cues for severity
+    HighContrast, // a single emphasis color, reverse video and shape cues instead of shades
+}
+
+impl Palette {
+    pub fn from_config(palette: &str) -> Self {
+        match palette {
+            "colorblind" => Palette::Colorblind,
+            "high-contrast" => Palette::HighContrast,
+            _ => Palette::Default,
+        }
+    }
+    pub fn highlight(&self) -> usize {
+        3
+    }
+    pub fn hour(&self) -> usize {
+        0
+    }
+    pub fn precipitation(&self) -> usize {
+        1
+    }
+    pub fn precipitation_probability_color(&self, probability: usize) -> Option<usize> {
+        // themes only offer a few emphasis colors rather than shades of blue, so unlikely precipitation stays plain
+        match (self, probability) {
+            (_, 0..=19) => None,
+            (Palette::HighContrast, _) => Some(3),
+            (_, 20..=69) => Some(self.precipitation()),
+            (_, _) => Some(3),
+        }
+    }
+    pub fn golden_hour(&self) -> usize {
+        0
+    }
+    pub fn blue_hour(&self) -> usize {
+        1
+    }
+    pub fn severity_color(&self, severity: Severity) -> Option<usize> {
+        match (self, severity) {
+            (_, Severity::None) => None,
+            (Palette::HighContrast, _) => Some(3),
+            (_, Severity::Mild) => Some(1),
+            (Palette::Default, Severity::Severe) => Some(3),
+            (Palette::Colorblind, Severity::Severe) => Some(0),
+        }
+    }
+    pub fn severity_marker(&self, severity: Severity) -> &'static str {
+        match (self, severity) {
+            (Palette::Colorblind | Palette::HighContrast, Severity::Mild) => " !",
+            (Palette::Colorblind | Palette::HighContrast, Severity::Severe) => " !!",
+            _ => "",
+        }
+    }
+    pub fn activity_score_color(&self, score: usize) -> usize {
+        match (self, score) {
+            (_, 0..=3) => 3,
+            (_, 4..=6) => 0,
+            (Palette::Default, _) => 2,
+            (Palette::Colorblind | Palette::HighContrast, _) => 1,
+        }
+    }
+    pub fn data_age_color(&self, age_minutes: i64, refresh_minutes: i64) -> usize {
+        // fresh, a refresh was missed, several refreshes were missed
+        match (self, age_minutes / refresh_minutes.max(1)) {
+            (Palette::Default, 0) => 2,
+            (Palette::Colorblind, 0) => 1,
+            (Palette::HighContrast, 0) => 0,
+            (_, 1) => 0,
+            (_, _) => 3,
+        }
+    }
+    pub fn comfort_color(&self, comfort: Comfort) -> Option<usize> {
+        match (self, comfort) {
+            (Palette::Default, Comfort::Pleasant) => Some(2),
+            (Palette::Colorblind, Comfort::Pleasant) => None,
+            (Palette::HighContrast, Comfort::Pleasant) => None,
+            (Palette::HighContrast, _) => Some(3), // bold rather than a color of its own for each
+            (_, Comfort::Wet) | (_, Comfort::Cold) => Some(1), // wet rows also stand out as selected
+            (_, Comfort::Windy) => Some(0),
+            (_, Comfort::Hot) => Some(3),
+        }
+    }
+    pub fn temperature_gradient(&self) -> &'static [usize] {
+        match self {
+            Palette::Default => &[1, 2, 0, 3], // cyan, green, orange, magenta
+            Palette::Colorblind => &[1, 3, 0], // cyan, magenta, orange
+            Palette::HighContrast => &[0],
+        }
+    }
+    // emphasized rows, eg. the best window, are shown in reverse video rather than only by color
+    pub fn reverse_video(&self) -> bool {
+        matches!(self, Palette::HighContrast)
+    }
+    // zellij draws emphasis colors in bold, so text in this one stands out by weight as well
+    pub fn bold(&self) -> Option<usize> {
+        match self {
+            Palette::HighContrast => Some(3),
+            _ => None,
+        }
+    }
+    // between the parts of a header
+    pub fn separator(&self) -> &'static str {
+        match self {
+            Palette::HighContrast => " │ ",
+            _ => "  ",
+        }
+    }
+}
@@ -0,0 +1,474 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use crate::error::{RequestKind, WeatherPalError};
+use crate::wmo::WmoCode;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct HourlyData {
+    pub temperature_2m: f64,
+    pub precipitation_probability: Option<usize>,
+    pub precipitation: Option<f64>,
+    pub wind_speed_10m: Option<f64>,
+    pub wind_direction_10m: Option<usize>,
+    pub wind_gusts_10m: Option<f64>,
+    pub wind_speed_80m: Option<f64>,
+    pub wind_speed_120m: Option<f64>,
+    pub wind_speed_180m: Option<f64>,
+    pub uv_index: Option<f64>,
+    pub soil_temperature_0cm: Option<f64>,
+    pub soil_moisture_0_to_1cm: Option<f64>, // m³/m³
+    pub shortwave_radiation: Option<f64>, // W/m²
+    pub global_tilted_irradiance: Option<f64>,
+    pub cape: Option<f64>, // J/kg
+    pub freezing_level_height: Option<f64>, // meters
+    pub snowfall: Option<f64>, // cm
+    pub snow_depth: Option<f64>, // meters
+    pub wind_speed_700hpa: Option<f64>, // kph, around 3000m
+    pub visibility: Option<f64>, // meters
+    pub cloud_cover: Option<usize>, // percent
+    pub wmo_code: Option<WmoCode>,
+    pub extras: BTreeMap<String, f64>, // the variables of hourly_extra, by their name
+}
+
+impl HourlyData {
+    // at the heights of wind turbine hubs and drone flights, in meters above the ground
+    pub fn wind_speed_at(&self, height: usize) -> Option<f64> {
+        match height {
+            80 => self.wind_speed_80m,
+            120 => self.wind_speed_120m,
+            180 => self.wind_speed_180m,
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct DailyData {
+    pub date: String,
+    pub temperature_2m_max: Option<f64>,
+    pub temperature_2m_min: Option<f64>,
+    pub precipitation_sum: Option<f64>,
+    pub precipitation_hours: Option<f64>,
+    pub wind_speed_10m_max: Option<f64>,
+    pub sunrise: Option<String>,
+    pub sunset: Option<String>,
+    pub et0_fao_evapotranspiration: Option<f64>, // mm
+    pub wmo_code: Option<WmoCode>,
+}
+
+// days before today that are requested for comparison, they come first in the response
+pub const PAST_DAYS: usize = 1;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Forecast {
+    pub hourly: BTreeMap<usize, HourlyData>,
+    pub daily: BTreeMap<usize, DailyData>,
+    pub yesterday: Option<DailyData>,
+    pub past_hourly: BTreeMap<usize, HourlyData>, // the hours of yesterday, as the model now has them
+    pub nowcast: Vec<Option<f64>>, // precipitation in mm for each 15 minutes from now, empty outside of supported regions
+    pub hourly_units: BTreeMap<String, String>, // eg. "pressure_msl": "hPa"
+}
+
+#[derive(Deserialize)]
+struct ForecastResponse {
+    hourly: HourlyResponse,
+    #[serde(default)]
+    hourly_units: BTreeMap<String, String>,
+    #[serde(default)]
+    daily: DailyResponse,
+    #[serde(default)]
+    minutely_15: MinutelyResponse,
+}
+
+// the API sends null for values a model doesn't have, so every field is optional
+#[derive(Deserialize)]
+struct HourlyResponse {
+    #[serde(default)]
+    temperature_2m: Vec<Option<f64>>,
+    #[serde(default)]
+    precipitation_probability: Vec<Option<usize>>,
+    #[serde(default)]
+    precipitation: Vec<Option<f64>>,
+    #[serde(default)]
+    wind_speed_10m: Vec<Option<f64>>,
+    #[serde(default)]
+    wind_direction_10m: Vec<Option<usize>>,
+    #[serde(default)]
+    wind_gusts_10m: Vec<Option<f64>>,
+    #[serde(default)]
+    wind_speed_80m: Vec<Option<f64>>,
+    #[serde(default)]
+    wind_speed_120m: Vec<Option<f64>>,
+    #[serde(default)]
+    wind_speed_180m: Vec<Option<f64>>,
+    #[serde(default)]
+    uv_index: Vec<Option<f64>>,
+    #[serde(default)]
+    soil_temperature_0cm: Vec<Option<f64>>,
+    #[serde(default)]
+    soil_moisture_0_to_1cm: Vec<Option<f64>>,
+    #[serde(default)]
+    shortwave_radiation: Vec<Option<f64>>,
+    #[serde(default)]
+    global_tilted_irradiance: Vec<Option<f64>>,
+    #[serde(default)]
+    cape: Vec<Option<f64>>,
+    #[serde(default)]
+    freezing_level_height: Vec<Option<f64>>,
+    #[serde(default)]
+    snowfall: Vec<Option<f64>>,
+    #[serde(default)]
+    snow_depth: Vec<Option<f64>>,
+    #[serde(default, rename = "wind_speed_700hPa")]
+    wind_speed_700hpa: Vec<Option<f64>>,
+    #[serde(default)]
+    visibility: Vec<Option<f64>>,
+    #[serde(default)]
+    cloud_cover: Vec<Option<usize>>,
+    #[serde(default)]
+    weather_code: Vec<Option<usize>>,
+    #[serde(flatten)]
+    extras: BTreeMap<String, Value>, // any other variable, as requested with hourly_extra
+}
+
+impl HourlyResponse {
+    fn hour(&self, i: usize) -> Option<HourlyData> {
+        // hours without a temperature are skipped, other missing values are rendered as such
+        Some(HourlyData {
+            temperature_2m: (*self.temperature_2m.get(i)?)?,
+            precipitation_probability: self.precipitation_probability.get(i).copied().flatten(),
+            precipitation: self.precipitation.get(i).copied().flatten(),
+            wind_speed_10m: self.wind_speed_10m.get(i).copied().flatten(),
+            wind_direction_10m: self.wind_direction_10m.get(i).copied().flatten(),
+            wind_gusts_10m: self.wind_gusts_10m.get(i).copied().flatten(),
+            wind_speed_80m: self.wind_speed_80m.get(i).copied().flatten(),
+            wind_speed_120m: self.wind_speed_120m.get(i).copied().flatten(),
+            wind_speed_180m: self.wind_speed_180m.get(i).copied().flatten(),
+            uv_index: self.uv_index.get(i).copied().flatten(),
+            soil_temperature_0cm: self.soil_temperature_0cm.get(i).copied().flatten(),
+            soil_moisture_0_to_1cm: self.soil_moisture_0_to_1cm.get(i).copied().flatten(),
+            shortwave_radiation: self.shortwave_radiation.get(i).copied().flatten(),
+            global_tilted_irradiance: self.global_tilted_irradiance.get(i).copied().flatten(),
+            cape: self.cape.get(i).copied().flatten(),
+            freezing_level_height: self.freezing_level_height.get(i).copied().flatten(),
+            snowfall: self.snowfall.get(i).copied().flatten(),
+            snow_depth: self.snow_depth.get(i).copied().flatten(),
+            wind_speed_700hpa: self.wind_speed_700hpa.get(i).copied().flatten(),
+            visibility: self.visibility.get(i).copied().flatten(),
+            cloud_cover: self.cloud_cover.get(i).copied().flatten(),
+            wmo_code: self.weather_code.get(i).copied().flatten().and_then(|c| WmoCode::try_from(c).ok()),
+            // only numbers, which leaves out the time
+            extras: self.extras.iter().filter_map(|(name, values)| Some((name.clone(), values.get(i)?.as_f64()?))).collect(),
+        })
+    }
+}
+
+#[derive(Default, Deserialize)]
+struct DailyResponse {
+    #[serde(default)]
+    time: Vec<String>,
+    #[serde(default)]
+    temperature_2m_max: Vec<Option<f64>>,
+    #[serde(default)]
+    temperature_2m_min: Vec<Option<f64>>,
+    #[serde(default)]
+    precipitation_sum: Vec<Option<f64>>,
+    #[serde(default)]
+    precipitation_hours: Vec<Option<f64>>,
+    #[serde(default)]
+    wind_speed_10m_max: Vec<Option<f64>>,
+    #[serde(default)]
+    sunrise: Vec<Option<String>>,
+    #[serde(default)]
+    sunset: Vec<Option<String>>,
+    #[serde(default)]
+    et0_fao_evapotranspiration: Vec<Option<f64>>,
+    #[serde(default)]
+    weather_code: Vec<Option<usize>>,
+}
+
+impl DailyResponse {
+    fn day(&self, i: usize) -> Option<DailyData> {
+        Some(DailyData {
+            date: self.time.get(i)?.clone(),
+            temperature_2m_max: self.temperature_2m_max.get(i).copied().flatten(),
+            temperature_2m_min: self.temperature_2m_min.get(i).copied().flatten(),
+            precipitation_sum: self.precipitation_sum.get(i).copied().flatten(),
+            precipitation_hours: self.precipitation_hours.get(i).copied().flatten(),
+            wind_speed_10m_max: self.wind_speed_10m_max.get(i).copied().flatten(),
+            sunrise: self.sunrise.get(i).cloned().flatten(),
+            sunset: self.sunset.get(i).cloned().flatten(),
+            et0_fao_evapotranspiration: self.et0_fao_evapotranspiration.get(i).copied().flatten(),
+            wmo_code: self.weather_code.get(i).copied().flatten().and_then(|c| WmoCode::try_from(c).ok()),
+        })
+    }
+}
+
+#[derive(Default, Deserialize)]
+struct MinutelyResponse {
+    #[serde(default)]
+    precipitation: Vec<Option<f64>>,
+}
+
+#[derive(Deserialize)]
+struct ElevationResponse {
+    #[serde(default)]
+    elevation: Vec<f64>,
+}
+
+#[derive(Deserialize)]
+struct GeocodeResponse {
+    #[serde(default)]
+    results: Vec<GeocodeResult>,
+}
+
+#[derive(Deserialize)]
+struct GeocodeResult {
+    latitude: f64,
+    longitude: f64,
+    name: String,
+    country: String,
+    #[serde(default)]
+    population: Option<u64>,
+    #[serde(default)]
+    admin1: Option<String>, // state or province
+}
+
+pub fn parse_weather_data(body: Vec<u8>, past_days: usize) -> Result<Forecast, WeatherPalError> {
+    // indices are relative to the start of today, past data is only kept for yesterday's summary and the forecast accuracy
+    let response: ForecastResponse = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Weather, e.to_string()))?;
+    let mut forecast = Forecast::default();
+    for i in past_days * 24..response.hourly.temperature_2m.len() {
+        if let Some(hourly_data) = response.hourly.hour(i) {
+            forecast.hourly.insert(i - past_days * 24, hourly_data);
+        }
+    }
+    if forecast.hourly.is_empty() {
+        return Err(WeatherPalError::Parse(RequestKind::Weather, "no forecast data".to_owned()));
+    }
+    for i in past_days..response.daily.time.len() {
+        if let Some(daily_data) = response.daily.day(i) {
+            forecast.daily.insert(i - past_days, daily_data);
+        }
+    }
+    forecast.yesterday = past_days.checked_sub(1).and_then(|i| response.daily.day(i));
+    for i in past_days.saturating_sub(1) * 24..past_days * 24 {
+        if let Some(hourly_data) = response.hourly.hour(i) {
+            forecast.past_hourly.insert(i - past_days.saturating_sub(1) * 24, hourly_data);
+        }
+    }
+    forecast.nowcast = response.minutely_15.precipitation;
+    forecast.hourly_units = response.hourly_units;
+    Ok(forecast)
+}
+
+pub fn parse_lat_lon_and_location(body: Vec<u8>, prefer_largest: bool) -> Result<(f64, f64, String), WeatherPalError> {
+    let geocode: GeocodeResponse = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Geocode, e.to_string()))?;
+    let result = if prefer_largest {
+        // the first of the most populated results, those without a population count as empty
+        geocode.results.iter().rev().max_by_key(|result| result.population.unwrap_or(0))
+    } else {
+        geocode.results.first()
+    };
+    let result = result.ok_or(WeatherPalError::GeocodeEmpty)?;
+    Ok((result.latitude, result.longitude, format!("{}, {}", result.name, result.country)))
+}
+
+pub fn parse_location_suggestions(body: Vec<u8>) -> Result<Vec<(f64, f64, String)>, WeatherPalError> {
+    let geocode: GeocodeResponse = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Geocode, e.to_string()))?;
+    Ok(geocode
+        .results
+        .iter()
+        .map(|result| {
+            // the region helps telling apart places of the same name
+            let location = match &result.admin1 {
+                Some(admin1) if *admin1 != result.name => format!("{}, {}, {}", result.name, admin1, result.country),
+                _ => format!("{}, {}", result.name, result.country),
+            };
+            (result.latitude, result.longitude, location)
+        })
+        .collect())
+}
+
+pub fn parse_elevation(body: Vec<u8>) -> Result<f64, WeatherPalError> {
+    let response: ElevationResponse = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Elevation, e.to_string()))?;
+    response.elevation.first().copied().ok_or_else(|| WeatherPalError::Parse(RequestKind::Elevation, "no elevation data".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_forecast_fixture() {
+        let weather_data = parse_weather_data(include_bytes!("../fixtures/forecast_with_synthetic_series.json").to_vec(), PAST_DAYS).unwrap().hourly;
+        let first_hour = &weather_data[&0];
+        assert_eq!(first_hour.temperature_2m, 2.7);
+        assert_eq!(first_hour.precipitation_probability, Some(24));
+        assert_eq!(first_hour.wind_speed_10m, Some(12.7));
+        assert_eq!(first_hour.wind_direction_10m, Some(170));
+        assert_eq!(first_hour.wmo_code, Some(WmoCode::Overcast));
+        assert_eq!(first_hour.soil_moisture_0_to_1cm, Some(0.306));
+        assert_eq!(weather_data[&144].wmo_code, Some(WmoCode::Fog));
+        assert_eq!(weather_data.len(), 168);
+    }
+
+    #[test]
+    fn parses_a_captured_response() {
+        // as the API sent it, without the daily, 15 minute and past series the other fixture adds
+        let forecast = parse_weather_data(include_bytes!("../fixtures/forecast.json").to_vec(), 0).unwrap();
+        let first_hour = &forecast.hourly[&0];
+        assert_eq!(first_hour.temperature_2m, 2.7);
+        assert_eq!(first_hour.precipitation_probability, Some(24));
+        assert_eq!(first_hour.wind_speed_10m, Some(12.7));
+        assert_eq!(first_hour.wind_direction_10m, Some(170));
+        assert_eq!(first_hour.wmo_code, Some(WmoCode::Overcast));
+        assert_eq!(forecast.hourly[&144].wmo_code, Some(WmoCode::Fog));
+        assert_eq!(forecast.hourly.len(), 168);
+        assert!(forecast.daily.is_empty());
+        assert!(forecast.nowcast.is_empty());
+    }
+
+    #[test]
+    fn passes_other_hourly_variables_through() {
+        let body = br#"{"hourly_units":{"time":"iso8601","pressure_msl":"hPa"},"hourly":{"time":["2023-11-19T00:00","2023-11-19T01:00"],"temperature_2m":[2.7,2.5],"pressure_msl":[1013.2,null],"is_day":[0,0]}}"#;
+        let forecast = parse_weather_data(body.to_vec(), 0).unwrap();
+        assert_eq!(forecast.hourly[&0].extras.get("pressure_msl"), Some(&1013.2));
+        assert_eq!(forecast.hourly[&0].extras.get("is_day"), Some(&0.0));
+        assert_eq!(forecast.hourly[&1].extras.get("pressure_msl"), None);
+        assert!(!forecast.hourly[&0].extras.contains_key("time"));
+        assert_eq!(forecast.hourly_units.get("pressure_msl").map(|u| u.as_str()), Some("hPa"));
+    }
+
+    #[test]
+    fn parses_daily_forecast_fixture() {
+        let daily_data = parse_weather_data(include_bytes!("../fixtures/forecast_with_synthetic_series.json").to_vec(), PAST_DAYS).unwrap().daily;
+        assert_eq!(daily_data.len(), 7);
+        assert_eq!(daily_data[&1].date, "2023-11-20");
+        assert_eq!(daily_data[&1].temperature_2m_max, Some(6.7));
+        assert_eq!(daily_data[&1].precipitation_sum, Some(5.4));
+        assert_eq!(daily_data[&1].precipitation_hours, Some(15.0));
+        assert_eq!(daily_data[&1].wind_speed_10m_max, Some(22.4));
+        assert_eq!(daily_data[&1].wmo_code, Some(WmoCode::SlightRainShowers));
+        assert_eq!(daily_data[&1].sunrise.as_deref(), Some("2023-11-20T06:00"));
+        assert_eq!(daily_data[&1].et0_fao_evapotranspiration, Some(0.38));
+    }
+
+    #[test]
+    fn keeps_yesterday_apart_from_the_forecast() {
+        let forecast = parse_weather_data(include_bytes!("../fixtures/forecast_with_synthetic_series.json").to_vec(), PAST_DAYS).unwrap();
+        let yesterday = forecast.yesterday.unwrap();
+        assert_eq!(yesterday.date, "2023-11-18");
+        assert_eq!(yesterday.temperature_2m_max, Some(6.6));
+        assert_eq!(forecast.past_hourly.len(), 24);
+        assert!(!forecast.hourly.is_empty());
+        assert_eq!(forecast.daily[&0].date, "2023-11-19");
+    }
+
+    #[test]
+    fn parses_nowcast_when_available() {
+        let forecast = parse_weather_data(include_bytes!("../fixtures/forecast_with_synthetic_series.json").to_vec(), PAST_DAYS).unwrap();
+        assert_eq!(forecast.nowcast.len(), 8);
+        assert_eq!(forecast.nowcast[4], Some(0.6));
+        let body = r#"{"hourly":{"temperature_2m":[1.0]}}"#;
+        assert!(parse_weather_data(body.as_bytes().to_vec(), 0).unwrap().nowcast.is_empty());
+    }
+
+    fn forecast_fixture() -> serde_json::Value {
+        serde_json::from_slice(include_bytes!("../fixtures/forecast_with_synthetic_series.json")).unwrap()
+    }
+
+    #[test]
+    fn skips_hours_without_temperature_and_keeps_partial_hours() {
+        let mut forecast = forecast_fixture();
+        forecast["hourly"]["temperature_2m"][PAST_DAYS * 24 + 3] = serde_json::Value::Null;
+        forecast["hourly"]["precipitation_probability"][PAST_DAYS * 24 + 4] = serde_json::Value::Null;
+        forecast["hourly"]["weather_code"][PAST_DAYS * 24 + 4] = serde_json::Value::Null;
+        let weather_data = parse_weather_data(serde_json::to_vec(&forecast).unwrap(), PAST_DAYS).unwrap().hourly;
+        assert!(!weather_data.contains_key(&3));
+        assert_eq!(weather_data[&4].precipitation_probability, None);
+        assert_eq!(weather_data[&4].wmo_code, None);
+        assert_eq!(weather_data[&4].wind_direction_10m, Some(229));
+    }
+
+    #[test]
+    fn parses_forecast_with_missing_field() {
+        let mut forecast = forecast_fixture();
+        forecast["hourly"].as_object_mut().unwrap().remove("weather_code");
+        let weather_data = parse_weather_data(serde_json::to_vec(&forecast).unwrap(), PAST_DAYS).unwrap().hourly;
+        assert_eq!(weather_data[&0].temperature_2m, 2.7);
+        assert!(weather_data.values().all(|hourly_data| hourly_data.wmo_code.is_none()));
+    }
+
+    #[test]
+    fn parses_forecast_of_any_length() {
+        let body = r#"{"hourly":{"temperature_2m":[1.0,1.5],"precipitation_probability":[0],"wind_speed_10m":[3.2,4.1],"wind_direction_10m":[90,95],"weather_code":[0,1]}}"#;
+        let weather_data = parse_weather_data(body.as_bytes().to_vec(), 0).unwrap().hourly;
+        assert_eq!(weather_data.len(), 2);
+        assert_eq!(weather_data[&1].precipitation_probability, None);
+        assert_eq!(weather_data[&1].wmo_code, Some(WmoCode::MainlyClear));
+    }
+
+    #[test]
+    fn fails_to_parse_empty_forecast() {
+        let body = r#"{"hourly":{"temperature_2m":[],"precipitation_probability":[],"wind_speed_10m":[],"wind_direction_10m":[],"weather_code":[]}}"#;
+        assert_eq!(
+            parse_weather_data(body.as_bytes().to_vec(), 0).err(),
+            Some(WeatherPalError::Parse(RequestKind::Weather, "no forecast data".to_owned()))
+        );
+    }
+
+    #[test]
+    fn fails_to_parse_invalid_json() {
+        assert!(parse_weather_data(b"<html>Bad Gateway</html>".to_vec(), 0).is_err());
+        assert!(parse_lat_lon_and_location(b"<html>Bad Gateway</html>".to_vec(), false).is_err());
+    }
+
+    #[test]
+    fn parses_geocode_fixture() {
+        let (latitude, longitude, location) = parse_lat_lon_and_location(include_bytes!("../fixtures/geocode.json").to_vec(), false).unwrap();
+        assert_eq!(latitude, 48.20849);
+        assert_eq!(longitude, 16.37208);
+        assert_eq!(location, "Vienna, Austria");
+    }
+
+    #[test]
+    fn prefers_the_largest_geocode_result() {
+        let body = br#"{"results":[
+            {"name":"Paris","latitude":33.66,"longitude":-95.55,"country":"United States","population":24171},
+            {"name":"Paris","latitude":48.85,"longitude":2.35,"country":"France","population":2138551},
+            {"name":"Paris","latitude":36.30,"longitude":-88.32,"country":"United States"}
+        ]}"#;
+        assert_eq!(parse_lat_lon_and_location(body.to_vec(), false).unwrap().2, "Paris, United States");
+        assert_eq!(parse_lat_lon_and_location(body.to_vec(), true).unwrap(), (48.85, 2.35, "Paris, France".to_owned()));
+    }
+
+    #[test]
+    fn parses_location_suggestions() {
+        let body = br#"{"results":[
+            {"name":"Sankt Gallen","latitude":47.42,"longitude":9.37,"country":"Switzerland","admin1":"Saint Gallen"},
+            {"name":"Vienna","latitude":48.2,"longitude":16.37,"country":"Austria","admin1":"Vienna"}
+        ]}"#;
+        let suggestions = parse_location_suggestions(body.to_vec()).unwrap();
+        assert_eq!(suggestions[0].2, "Sankt Gallen, Saint Gallen, Switzerland");
+        assert_eq!(suggestions[1], (48.2, 16.37, "Vienna, Austria".to_owned()));
+        assert!(parse_location_suggestions(include_bytes!("../fixtures/geocode_no_results.json").to_vec()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_elevation() {
+        assert_eq!(parse_elevation(br#"{"elevation":[2962.0]}"#.to_vec()), Ok(2962.0));
+        assert_eq!(parse_elevation(br#"{"elevation":[]}"#.to_vec()), Err(WeatherPalError::Parse(RequestKind::Elevation, "no elevation data".to_owned())));
+    }
+
+    #[test]
+    fn fails_to_parse_geocode_without_results() {
+        assert_eq!(
+            parse_lat_lon_and_location(include_bytes!("../fixtures/geocode_no_results.json").to_vec(), false).err(),
+            Some(WeatherPalError::GeocodeEmpty)
+        );
+    }
+}
@@ -0,0 +1,534 @@
+use chrono::NaiveDate;
+use std::ops::Range;
+
+use crate::air_quality::{AirQuality, Level};
+use crate::chart::bar_character;
+use crate::format::{beaufort, beaufort_severity, hour_of_day, time_of_day, wind_direction_arrow, wind_label};
+use crate::i18n::translate;
+use crate::marine::{tide, Tide};
+use crate::parse::HourlyData;
+use crate::stargazing::{moon_phase, observing_rating, rating_label};
+use crate::state::State;
+use crate::sun::{astronomical_twilight_minutes, compass_point, SunTimes};
+
+// presets for an activity, each requests the extra data it needs and shows it in the hourly view
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Profile {
+    Mountain,
+    Ski,
+    Surf,
+    Sailing,
+    Stargazing,
+    Allergy,
+}
+
+// the columns a profile adds to the hourly view, in this order
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProfileColumn {
+    Snow, // fresh snow and the depth on the ground
+    FreezingLevel,
+    WindAtAltitude, // on the summits, around 3000m
+    Visibility,
+    Swell, // height, period and where it comes from
+    Tide,
+    Wind, // speed, gusts, compass direction and beaufort force
+    Waves,
+    CloudCover,
+    Pollen, // the species with the most pollen in the air
+    AirQuality, // PM2.5 and ozone
+}
+
+impl Profile {
+    pub fn from_config(profile: &str) -> Option<Self> {
+        match profile.trim().to_lowercase().as_str() {
+            "mountain" => Some(Profile::Mountain),
+            "ski" => Some(Profile::Ski),
+            "surf" => Some(Profile::Surf),
+            "sailing" => Some(Profile::Sailing),
+            "stargazing" => Some(Profile::Stargazing),
+            "allergy" => Some(Profile::Allergy),
+            _ => None,
+        }
+    }
+    pub fn hourly_variables(&self) -> &'static [&'static str] {
+        match self {
+            Profile::Mountain => &["freezing_level_height"],
+            Profile::Ski => &["snowfall", "snow_depth", "freezing_level_height", "wind_speed_700hPa", "visibility"],
+            Profile::Surf => &[],
+            Profile::Sailing => &["wind_gusts_10m"],
+            Profile::Stargazing => &["cloud_cover"],
+            Profile::Allergy => &[],
+        }
+    }
+    pub fn columns(&self) -> &'static [ProfileColumn] {
+        match self {
+            Profile::Mountain => &[ProfileColumn::FreezingLevel],
+            Profile::Ski => &[ProfileColumn::Snow, ProfileColumn::FreezingLevel, ProfileColumn::WindAtAltitude, ProfileColumn::Visibility],
+            Profile::Surf => &[ProfileColumn::Swell, ProfileColumn::Tide],
+            Profile::Sailing => &[ProfileColumn::Wind, ProfileColumn::Waves],
+            Profile::Stargazing => &[ProfileColumn::CloudCover],
+            Profile::Allergy => &[ProfileColumn::Pollen, ProfileColumn::AirQuality],
+        }
+    }
+    // the sea state comes from the marine API, in a request of its own
+    pub fn uses_marine_data(&self) -> bool {
+        matches!(self, Profile::Surf | Profile::Sailing)
+    }
+    // pollen and pollutants come from the air quality API, in a request of its own
+    pub fn uses_air_quality_data(&self) -> bool {
+        matches!(self, Profile::Allergy)
+    }
+    // a curated view leaves out what doesn't matter for the activity
+    pub fn shows_activity_score(&self) -> bool {
+        !matches!(self, Profile::Ski | Profile::Surf | Profile::Sailing | Profile::Stargazing)
+    }
+    // replaced by the more detailed wind column, and a timeline of the wind under the table
+    pub fn shows_wind_details(&self) -> bool {
+        matches!(self, Profile::Sailing)
+    }
+    // a summary of the day's pollen and pollutants under the table
+    pub fn shows_allergy_briefing(&self) -> bool {
+        matches!(self, Profile::Allergy)
+    }
+    // a rating of the night ahead under the table
+    pub fn shows_observing_conditions(&self) -> bool {
+        matches!(self, Profile::Stargazing)
+    }
+}
+
+impl ProfileColumn {
+    // in the header of the hourly view, translated when shown
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProfileColumn::Snow => "Snow",
+            ProfileColumn::FreezingLevel => "Freezing",
+            ProfileColumn::WindAtAltitude => "Summit",
+            ProfileColumn::Visibility => "Visibility",
+            ProfileColumn::Swell => "Swell",
+            ProfileColumn::Tide => "Tide",
+            ProfileColumn::Wind => "Wind",
+            ProfileColumn::Waves => "Waves",
+            ProfileColumn::CloudCover => "Clouds",
+            ProfileColumn::Pollen => "Pollen",
+            ProfileColumn::AirQuality => "Air quality",
+        }
+    }
+}
+
+impl State {
+    pub(crate) fn profile_texts(&self, hour: usize, hourly_data: &HourlyData) -> Vec<(String, Option<usize>)> { // text + color
+        let Some(profile) = self.profile else {
+            return vec![];
+        };
+        let or_dash = |text: Option<String>| text.unwrap_or_else(|| "-".to_owned());
+        profile
+            .columns()
+            .iter()
+            .map(|column| match column {
+                ProfileColumn::Snow => {
+                    let snowfall = or_dash(hourly_data.snowfall.map(|s| self.locale.format_snow(s)));
+                    let snow_depth = or_dash(hourly_data.snow_depth.map(|d| self.locale.format_snow(d * 100.0)));
+                    let fresh_snow = hourly_data.snowfall.map(|s| s > 0.0).unwrap_or(false);
+                    (format!("❄ {} ⛷ {}", snowfall, snow_depth), if fresh_snow { Some(self.palette.highlight()) } else { None })
+                }
+                ProfileColumn::FreezingLevel => {
+                    // at or below the altitude of the location, precipitation comes down as snow
+                    let freezing_level = hourly_data.freezing_level_height;
+                    let snow_here = freezing_level.zip(self.elevation).map(|(freezing_level, elevation)| freezing_level <= elevation).unwrap_or(false);
+                    let text = format!("0° {}", or_dash(freezing_level.map(|f| self.locale.format_elevation(f))));
+                    (text, if snow_here { Some(self.palette.precipitation()) } else { None })
+                }
+                ProfileColumn::WindAtAltitude => {
+                    let (_, color) = wind_label(None, hourly_data.wind_speed_700hpa, &self.locale, self.palette);
+                    (format!("⛰ {}", or_dash(hourly_data.wind_speed_700hpa.map(|w| self.locale.format_wind_speed(w)))), color)
+                }
+                ProfileColumn::Visibility => (format!("👁 {}", or_dash(hourly_data.visibility.map(|v| self.locale.format_distance(v)))), None),
+                ProfileColumn::Wind => {
+                    let force = hourly_data.wind_speed_10m.map(beaufort);
+                    let direction = hourly_data.wind_direction_10m.map(|d| format!("{} {}°", compass_point(d as f64), d)).unwrap_or_else(|| "-".to_owned());
+                    let speed = or_dash(hourly_data.wind_speed_10m.map(|w| self.locale.format_wind_speed(w)));
+                    let gusts = or_dash(hourly_data.wind_gusts_10m.map(|g| self.locale.format_wind_speed(g)));
+                    let force_text = force.map(|f| format!("Bft {}", f)).unwrap_or_default();
+                    (format!("{} {} ({}) {}", direction, speed, gusts, force_text), force.and_then(|f| self.palette.severity_color(beaufort_severity(f))))
+                }
+                ProfileColumn::Waves => {
+                    let marine_data = self.marine_data.get(&hour).copied().unwrap_or_default();
+                    let height = or_dash(marine_data.wave_height.map(|h| self.locale.format_wave_height(h)));
+                    let period = or_dash(marine_data.wave_period.map(|p| format!("{}s", p.round())));
+                    (format!("〰 {} {}", height, period), Some(self.palette.precipitation()))
+                }
+                ProfileColumn::Swell => {
+                    let marine_data = self.marine_data.get(&hour).copied().unwrap_or_default();
+                    let height = or_dash(marine_data.swell_wave_height.map(|h| self.locale.format_wave_height(h)));
+                    let period = or_dash(marine_data.swell_wave_period.map(|p| format!("{}s", p.round())));
+                    (format!("🌊 {} {} {}", height, period, marine_data.swell_wave_direction.map(wind_direction_arrow).unwrap_or(' ')), Some(self.palette.precipitation()))
+                }
+                ProfileColumn::Tide => {
+                    let sea_level = |hour: Option<usize>| hour.and_then(|h| self.marine_data.get(&h)).and_then(|m| m.sea_level_height_msl);
+                    let Some(level) = sea_level(Some(hour)) else {
+                        return ("-".to_owned(), None);
+                    };
+                    // the turns of the tide stand out, surf spots often work best around one of them
+                    let (tide_text, color) = match tide(sea_level(hour.checked_sub(1)), level, sea_level(Some(hour + 1))) {
+                        Some(Tide::High) => (format!("▲ {}", translate(self.language, "high tide")), Some(self.palette.highlight())),
+                        Some(Tide::Low) => (format!("▼ {}", translate(self.language, "low tide")), Some(self.palette.highlight())),
+                        Some(Tide::Rising) => ("↑".to_owned(), None),
+                        Some(Tide::Falling) => ("↓".to_owned(), None),
+                        None => (String::new(), None),
+                    };
+                    (format!("{} {}", self.locale.format_wave_height(level), tide_text), color)
+                }
+                ProfileColumn::Pollen => {
+                    let Some(air_quality) = self.air_quality.get(&hour) else {
+                        return ("🌿 -".to_owned(), None);
+                    };
+                    // the species that is worst for allergies, others are in the briefing under the table
+                    match air_quality.pollen_levels().into_iter().max_by(|a, b| a.2.cmp(&b.2).then(a.1.total_cmp(&b.1))) {
+                        Some((name, grains, level)) => (format!("🌿 {} {:.0}", translate(self.language, name), grains), self.palette.severity_color(level.severity())),
+                        None => (format!("🌿 {}", translate(self.language, "none")), None),
+                    }
+                }
+                ProfileColumn::AirQuality => {
+                    let Some(air_quality) = self.air_quality.get(&hour) else {
+                        return ("-".to_owned(), None);
+                    };
+                    let level = air_quality.pm2_5_level(self.air_quality_thresholds).max(air_quality.ozone_level(self.air_quality_thresholds));
+                    // the worst hours of the day are flagged
+                    let worst = self.worst_air_quality_hours(hour / 24).map(|hours| hours.contains(&hour)).unwrap_or(false);
+                    let text = format!(
+                        "PM2.5 {} O₃ {}{}",
+                        air_quality.pm2_5.map(|p| format!("{:.0}", p)).unwrap_or_else(|| "-".to_owned()),
+                        air_quality.ozone.map(|o| format!("{:.0}", o)).unwrap_or_else(|| "-".to_owned()),
+                        if worst { " ⚠" } else { "" }
+                    );
+                    (text, self.palette.severity_color(level.severity()))
+                }
+                ProfileColumn::CloudCover => {
+                    let clear = hourly_data.cloud_cover.map(|c| c <= 20).unwrap_or(false);
+                    (format!("☁ {}", or_dash(hourly_data.cloud_cover.map(|c| self.locale.format_percentage(c)))), if clear { Some(self.palette.highlight()) } else { None })
+                }
+            })
+            .collect()
+    }
+    pub(crate) fn wind_timeline_text(&self, hour: usize) -> Option<String> {
+        // the wind over the next day at a glance, scaled to the strongest hour
+        if !self.profile?.shows_wind_details() {
+            return None;
+        }
+        let wind_speeds: Vec<f64> = self.weather_data.range(hour..hour + 24).map(|(_, hourly_data)| hourly_data.wind_speed_10m.unwrap_or(0.0)).collect();
+        if wind_speeds.is_empty() {
+            return None;
+        }
+        let max = wind_speeds.iter().copied().fold(0.0, f64::max);
+        let min = wind_speeds.iter().copied().fold(f64::MAX, f64::min);
+        let timeline: String = wind_speeds.iter().map(|w| if max > 0.0 { bar_character(w / max) } else { ' ' }).collect();
+        Some(format!("💨 {} ▕{}▏ Bft {}-{}", translate(self.language, "Next 24h"), timeline, beaufort(min), beaufort(max)))
+    }
+    pub(crate) fn worst_air_quality_hours(&self, day: usize) -> Option<Range<usize>> {
+        // from the first to the last hour of the day at its worst level, when that is at least moderate
+        let day_hours: Vec<(usize, Level)> = self.air_quality.range(day * 24..(day + 1) * 24).map(|(hour, air_quality)| (*hour, air_quality.level(self.air_quality_thresholds))).collect();
+        let worst = day_hours.iter().map(|(_, level)| *level).max().filter(|level| *level >= Level::Moderate)?;
+        let worst_hours: Vec<usize> = day_hours.iter().filter(|(_, level)| *level == worst).map(|(hour, _)| *hour).collect();
+        Some(*worst_hours.first()?..*worst_hours.last()? + 1)
+    }
+    pub(crate) fn allergy_briefing_text(&self, hour: usize) -> Option<(String, Level)> { // text, the worst level of the day
+        // eg. "🤧 Today: birch high, grass moderate · PM2.5 22 · O₃ 110 · worst 13:00-16:00"
+        if !self.profile?.shows_allergy_briefing() {
+            return None;
+        }
+        let day = hour / 24;
+        let day_hours: Vec<&AirQuality> = self.air_quality.range(day * 24..(day + 1) * 24).map(|(_, air_quality)| air_quality).collect();
+        if day_hours.is_empty() {
+            return None;
+        }
+        // the highest count of each species, most troublesome first
+        let mut pollen: Vec<(&str, f64, Level)> = vec![];
+        for (name, grains, level) in day_hours.iter().flat_map(|air_quality| air_quality.pollen_levels()) {
+            match pollen.iter_mut().find(|(n, ..)| *n == name) {
+                Some(species) if grains > species.1 => *species = (name, grains, level),
+                Some(_) => {}
+                None => pollen.push((name, grains, level)),
+            }
+        }
+        pollen.sort_by(|a, b| b.2.cmp(&a.2).then(b.1.total_cmp(&a.1)));
+        let pollen_text = if pollen.is_empty() {
+            format!("{} {}", translate(self.language, "pollen"), translate(self.language, "none"))
+        } else {
+            pollen.iter().map(|(name, _, level)| format!("{} {}", translate(self.language, name), translate(self.language, level.label()))).collect::<Vec<_>>().join(", ")
+        };
+        let max = |value: fn(&AirQuality) -> Option<f64>| day_hours.iter().filter_map(|air_quality| value(air_quality)).fold(None, |max: Option<f64>, v| Some(max.map_or(v, |max| max.max(v))));
+        let or_dash = |value: Option<f64>| value.map(|v| format!("{:.0}", v)).unwrap_or_else(|| "-".to_owned());
+        let mut text = format!("🤧 {}: {} · PM2.5 {} · O₃ {}", translate(self.language, "Today"), pollen_text, or_dash(max(|a| a.pm2_5)), or_dash(max(|a| a.ozone)));
+        if let Some(worst_hours) = self.worst_air_quality_hours(day) {
+            text.push_str(&format!(" · {} {:02}:00-{:02}:00", translate(self.language, "worst"), hour_of_day(worst_hours.start), hour_of_day(worst_hours.end)));
+        }
+        let level = day_hours.iter().map(|air_quality| air_quality.level(self.air_quality_thresholds)).max().unwrap_or(Level::None);
+        Some((text, level))
+    }
+    pub(crate) fn tonight(&self, hour: usize) -> Option<(usize, usize, bool, f64)> { // dusk, dawn, whether it gets fully dark, the average cloud cover in between
+        // from the end of astronomical twilight to its start in the morning, or from sunset to sunrise where it doesn't get that dark
+        let (latitude, _) = self.geolocation?;
+        let today = hour / 24;
+        let sunrise_today = SunTimes::from_daily(self.daily_data.get(&today)?)?.sunrise;
+        // before sunrise, tonight is the night that is still going on
+        let day = if hour % 24 < sunrise_today / 60 { today.saturating_sub(1) } else { today };
+        let evening = self.daily_data.get(&day)?;
+        let sunset = SunTimes::from_daily(evening)?.sunset;
+        let sunrise = self.daily_data.get(&(day + 1)).and_then(SunTimes::from_daily).map(|sun_times| sun_times.sunrise).unwrap_or(sunrise_today);
+        let twilight = NaiveDate::parse_from_str(&evening.date, "%Y-%m-%d").ok().and_then(|date| astronomical_twilight_minutes(latitude, date));
+        let (dusk, dawn) = match twilight {
+            Some(twilight) => (sunset + twilight, sunrise.saturating_sub(twilight)),
+            None => (sunset, sunrise),
+        };
+        let cloud_cover: Vec<usize> = self.weather_data.range(day * 24 + dusk / 60..(day + 1) * 24 + dawn / 60).filter_map(|(_, hourly_data)| hourly_data.cloud_cover).collect();
+        if cloud_cover.is_empty() {
+            return None;
+        }
+        Some((dusk, dawn, twilight.is_some(), cloud_cover.iter().sum::<usize>() as f64 / cloud_cover.len() as f64))
+    }
+    pub(crate) fn observing_conditions_text(&self, hour: usize) -> Option<String> {
+        // eg. "🔭 Tonight: good 7/10 · ☁ 15% · ☾ 23% waxing · dark 19:32-04:41"
+        if !self.profile?.shows_observing_conditions() {
+            return None;
+        }
+        let (dusk, dawn, fully_dark, cloud_cover) = self.tonight(hour)?;
+        let (moon_illumination, waxing) = moon_phase(self.clock?);
+        let rating = observing_rating(cloud_cover, moon_illumination, fully_dark);
+        let darkness = if fully_dark {
+            format!("{} {}-{}", translate(self.language, "dark"), time_of_day(dusk), time_of_day(dawn))
+        } else {
+            translate(self.language, "no full darkness").to_owned()
+        };
+        Some(format!(
+            "🔭 {}: {} {}/10 · ☁ {} · ☾ {} {} · {}",
+            translate(self.language, "Tonight"),
+            translate(self.language, rating_label(rating)),
+            rating,
+            self.locale.format_percentage(cloud_cover.round() as usize),
+            self.locale.format_percentage((moon_illumination * 100.0).round() as usize),
+            translate(self.language, if waxing { "waxing" } else { "waning" }),
+            darkness
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::marine::MarineData;
+    use crate::render::tests::{render_snapshot, state_with_forecast};
+
+    #[test]
+    fn parses_profiles() {
+        assert_eq!(Profile::from_config("mountain"), Some(Profile::Mountain));
+        assert_eq!(Profile::from_config(" Mountain "), Some(Profile::Mountain));
+        assert_eq!(Profile::from_config("ski"), Some(Profile::Ski));
+        assert_eq!(Profile::from_config("SURF"), Some(Profile::Surf));
+        assert_eq!(Profile::from_config("sailing"), Some(Profile::Sailing));
+        assert_eq!(Profile::from_config("Stargazing"), Some(Profile::Stargazing));
+        assert_eq!(Profile::from_config("allergy"), Some(Profile::Allergy));
+        assert_eq!(Profile::from_config("beach"), None);
+    }
+
+    #[test]
+    fn shows_the_freezing_level_in_the_mountain_profile() {
+        let mut state = State {
+            profile: Some(Profile::Mountain),
+            elevation: Some(1800.0),
+            ..state_with_forecast()
+        };
+        for (hour, hourly_data) in state.weather_data.iter_mut() {
+            hourly_data.freezing_level_height = Some(3000.0 - *hour as f64 * 100.0);
+        }
+        state.weather_data.get_mut(&14).unwrap().freezing_level_height = None;
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ⛰ 1800m  ☀ 9h 10m (-2 min)",
+            "          Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "               Sun 19 Sky           Temp      Rain    Wind       Score Freezing",
+            "               11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5    0° 2000m",
+            "               12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4    0° 1900m",
+            "               13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5    0° 1800m",
+            "               14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6    0° 1700m",
+            "               15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7    0° -",
+            "               16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6    0° 1500m",
+            "               17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6    0° 1400m",
+            "               18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4    0° 1300m",
+            "                    Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                            Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 90, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn curates_the_hourly_view_for_skiing() {
+        let mut state = State {
+            profile: Some(Profile::Ski),
+            elevation: Some(1800.0),
+            ..state_with_forecast()
+        };
+        for (hour, hourly_data) in state.weather_data.iter_mut() {
+            hourly_data.snowfall = Some(if *hour % 3 == 0 { 1.4 } else { 0.0 });
+            hourly_data.snow_depth = Some(0.85);
+            hourly_data.freezing_level_height = Some(1600.0);
+            hourly_data.wind_speed_700hpa = Some(*hour as f64 * 4.0);
+            hourly_data.visibility = Some(if *hour < 13 { 600.0 } else { 24140.0 });
+        }
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                            Vienna, Austria  ⛰ 1800m  ☀ 9h 10m (-2 min)",
+            "               Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "      Sun 19 Sky           Temp      Rain    Wind       Snow            Freezing Summit   Visibility",
+            "      11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ❄ 0cm ⛷ 85cm   0° 1600m ⛰ 40kph 👁 600m",
+            "      12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ❄ 0cm ⛷ 85cm   0° 1600m ⛰ 44kph 👁 600m",
+            "      13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ❄ 1.4cm ⛷ 85cm 0° 1600m ⛰ 48kph 👁 600m",
+            "      14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ❄ 0cm ⛷ 85cm   0° 1600m ⛰ 52kph 👁 24.1km",
+            "      15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ❄ 0cm ⛷ 85cm   0° 1600m ⛰ 56kph 👁 24.1km",
+            "      16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ❄ 1.4cm ⛷ 85cm 0° 1600m ⛰ 60kph 👁 24.1km",
+            "      17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ❄ 0cm ⛷ 85cm   0° 1600m ⛰ 64kph 👁 24.1km",
+            "      18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ❄ 0cm ⛷ 85cm   0° 1600m ⛰ 68kph 👁 24.1km",
+            "                         Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                                 Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 100, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_swell_and_tide_in_the_surf_profile() {
+        let mut state = State {
+            profile: Some(Profile::Surf),
+            ..state_with_forecast()
+        };
+        for hour in 9..19 {
+            let marine_data = MarineData {
+                swell_wave_height: Some(1.0 + hour as f64 / 10.0),
+                swell_wave_period: Some(11.2),
+                swell_wave_direction: Some(290),
+                sea_level_height_msl: Some([0.2, 0.5, 0.7, 0.8, 0.6, 0.2, -0.3, -0.6, -0.7, -0.5][hour - 9]),
+                ..Default::default()
+            };
+            state.marine_data.insert(hour, marine_data);
+        }
+        state.marine_data.get_mut(&15).unwrap().swell_wave_height = None;
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                            Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "          Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "      Sun 19 Sky           Temp      Rain    Wind       Swell         Tide",
+            "      11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph 🌊 2m 11s →   0.5m ↑",
+            "      12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph 🌊 2.1m 11s → 0.7m ↑",
+            "      13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  🌊 2.2m 11s → 0.8m ▲ high tide",
+            "      14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  🌊 2.3m 11s → 0.6m ↓",
+            "      15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  🌊 2.4m 11s → 0.2m ↓",
+            "      16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  🌊 - 11s →    -0.3m ↓",
+            "      17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  🌊 2.6m 11s → -0.6m ↓",
+            "      18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  🌊 2.7m 11s → -0.7m ▼ low tide",
+            "                    Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                            Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 90, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn foregrounds_the_wind_in_the_sailing_profile() {
+        let mut state = State {
+            profile: Some(Profile::Sailing),
+            ..state_with_forecast()
+        };
+        for (hour, hourly_data) in state.weather_data.iter_mut() {
+            hourly_data.wind_speed_10m = Some(*hour as f64 * 2.5);
+            hourly_data.wind_gusts_10m = Some(*hour as f64 * 4.0);
+        }
+        state.marine_data.insert(10, MarineData { wave_height: Some(0.8), wave_period: Some(4.4), ..Default::default() });
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                            Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "          Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 57.5kph",
+            "       Sun 19 Sky           Temp      Rain    Wind                          Waves",
+            "       11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   SW 224° 25kph (40kph) Bft 4   〰 0.8m 4s",
+            "       12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  SW 229° 27.5kph (44kph) Bft 4 〰 - -",
+            "       13:00  CLEAR SKY     9.1  °C → 💧 10%  S 190° 30kph (48kph) Bft 5    〰 - -",
+            "       14:00  MAINLY CLEAR  9.2  °C → 💧 7%   S 192° 32.5kph (52kph) Bft 5  〰 - -",
+            "       15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   S 201° 35kph (56kph) Bft 5    〰 - -",
+            "       16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   S 188° 37.5kph (60kph) Bft 5  〰 - -",
+            "       17:00  MAINLY CLEAR  9.3  °C → 💧 0%   SW 217° 40kph (64kph) Bft 6   〰 - -",
+            "       18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  S 186° 42.5kph (68kph) Bft 6  〰 - -",
+            "                      💨 Next 24h ▕▂▃▃▃▃▄▄▄▄▅▅▅▅▆▆▆▆▇▇▇▇███▏ Bft 4-9",
+            "                            Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 90, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn rates_tonights_observing_conditions_in_the_stargazing_profile() {
+        let mut state = State {
+            profile: Some(Profile::Stargazing),
+            geolocation: Some((48.21, 16.37)),
+            clock: Some(NaiveDate::from_ymd_opt(2023, 11, 19).unwrap().and_hms_opt(10, 0, 0).unwrap().and_utc()),
+            ..state_with_forecast()
+        };
+        for (hour, hourly_data) in state.weather_data.iter_mut() {
+            hourly_data.cloud_cover = Some(if *hour < 20 { 80 } else { 10 });
+        }
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                            Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "          Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "                 Sun 19 Sky           Temp      Rain    Wind       Clouds",
+            "                 11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ☁ 80%",
+            "                 12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ☁ 80%",
+            "                 13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ☁ 80%",
+            "                 14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ☁ 80%",
+            "                 15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ☁ 80%",
+            "                 16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ☁ 80%",
+            "                 17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ☁ 80%",
+            "                 18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ☁ 80%",
+            "             🔭 Tonight: fair 5/10 · ☁ 33% · ☾ 37% waxing · dark 17:58-05:10",
+            "                            Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 90, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn briefs_on_pollen_and_air_quality_in_the_allergy_profile() {
+        let mut state = State {
+            profile: Some(Profile::Allergy),
+            ..state_with_forecast()
+        };
+        for hour in 0..24 {
+            let birch = if (13..16).contains(&hour) { 140.0 } else { 40.0 };
+            let grass = if hour < 12 { 0.0 } else { 6.0 };
+            let air_quality = AirQuality { pm2_5: Some(8.0 + hour as f64), ozone: Some(60.0 + hour as f64 * 2.0), pollen: vec![None, Some(birch), Some(grass), None, None, None] };
+            state.air_quality.insert(hour, air_quality);
+        }
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "                ⚠ Limit outdoor exercise 11:00-01:00 (PM2.5 up to 31µg/m³)",
+            "                            Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "          Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "      Sun 19 Sky           Temp      Rain    Wind       Score Pollen       Air quality",
+            "      11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5    🌿 birch 40  PM2.5 18 O₃ 80",
+            "      12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4    🌿 birch 40  PM2.5 19 O₃ 82",
+            "      13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5    🌿 birch 40  PM2.5 20 O₃ 84",
+            "      14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6    🌿 birch 140 PM2.5 21 O₃ 86",
+            "      15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7    🌿 birch 140 PM2.5 22 O₃ 88",
+            "      16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6    🌿 birch 140 PM2.5 23 O₃ 90",
+            "      17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6    🌿 birch 40  PM2.5 24 O₃ 92",
+            "      18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4    🌿 birch 40  PM2.5 25 O₃ 94",
+            "          🤧 Today: birch high, grass low · PM2.5 31 · O₃ 106 · worst 14:00-17:00",
+            "                            Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 90, 10), expected.join("\n"));
+    }
+}
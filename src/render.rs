@@ -0,0 +1,2510 @@
+use zellij_tile::prelude::*;
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::air_quality::{unhealthy_air, Level};
+use crate::aurora::{aurora_chance, max_kp, AuroraChance};
+use crate::chart::{bar_character, BrailleCanvas, Canvas};
+use crate::climate::anomaly;
+use crate::format::{controls_text_with_keys_highlighted, daylight_text, error_message, hour_of_day, temperature_gradient_color, temperature_trend_arrow, time_of_day, colored_text, wind_label, wmo_code_label, wmo_code_to_text};
+use crate::i18n::{translate, Language};
+use crate::parse::HourlyData;
+use crate::settings::Setting;
+use crate::score::{activity_score, comfort};
+use crate::state::{SortOrder, State, View};
+use crate::template::{render_template, DEFAULT_FORMAT};
+use crate::sun::{compass_point, sun_position, SunTimes};
+use crate::width::{display_width, wrap_words};
+use crate::wmo::{lightning_risk, Severity};
+
+const NOWCAST_FULL_BAR_MM: f64 = 2.0; // per 15 minutes, heavy rain
+const COMPACT_ROWS: usize = 2; // panes this short only show the one-line summary
+
+pub trait Renderer {
+    fn print_text(&mut self, text: Text, x: usize, y: usize);
+    fn print_table(&mut self, table: Table, x: usize, y: usize);
+    fn print_ribbon(&mut self, text: Text, x: usize, y: usize);
+}
+
+pub struct ZellijRenderer;
+
+impl Renderer for ZellijRenderer {
+    fn print_text(&mut self, text: Text, x: usize, y: usize) {
+        print_text_with_coordinates(text, x, y, None, None);
+    }
+    fn print_table(&mut self, table: Table, x: usize, y: usize) {
+        print_table_with_coordinates(table, x, y, None, None);
+    }
+    fn print_ribbon(&mut self, text: Text, x: usize, y: usize) {
+        print_ribbon_with_coordinates(text, x, y, None, None);
+    }
+}
+
+impl State {
+    pub fn render_with<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize) {
+        let highlight = self.palette.highlight();
+        if let Some(scroll) = self.raw_response_scroll {
+            // shown over everything else, errors included, as that's usually when it's needed
+            self.render_raw_response(renderer, rows, cols, scroll);
+        } else if self.permission_rationale {
+            self.render_permission_rationale(renderer, rows, cols);
+        } else if let Some(cursor) = self.settings_cursor {
+            self.render_settings(renderer, rows, cols, cursor);
+        } else if let Some(error) = &self.error {
+            let error = error_message(error, self.language);
+            renderer.print_text(Text::new(&error).color_range(highlight, ..), (cols / 2).saturating_sub(display_width(&error) / 2), rows / 2);
+            self.render_controls_footer(renderer, rows);
+        } else if let Some(location_being_typed) = &self.location_being_typed {
+            // the cursor is drawn between characters while editing in the middle of the line
+            let (before_cursor, after_cursor) = location_being_typed.text().split_at(location_being_typed.text().char_indices().nth(location_being_typed.cursor()).map(|(i, _)| i).unwrap_or(location_being_typed.text().len()));
+            let cursor = if after_cursor.is_empty() { "_" } else { "▏" };
+            let location_being_typed = format!("{}: {}{}{}", translate(self.language, "Enter desired location"), before_cursor, cursor, after_cursor);
+            renderer.print_text(Text::new(&location_being_typed).color_range(highlight, ..), (cols / 2).saturating_sub(display_width(&location_being_typed) / 2), rows / 2);
+            if let Some(query) = &self.location_not_found {
+                let not_found = translate(self.language, "Couldn't find '{}' — check spelling or try adding a country").replace("{}", query);
+                renderer.print_text(Text::new(&not_found), (cols / 2).saturating_sub(display_width(&not_found) / 2), (rows / 2).saturating_sub(2));
+            }
+            // matches for what is being typed, the one picked with the arrow keys is entered instead of the text
+            let width = self.typeahead.iter().map(|(_, _, location)| display_width(location) + 2).max().unwrap_or(0);
+            for (i, (_, _, location)) in self.typeahead.iter().enumerate() {
+                let typeahead_match = if self.typeahead_cursor == Some(i) { Text::new(format!("> {}", location)).color_range(highlight, ..) } else { Text::new(format!("  {}", location)) };
+                renderer.print_text(typeahead_match, (cols / 2).saturating_sub(width / 2), rows / 2 + 2 + i);
+            }
+        } else if !self.location_suggestions.is_empty() {
+            self.render_location_suggestions(renderer, rows, cols);
+            self.render_controls_footer(renderer, rows);
+        } else if self.picking_day && !self.weather_data.is_empty() {
+            self.render_day_picker(renderer, rows, cols);
+            self.render_controls_footer(renderer, rows);
+        } else if self.fetching_data {
+            let fetching_data_text = translate(self.language, "Fetching data...");
+            renderer.print_text(Text::new(fetching_data_text).color_range(highlight, ..), (cols / 2).saturating_sub(display_width(fetching_data_text) / 2), rows / 2);
+        } else if self.weather_data.is_empty() {
+            let controls_text = translate(self.language, "Press <ENTER> to run, <Ctrl-w> to enter a new location");
+            renderer.print_text(controls_text_with_keys_highlighted(controls_text, highlight), (cols / 2).saturating_sub(display_width(controls_text) / 2), rows / 2);
+        } else if self.screen_reader {
+            self.render_linear(renderer, rows, cols, hour);
+            self.render_controls_footer(renderer, rows);
+        } else if rows <= COMPACT_ROWS || self.compact {
+            // eg. a pane of a single line next to the status bar, with only the summary of the current hour
+            let summary = self.summary_line(hour);
+            renderer.print_text(Text::new(&summary), (cols / 2).saturating_sub(display_width(&summary) / 2), 0);
+        } else {
+            if !matches!(self.view, View::Chart | View::Map | View::Trip | View::Team | View::Aviation) {
+                self.render_location_header(renderer, rows, cols);
+                self.render_alert_banner(renderer, rows, cols, hour);
+            }
+            self.render_controls_footer(renderer, rows);
+            self.render_footer_status(renderer, rows, cols, hour);
+            if shows_tab_bar(rows) {
+                self.render_tab_bar(renderer);
+            }
+            match self.view {
+                View::Hourly if self.compare_index.is_some() => self.render_comparison_table(renderer, rows, cols, hour),
+                View::Hourly => self.render_hourly_table(renderer, rows, cols, hour),
+                View::Daily => self.render_daily_table(renderer, rows, cols, hour),
+                View::Week => self.render_week_grid(renderer, rows, cols, hour),
+                View::Chart => self.render_chart(renderer, rows, cols, hour),
+                View::Map => self.render_map(renderer, rows, cols),
+                View::Trip => self.render_trip(renderer, rows, cols),
+                View::Team => self.render_team(renderer, rows, cols),
+                View::Aviation => self.render_aviation(renderer, rows, cols),
+            }
+        }
+        if self.show_debug_overlay {
+            self.render_debug_overlay(renderer);
+        }
+    }
+    fn render_location_header<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize) {
+        if self.hide_location {
+            return;
+        }
+        let location = self.location_header().cloned();
+        let location_len = location.as_ref().map(|location| location.chars().count()).unwrap_or(0);
+        let elevation = self.elevation.map(|e| format!("⛰ {}", self.locale.format_elevation(e)));
+        let header_parts: Vec<String> = location.into_iter().chain(elevation).chain(self.daylight_text()).collect();
+        if header_parts.is_empty() {
+            return;
+        }
+        let header = header_parts.join(self.palette.separator());
+        let text = Text::new(&header)
+            .color_range(self.palette.highlight(), ..location_len)
+            .color_range(self.palette.golden_hour(), location_len..);
+        renderer.print_text(text, (cols / 2).saturating_sub(display_width(&header) / 2), self.location_header_row(rows));
+    }
+    fn location_header_row(&self, rows: usize) -> usize {
+        if self.view == View::Hourly { self.hourly_layout(rows).0.saturating_sub(2) } else { (rows / 2).saturating_sub(6) }
+    }
+    fn render_alert_banner<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize) {
+        // active warnings right above the location header, the most severe first and colored by it,
+        // followed by what to do about unhealthy air
+        let mut alerts: Vec<_> = self.alerts.iter().collect();
+        alerts.sort_by_key(|alert| std::cmp::Reverse(alert.level));
+        let mut events: Vec<String> = vec![];
+        for alert in &alerts {
+            if !events.contains(&alert.event) {
+                events.push(alert.event.clone());
+            }
+        }
+        let air_quality_guidance = self.air_quality_guidance_text(hour);
+        let severities = alerts.first().map(|alert| alert.level.severity()).into_iter().chain(air_quality_guidance.as_ref().map(|(_, level)| level.severity()));
+        let (Some(severity), Some(row)) = (severities.max_by_key(|severity| *severity as usize), self.location_header_row(rows).checked_sub(1)) else {
+            return;
+        };
+        if shows_tab_bar(rows) && row == 0 {
+            return;
+        }
+        events.extend(air_quality_guidance.map(|(guidance, _)| guidance));
+        let banner = format!("⚠ {}{}", events.join(" · "), self.palette.severity_marker(severity));
+        let (text, banner_len) = colored_text(banner, self.palette.severity_color(severity));
+        renderer.print_text(text, (cols / 2).saturating_sub(banner_len / 2), row);
+    }
+    fn air_quality_guidance_text(&self, hour: usize) -> Option<(String, Level)> {
+        // eg. "Limit outdoor exercise 14:00-18:00 (ozone up to 130µg/m³)"
+        let unhealthy_air = unhealthy_air(&self.air_quality, hour, self.air_quality_thresholds)?;
+        let advice = if unhealthy_air.level == Level::High { "Avoid outdoor exercise" } else { "Limit outdoor exercise" };
+        let text = format!(
+            "{} {:02}:00-{:02}:00 ({} {} {:.0}µg/m³)",
+            translate(self.language, advice),
+            hour_of_day(unhealthy_air.hours.start),
+            hour_of_day(unhealthy_air.hours.end),
+            translate(self.language, unhealthy_air.pollutant),
+            translate(self.language, "up to"),
+            unhealthy_air.peak
+        );
+        Some((text, unhealthy_air.level))
+    }
+    fn daylight_text(&self) -> Option<String> {
+        // for the day shown in the hourly view, compared with the day before it
+        let day = if self.view == View::Hourly { self.day_offset } else { 0 };
+        let sun_times = self.daily_data.get(&day).and_then(SunTimes::from_daily)?;
+        let previous_day = match day.checked_sub(1) {
+            Some(previous_day) => self.daily_data.get(&previous_day),
+            None => self.yesterday.as_ref(),
+        };
+        let previous_daylight = previous_day.and_then(SunTimes::from_daily).map(|previous_sun_times| previous_sun_times.daylight_minutes());
+        Some(daylight_text(sun_times.daylight_minutes(), previous_daylight))
+    }
+    // plain sentences from the left edge, one per line, for screen readers rather than the eye
+    fn render_linear<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize) {
+        let degrees = translate(self.language, "degrees");
+        let mut lines = vec![];
+        let (day_min, day_max) = self.daily_temperature_range(hour);
+        if day_min <= day_max {
+            let (precipitation, _) = self.daily_precipitation_and_peak_wind(hour);
+            let location = self.location_header().map(|location| format!("{}. ", location)).unwrap_or_default();
+            lines.extend(wrap_words(&format!(
+                "{}{} {} {}, {} {} {}, {} {}.",
+                location,
+                translate(self.language, "High"),
+                self.locale.format_temperature(day_max),
+                degrees,
+                translate(self.language, "Low").to_lowercase(),
+                self.locale.format_temperature(day_min),
+                degrees,
+                self.locale.format_precipitation(precipitation),
+                translate(self.language, "of precipitation")
+            ), cols));
+        }
+        // whole sentences only, as many as fit above the footer once wrapped to the pane
+        let first_hour = self.first_displayed_hour(hour);
+        for (hour, hourly_data) in self.weather_data.range(first_hour..) {
+            let mut sentence = vec![format!("{:02}:00", hour_of_day(*hour)), format!("{} {}", self.locale.format_temperature(hourly_data.temperature_2m), degrees)];
+            if let Some(wmo_code) = hourly_data.wmo_code {
+                sentence.push(translate(self.language, wmo_code.description()).to_lowercase());
+            }
+            if let Some(precipitation_probability) = hourly_data.precipitation_probability {
+                sentence.push(format!("{} {}", precipitation_probability, translate(self.language, "percent chance of precipitation")));
+            }
+            if let Some(wind_speed) = hourly_data.wind_speed_10m {
+                let direction = hourly_data.wind_direction_10m.map(|d| format!(" {} {}", translate(self.language, "blowing from"), compass_point(d as f64))).unwrap_or_default();
+                sentence.push(format!("{} {}{}", translate(self.language, "wind"), self.locale.format_wind_speed(wind_speed), direction));
+            }
+            let sentence_lines = wrap_words(&format!("{}.", sentence.join(", ")), cols);
+            if lines.len() + sentence_lines.len() + 1 > rows {
+                break;
+            }
+            lines.extend(sentence_lines);
+        }
+        for (row, line) in lines.iter().enumerate().take(rows.saturating_sub(1)) {
+            renderer.print_text(Text::new(line), 0, row);
+        }
+    }
+    // the one-line summary of the compact mode and the pane title, as set with format
+    pub(crate) fn summary_line(&self, hour: usize) -> String {
+        let mut fields = BTreeMap::new();
+        if let Some(hourly_data) = self.weather_data.get(&hour) {
+            fields.insert("temp", self.locale.format_temperature(hourly_data.temperature_2m));
+            fields.insert("unit", self.locale.temperature_symbol().to_owned());
+            if let Some(wmo_code) = hourly_data.wmo_code {
+                fields.insert("icon", wmo_code.icon().to_owned());
+                fields.insert("desc", translate(self.language, wmo_code.description()).to_owned());
+            }
+            if let Some(precipitation_probability) = hourly_data.precipitation_probability {
+                fields.insert("precip", precipitation_probability.to_string());
+            }
+            if let Some(wind_speed) = hourly_data.wind_speed_10m {
+                fields.insert("wind", self.locale.format_wind_speed(wind_speed));
+            }
+            let (day_min, day_max) = self.daily_temperature_range(hour);
+            if day_min <= day_max {
+                fields.insert("high", self.locale.format_temperature(day_max));
+                fields.insert("low", self.locale.format_temperature(day_min));
+            }
+        }
+        if let Some(location) = self.location_header() {
+            fields.insert("location", location.clone());
+        }
+        render_template(self.format.as_deref().unwrap_or(DEFAULT_FORMAT), &fields)
+    }
+    fn location_header(&self) -> Option<&String> {
+        if self.hide_location {
+            None
+        } else {
+            self.location_label.as_ref().or(self.weather_location.as_ref())
+        }
+    }
+    fn render_location_suggestions<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize) {
+        let title = translate(self.language, "No matching location found, did you mean:");
+        let suggestions: Vec<String> = self.location_suggestions.iter().enumerate().map(|(i, (_, _, location))| format!("<{}> {}", i + 1, location)).collect();
+        let width = suggestions.iter().map(|s| display_width(s)).max().unwrap_or(0);
+        let top = (rows / 2).saturating_sub((suggestions.len() + 2) / 2);
+        renderer.print_text(Text::new(title).color_range(self.palette.highlight(), ..), (cols / 2).saturating_sub(display_width(title) / 2), top);
+        for (i, suggestion) in suggestions.iter().enumerate() {
+            renderer.print_text(controls_text_with_keys_highlighted(suggestion, self.palette.highlight()), (cols / 2).saturating_sub(width / 2), top + 2 + i);
+        }
+    }
+    fn render_day_picker<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize) {
+        let title = translate(self.language, "Jump to day:");
+        let days: Vec<String> = self.pickable_days().iter().enumerate().map(|(i, date)| format!("<{}> {}", i + 1, day_label(date, self.language))).collect();
+        let width = days.iter().map(|d| display_width(d)).max().unwrap_or(0);
+        let top = (rows / 2).saturating_sub((days.len() + 2) / 2);
+        renderer.print_text(Text::new(title).color_range(self.palette.highlight(), ..), (cols / 2).saturating_sub(display_width(title) / 2), top);
+        for (i, day) in days.iter().enumerate() {
+            let text = controls_text_with_keys_highlighted(day, self.palette.highlight());
+            let text = if i == self.day_offset { text.selected() } else { text };
+            renderer.print_text(text, (cols / 2).saturating_sub(width / 2), top + 2 + i);
+        }
+    }
+    fn render_permission_rationale<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize) {
+        let highlight = self.palette.highlight();
+        let title = translate(self.language, "Weather-Pal will ask for permission to:");
+        let reasons = [
+            translate(self.language, "• access the web, to find the location and fetch its forecast from Open-Meteo"),
+            translate(self.language, "• run commands: timedatectl to find the location from the system timezone, and the browser with o"),
+            translate(self.language, "• change the application state, only to close this pane with q or Esc and set its title"),
+        ];
+        let controls_text = translate(self.language, "<ENTER> continue, <n> continue without running commands and type the location");
+        let width = reasons.iter().map(|r| display_width(r)).max().unwrap_or(0);
+        let top = (rows / 2).saturating_sub((reasons.len() + 4) / 2);
+        renderer.print_text(Text::new(title).color_range(highlight, ..), (cols / 2).saturating_sub(display_width(title) / 2), top);
+        for (i, reason) in reasons.iter().enumerate() {
+            renderer.print_text(Text::new(reason), (cols / 2).saturating_sub(width / 2), top + 2 + i);
+        }
+        renderer.print_text(controls_text_with_keys_highlighted(controls_text, highlight), (cols / 2).saturating_sub(display_width(controls_text) / 2), top + 3 + reasons.len());
+    }
+    fn render_settings<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, cursor: usize) {
+        let highlight = self.palette.highlight();
+        let title = translate(self.language, "Settings");
+        let name_width = Setting::ALL.iter().map(|s| display_width(translate(self.language, s.name()))).max().unwrap_or(0);
+        let settings: Vec<String> = Setting::ALL
+            .iter()
+            .map(|setting| {
+                let value = match (setting, self.setting_value(*setting).as_str()) {
+                    (Setting::RefreshInterval, "0") => translate(self.language, "off").to_owned(),
+                    (Setting::RefreshInterval, minutes) => format!("{}m", minutes),
+                    (Setting::Soil | Setting::TimezoneCommand, "true") => translate(self.language, "on").to_owned(),
+                    (Setting::Soil | Setting::TimezoneCommand, _) => translate(self.language, "off").to_owned(),
+                    (_, value) => value.to_owned(),
+                };
+                let name = translate(self.language, setting.name());
+                format!("{}{}  ◂ {} ▸", name, " ".repeat(name_width - display_width(name)), value)
+            })
+            .collect();
+        let width = settings.iter().map(|s| display_width(s)).max().unwrap_or(0);
+        let top = (rows / 2).saturating_sub((settings.len() + 2) / 2);
+        renderer.print_text(Text::new(title).color_range(highlight, ..), (cols / 2).saturating_sub(display_width(title) / 2), top);
+        for (i, setting) in settings.iter().enumerate() {
+            let text = Text::new(setting).color_range(highlight, ..name_width);
+            let text = if i == cursor { text.selected() } else { text };
+            renderer.print_text(text, (cols / 2).saturating_sub(width / 2), top + 2 + i);
+        }
+        let controls_text = translate(self.language, "<↓↑> select, <←→> change, <Esc> close");
+        renderer.print_text(controls_text_with_keys_highlighted(controls_text, highlight), 0, rows);
+    }
+    fn render_controls_footer<R: Renderer>(&self, renderer: &mut R, rows: usize) {
+        if self.hide_controls {
+            return;
+        }
+        let controls_text = translate(self.language, "Press <ENTER> to reload, <Ctrl-w> to enter a new location");
+        renderer.print_text(controls_text_with_keys_highlighted(controls_text, self.palette.highlight()), 0, rows);
+    }
+    fn render_footer_status<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize) {
+        // the position in the forecast, the age of the forecast and when the next update is due,
+        // right aligned in the footer unless it would run into the controls
+        let position = self.position_text(rows, hour);
+        let dry_window = if self.show_dry_window { Some(self.dry_window_text(self.next_dry_window(hour))) } else { None };
+        let age = self.data_age_minutes.map(|age_minutes| format!("↻ {}m", age_minutes));
+        let countdown = self.minutes_until_refresh.map(|minutes_until_refresh| format!("{} {}m", translate(self.language, "next update in"), minutes_until_refresh));
+        let status = position.iter().chain(dry_window.iter()).chain(age.iter()).chain(countdown.iter()).cloned().collect::<Vec<String>>().join("  ");
+        let age_start = position.iter().chain(dry_window.iter()).map(|p| p.chars().count() + 2).sum::<usize>();
+        let controls_len = if self.hide_controls { 0 } else { display_width(translate(self.language, "Press <ENTER> to reload, <Ctrl-w> to enter a new location")) + 1 };
+        if status.is_empty() || controls_len + display_width(&status) > cols {
+            return;
+        }
+        let mut text = Text::new(&status);
+        if let (Some(age), Some(age_minutes)) = (&age, self.data_age_minutes) {
+            text = text.color_range(self.palette.data_age_color(age_minutes, self.refresh_minutes()), age_start..age_start + age.chars().count());
+        }
+        renderer.print_text(text, cols - display_width(&status), rows);
+    }
+    fn position_text(&self, rows: usize, hour: usize) -> Option<String> {
+        // eg. "hours 24–32 of 168", only once scrolled away from the current hour
+        let first_hour = self.first_displayed_hour(hour);
+        if self.view != View::Hourly || first_hour == hour {
+            return None;
+        }
+        let shown_hours = self.weather_data.range(first_hour..).take(self.hourly_layout(rows).1).count();
+        let total_hours = self.weather_data.range(hour..).count();
+        let start = first_hour - hour;
+        Some(format!("{} {}–{} {} {}", translate(self.language, "hours"), start, start + shown_hours, translate(self.language, "of"), total_hours))
+    }
+    fn render_tab_bar<R: Renderer>(&self, renderer: &mut R) {
+        let mut x = 0;
+        for view in self.views() {
+            let name = translate(self.language, view.name());
+            let ribbon = if view == self.view { Text::new(name).selected() } else { Text::new(name) };
+            renderer.print_ribbon(ribbon, x, 0);
+            x += display_width(name) + 4; // ribbons are padded and have a separator on each side
+        }
+    }
+    pub(crate) fn hourly_layout(&self, rows: usize) -> (usize, usize) { // top of the table, hours shown
+        // the hours fill whatever the tab bar, headers, the lines below the table and the controls leave
+        if self.horizontal_hourly {
+            return ((rows / 2).saturating_sub(4), 8);
+        }
+        let hour_rows = rows.saturating_sub(8 + !self.commute.is_empty() as usize).max(1);
+        ((rows / 2).saturating_sub(hour_rows.div_ceil(2)), hour_rows)
+    }
+    fn render_hourly_table<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize) {
+        let (top, hour_rows) = self.hourly_layout(rows);
+        let countdown_row = top + hour_rows + 1;
+        let best_window = if self.show_best_window { self.best_window(hour) } else { None };
+        let dry_window = if self.show_dry_window { self.next_dry_window(hour) } else { None };
+        let highlighted_hours: Vec<Range<usize>> = best_window
+            .map(|start| start..start + self.best_window_hours())
+            .into_iter()
+            .chain(dry_window.map(|start| start..start + self.dry_window_hours()))
+            .chain(self.commute_hours(self.first_displayed_hour(hour)))
+            .collect();
+        if let (Some(wind_timeline), true) = (self.wind_timeline_text(hour), countdown_row + 1 < rows) {
+            renderer.print_text(Text::new(&wind_timeline).color_range(self.palette.highlight(), ..), (cols / 2).saturating_sub(display_width(&wind_timeline) / 2), countdown_row);
+        } else if let (Some((allergy_briefing, level)), true) = (self.allergy_briefing_text(hour), countdown_row + 1 < rows) {
+            let color = self.palette.severity_color(level.severity()).unwrap_or(self.palette.highlight());
+            renderer.print_text(Text::new(&allergy_briefing).color_range(color, ..), (cols / 2).saturating_sub(display_width(&allergy_briefing) / 2), countdown_row);
+        } else if let (Some(observing_conditions), true) = (self.observing_conditions_text(hour), countdown_row + 1 < rows) {
+            renderer.print_text(Text::new(&observing_conditions).color_range(self.palette.highlight(), ..), (cols / 2).saturating_sub(display_width(&observing_conditions) / 2), countdown_row);
+        } else if self.show_best_window && countdown_row + 1 < rows {
+            let best_window_text = self.best_window_text(best_window);
+            renderer.print_text(Text::new(&best_window_text).color_range(self.palette.highlight(), ..), (cols / 2).saturating_sub(display_width(&best_window_text) / 2), countdown_row);
+        } else if let (Some(countdown), 0, true) = (self.precipitation_countdown_text(hour), self.day_offset, countdown_row + 1 < rows) {
+            renderer.print_text(Text::new(&countdown).color_range(self.palette.precipitation(), ..), (cols / 2).saturating_sub(display_width(&countdown) / 2), countdown_row);
+        }
+        let nowcast_row = countdown_row + 1;
+        if let (Some(nowcast), 0, true) = (self.nowcast_text(), self.day_offset, nowcast_row + 1 < rows) {
+            renderer.print_text(Text::new(&nowcast).color_range(self.palette.precipitation(), ..), (cols / 2).saturating_sub(display_width(&nowcast) / 2), nowcast_row);
+        }
+        let commute_row = nowcast_row + 1;
+        if let (Some((commute, rainy)), true) = (self.commute_text(self.first_displayed_hour(hour)), commute_row + 1 < rows) {
+            let color = if rainy { self.palette.precipitation() } else { self.palette.highlight() };
+            renderer.print_text(Text::new(&commute).color_range(color, ..), (cols / 2).saturating_sub(display_width(&commute) / 2), commute_row);
+        }
+        let hour = self.first_displayed_hour(hour);
+        if let Some((daily_summary_text, daily_summary_len)) = self.daily_summary_text(hour) {
+            renderer.print_text(daily_summary_text, (cols / 2).saturating_sub(daily_summary_len / 2), top.saturating_sub(1));
+        }
+        if self.horizontal_hourly {
+            self.render_hourly_strip(renderer, rows, cols, hour, &highlighted_hours);
+            return;
+        }
+        let shows_activity_score = self.profile.map(|p| p.shows_activity_score()).unwrap_or(true);
+        let shows_wind = !self.profile.map(|p| p.shows_wind_details()).unwrap_or(false);
+        // pinned above the hours, so the day and the columns stay known however far down the table is scrolled
+        let day = self.daily_data.get(&(hour / 24)).map(|daily_data| day_label(&daily_data.date, self.language)).unwrap_or_default();
+        let sorted_by = |name: &'static str, order: SortOrder| if self.sort_order == order { format!("{} ▼", translate(self.language, name)) } else { translate(self.language, name).to_owned() };
+        let mut header = vec![day, translate(self.language, "Sky").to_owned(), sorted_by("Temp", SortOrder::Temperature), String::new(), sorted_by("Rain", SortOrder::Precipitation)];
+        if shows_wind {
+            header.push(translate(self.language, "Wind").to_owned());
+        }
+        if shows_activity_score {
+            header.push(translate(self.language, "Score").to_owned());
+        }
+        if self.gardening {
+            header.push(translate(self.language, "Soil").to_owned());
+        }
+        if self.pv_system.is_some() {
+            header.push(translate(self.language, "Solar").to_owned());
+        }
+        if !self.wind_heights.is_empty() {
+            header.push(translate(self.language, "Wind aloft").to_owned());
+        }
+        // named as in the API, like their cells
+        header.extend(self.hourly_extra.iter().cloned());
+        header.extend(self.profile.iter().flat_map(|p| p.columns()).map(|column| translate(self.language, column.name()).to_owned()));
+        let mut weather_table = Table::new().add_styled_row(header.into_iter().map(|name| colored_text(name, Some(self.palette.hour())).0).collect());
+        let mut longest_line = 0;
+        // only the hours on screen are sorted, the window itself stays where it was scrolled to
+        let mut displayed_hours: Vec<(&usize, &HourlyData)> = self.weather_data.range(hour..).take(hour_rows).collect();
+        match self.sort_order {
+            SortOrder::Time => (),
+            SortOrder::Temperature => displayed_hours.sort_by(|(_, a), (_, b)| b.temperature_2m.total_cmp(&a.temperature_2m)),
+            SortOrder::Precipitation => displayed_hours.sort_by_key(|(_, h)| std::cmp::Reverse(h.precipitation_probability)),
+        }
+        for (hour, hourly_data) in displayed_hours {
+            let (day_min, day_max) = self.daily_temperature_range(*hour);
+            let temperature_color = temperature_gradient_color(hourly_data.temperature_2m, day_min, day_max, self.palette);
+            let previous_temperature = hour.checked_sub(1).and_then(|previous| self.weather_data.get(&previous)).map(|h| h.temperature_2m);
+            let highlighted = highlighted_hours.iter().any(|hours| hours.contains(hour));
+            let profile_texts = self.profile_texts(*hour, hourly_data);
+            let hour = hour_of_day(*hour);
+            let hour_string = if hour > 9 { hour.to_string() } else { format!("0{}", hour)};
+            let hour_text = format!("{}:00", hour_string);
+            let (wmo_code_label, wmo_code_color) = wmo_code_label(hourly_data.wmo_code, self.palette, self.language);
+            // high CAPE hints at thunderstorms even when the forecast doesn't call for one
+            let (wmo_code_label, wmo_code_color) = match self.palette.severity_color(lightning_risk(hourly_data.cape, hourly_data.wmo_code)) {
+                Some(lightning_color) => (format!("{} ⚡", wmo_code_label), wmo_code_color.or(Some(lightning_color))),
+                None => (wmo_code_label, wmo_code_color),
+            };
+            let degrees_text = self.locale.format_temperature(hourly_data.temperature_2m);
+            let degrees_symbol_text = format!("{} {}", self.locale.temperature_symbol(), temperature_trend_arrow(previous_temperature, hourly_data.temperature_2m));
+            let precipitation_text = format!("💧 {} ", hourly_data.precipitation_probability.map(|p| self.locale.format_percentage(p)).unwrap_or_else(|| "-".to_owned()));
+            let (wind_label, wind_color) = wind_label(hourly_data.wind_direction_10m, hourly_data.wind_speed_10m, &self.locale, self.palette);
+            let score = activity_score(hourly_data, self.activity_weights);
+            let score_text = format!("{}{}", bar_character(score as f64 / 10.0).max('▁'), score);
+            let soil_text = self.soil_text(hourly_data);
+            let solar_text = self.solar_text(hourly_data);
+            let wind_aloft = self.wind_aloft_text(hourly_data);
+            let extra_texts = self.extra_texts(hourly_data);
+            let line_len = display_width(&hour_text) + display_width(&wmo_code_label) + display_width(&degrees_text) + display_width(&degrees_symbol_text) + (display_width(&precipitation_text) + 1) + if shows_wind { display_width(&wind_label) + 1 } else { 0 } + if shows_activity_score { display_width(&score_text) + 1 } else { 0 } + soil_text.as_ref().map(|s| display_width(s) + 1).unwrap_or(0) + solar_text.as_ref().map(|s| display_width(s) + 1).unwrap_or(0) + wind_aloft.as_ref().map(|(w, _)| display_width(w) + 1).unwrap_or(0) + extra_texts.iter().map(|e| display_width(e) + 1).sum::<usize>() + profile_texts.iter().map(|(p, _)| display_width(p) + 1).sum::<usize>();
+            if line_len > longest_line {
+                longest_line = line_len;
+            }
+            let likely_precipitation = hourly_data.precipitation_probability.map(|p| p >= self.precipitation_threshold()).unwrap_or(false);
+            let reversed = likely_precipitation || (highlighted && self.palette.reverse_video());
+            // when tinting, every cell takes the color of the row's comfort instead of its own, and reversed rows are bold throughout with the bold palette
+            let tint = match self.palette.bold() {
+                Some(bold) if reversed => Some(Some(bold)),
+                _ if self.comfort_tint => Some(self.palette.comfort_color(comfort(hourly_data, self.precipitation_threshold()))),
+                _ => None,
+            };
+            let cell = |text: String, color: Option<usize>| colored_text(text, tint.unwrap_or(color)).0;
+            let mut row = vec![
+                cell(hour_text, Some(if highlighted { self.palette.highlight() } else { self.palette.hour() })),
+                cell(wmo_code_label, wmo_code_color),
+                cell(degrees_text, Some(temperature_color)),
+                cell(degrees_symbol_text, Some(temperature_color)),
+                cell(precipitation_text, hourly_data.precipitation_probability.and_then(|p| self.palette.precipitation_probability_color(p))),
+            ];
+            if shows_wind {
+                row.push(cell(wind_label, wind_color));
+            }
+            if shows_activity_score {
+                row.push(cell(score_text, Some(self.palette.activity_score_color(score))));
+            }
+            if let Some(soil_text) = soil_text {
+                row.push(cell(soil_text, None));
+            }
+            if let Some(solar_text) = solar_text {
+                row.push(cell(solar_text, Some(self.palette.golden_hour())));
+            }
+            if let Some((wind_aloft, color)) = wind_aloft {
+                row.push(cell(wind_aloft, color));
+            }
+            for extra_text in extra_texts {
+                row.push(cell(extra_text, None));
+            }
+            for (profile_text, color) in profile_texts {
+                row.push(cell(profile_text, color));
+            }
+            weather_table = if reversed {
+                weather_table.add_styled_row(row.into_iter().map(|cell| cell.selected()).collect())
+            } else {
+                weather_table.add_styled_row(row)
+            };
+        }
+        renderer.print_table(weather_table, (cols / 2).saturating_sub((longest_line + 5) / 2), top);
+    }
+    fn render_comparison_table<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize) {
+        // hour by hour, how much warmer or colder the compared location is and which of the two is likely to get rained on
+        let (top, hour_rows) = self.hourly_layout(rows);
+        let here = self.weather_location.as_deref().and_then(|l| l.split(", ").next()).unwrap_or_default();
+        let there = self.compare_location().and_then(|l| l.split(", ").next()).unwrap_or_default();
+        let header = vec![" ".to_owned(), here.to_owned(), there.to_owned(), "Δ".to_owned(), format!("💧 {}", here), format!("💧 {}", there), " ".to_owned()];
+        let mut rows_text = vec![];
+        for (hour, hourly_data) in self.weather_data.range(self.first_displayed_hour(hour)..).take(hour_rows) {
+            let compared = self.compare_data.get(hour);
+            let temperature = |temperature: f64| format!("{}{}", self.locale.format_temperature(temperature), self.locale.temperature_symbol());
+            let difference = compared.map(|c| format!("{}{}", self.locale.format_temperature_difference(c.temperature_2m - hourly_data.temperature_2m), self.locale.temperature_symbol()));
+            let probability = |hourly_data: Option<&HourlyData>| hourly_data.and_then(|h| h.precipitation_probability);
+            let is_wet = |probability: Option<usize>| probability.map(|p| p >= self.precipitation_threshold()).unwrap_or(false);
+            let wet_where = match (is_wet(probability(Some(hourly_data))), is_wet(probability(compared))) {
+                (true, true) => format!("☂ {}", translate(self.language, "both")),
+                (true, false) => format!("☂ {}", here),
+                (false, true) => format!("☂ {}", there),
+                (false, false) => String::new(),
+            };
+            rows_text.push((
+                vec![
+                    format!("{:02}:00", hour_of_day(*hour)),
+                    temperature(hourly_data.temperature_2m),
+                    compared.map(|c| temperature(c.temperature_2m)).unwrap_or_else(|| "-".to_owned()),
+                    difference.unwrap_or_else(|| "-".to_owned()),
+                    probability(Some(hourly_data)).map(|p| self.locale.format_percentage(p)).unwrap_or_else(|| "-".to_owned()),
+                    probability(compared).map(|p| self.locale.format_percentage(p)).unwrap_or_else(|| "-".to_owned()),
+                    wet_where,
+                ],
+                [probability(Some(hourly_data)), probability(compared)],
+            ));
+        }
+        let column_widths: Vec<usize> = (0..header.len()).map(|i| rows_text.iter().map(|(row, _)| &row[i]).chain([&header[i]]).map(|t| display_width(t)).max().unwrap_or(0)).collect();
+        let table_width = column_widths.iter().sum::<usize>() + column_widths.len() - 1;
+        let mut table = Table::new().add_styled_row(header.into_iter().map(|h| Text::new(h).color_range(self.palette.hour(), ..)).collect());
+        for (row, probabilities) in rows_text {
+            let colors = [
+                Some(self.palette.hour()),
+                None,
+                None,
+                Some(self.palette.highlight()),
+                probabilities[0].and_then(|p| self.palette.precipitation_probability_color(p)),
+                probabilities[1].and_then(|p| self.palette.precipitation_probability_color(p)),
+                Some(self.palette.precipitation()),
+            ];
+            table = table.add_styled_row(row.into_iter().zip(colors).map(|(text, color)| colored_text(text, color).0).collect());
+        }
+        renderer.print_table(table, (cols / 2).saturating_sub(table_width / 2), top);
+    }
+    fn render_hourly_strip<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize, highlighted_hours: &[Range<usize>]) {
+        // hours as columns and metrics as rows, as many hours as fit the width of the pane
+        let mut hour_row = vec![];
+        let mut icon_row = vec![];
+        let mut temperature_row = vec![];
+        let mut precipitation_row = vec![];
+        let mut wind_row = vec![];
+        let mut strip_width = 0;
+        for (hour, hourly_data) in self.weather_data.range(hour..).take(24) {
+            let (day_min, day_max) = self.daily_temperature_range(*hour);
+            let temperature_color = temperature_gradient_color(hourly_data.temperature_2m, day_min, day_max, self.palette);
+            let highlighted = highlighted_hours.iter().any(|hours| hours.contains(hour));
+            let hour_text = format!("{:02}:00", hour_of_day(*hour));
+            let lightning_marker = if lightning_risk(hourly_data.cape, hourly_data.wmo_code) == Severity::None { "" } else { "⚡" };
+            let icon_text = format!("{}{}", hourly_data.wmo_code.map(|c| c.icon()).unwrap_or("-"), lightning_marker);
+            let temperature_text = format!("{}{}", self.locale.format_temperature(hourly_data.temperature_2m), self.locale.temperature_symbol());
+            let precipitation_text = format!("💧 {}", hourly_data.precipitation_probability.map(|p| self.locale.format_percentage(p)).unwrap_or_else(|| "-".to_owned()));
+            let (wind_label, wind_color) = wind_label(hourly_data.wind_direction_10m, hourly_data.wind_speed_10m, &self.locale, self.palette);
+            let column_width = [&hour_text, &icon_text, &temperature_text, &precipitation_text, &wind_label].iter().map(|text| display_width(text)).max().unwrap_or(0);
+            if strip_width + column_width > cols {
+                break;
+            }
+            strip_width += column_width + 1;
+            let likely_precipitation = hourly_data.precipitation_probability.map(|p| p >= self.precipitation_threshold()).unwrap_or(false);
+            let cell = |text: String, color: Option<usize>| {
+                let text = colored_text(text, color).0;
+                if likely_precipitation { text.selected() } else { text }
+            };
+            hour_row.push(cell(hour_text, Some(if highlighted { self.palette.highlight() } else { self.palette.hour() })));
+            icon_row.push(cell(icon_text, None));
+            temperature_row.push(cell(temperature_text, Some(temperature_color)));
+            precipitation_row.push(cell(precipitation_text, hourly_data.precipitation_probability.and_then(|p| self.palette.precipitation_probability_color(p))));
+            wind_row.push(cell(wind_label, wind_color));
+        }
+        let weather_strip = Table::new()
+            .add_styled_row(hour_row)
+            .add_styled_row(icon_row)
+            .add_styled_row(temperature_row)
+            .add_styled_row(precipitation_row)
+            .add_styled_row(wind_row);
+        renderer.print_table(weather_strip, (cols / 2).saturating_sub(strip_width.saturating_sub(1) / 2), (rows / 2).saturating_sub(2));
+    }
+    fn render_daily_table<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize) {
+        let columns = if self.gardening { 7 } else { 6 };
+        let mut weather_table = Table::new().add_row(vec![" "; columns]);
+        let mut longest_line = 0;
+        for (_, daily_data) in self.daily_data.range(hour / 24..).take(7) {
+            let day_text = day_label(&daily_data.date, self.language);
+            let (wmo_code_text, wmo_code_len) = wmo_code_to_text(daily_data.wmo_code, self.palette, self.language);
+            let precipitation_text = format!("💧 {} ", daily_data.precipitation_sum.map(|p| self.locale.format_precipitation(p)).unwrap_or_else(|| "-".to_owned()));
+            let precipitation_hours_text = daily_data.precipitation_hours.map(|h| format!("{}h", self.locale.format_decimal(h))).unwrap_or_else(|| "-".to_owned());
+            let temperatures_len = [daily_data.temperature_2m_max, daily_data.temperature_2m_min]
+                .iter()
+                .map(|t| t.map(|t| display_width(&self.locale.format_temperature(t)) + display_width(self.locale.temperature_symbol())).unwrap_or(1) + 1)
+                .sum::<usize>();
+            // reference evapotranspiration, how much water a well watered lawn loses to the air
+            let evapotranspiration_text = if self.gardening {
+                Some(format!("🌿 {}", daily_data.et0_fao_evapotranspiration.map(|et0| self.locale.format_precipitation(et0)).unwrap_or_else(|| "-".to_owned())))
+            } else {
+                None
+            };
+            let line_len = display_width(&day_text) + wmo_code_len + temperatures_len + (display_width(&precipitation_text) + 1) + (display_width(&precipitation_hours_text) + 1) + evapotranspiration_text.as_ref().map(|e| display_width(e) + 1).unwrap_or(0);
+            if line_len > longest_line {
+                longest_line = line_len;
+            }
+            let mut row = vec![
+                Text::new(day_text).color_range(self.palette.hour(), ..),
+                wmo_code_text,
+                self.weekly_temperature_text(daily_data.temperature_2m_max, hour),
+                self.weekly_temperature_text(daily_data.temperature_2m_min, hour),
+                Text::new(precipitation_text).color_range(self.palette.precipitation(), ..),
+                Text::new(precipitation_hours_text).color_range(self.palette.precipitation(), ..),
+            ];
+            if let Some(evapotranspiration_text) = evapotranspiration_text {
+                row.push(Text::new(evapotranspiration_text));
+            }
+            weather_table = weather_table.add_styled_row(row);
+        }
+        renderer.print_table(weather_table, (cols / 2).saturating_sub((longest_line + 5) / 2), (rows / 2).saturating_sub(9 / 2));
+        if let Some(sun_times) = self.daily_data.get(&(hour / 24)).and_then(SunTimes::from_daily) {
+            let photography_lines = [
+                ("Golden hour", sun_times.golden_hours(), self.palette.golden_hour()),
+                ("Blue hour", sun_times.blue_hours(), self.palette.blue_hour()),
+            ];
+            for (i, &(label, [(morning_start, morning_end), (evening_start, evening_end)], color)) in photography_lines.iter().enumerate() {
+                let row = rows / 2 + 5 + i;
+                if row + 1 >= rows {
+                    break;
+                }
+                let line = format!("{} {}-{}, {}-{}", translate(self.language, label), time_of_day(morning_start), time_of_day(morning_end), time_of_day(evening_start), time_of_day(evening_end));
+                renderer.print_text(Text::new(&line).color_range(color, ..), (cols / 2).saturating_sub(display_width(&line) / 2), row);
+            }
+            // where the sun is right now, eg. to know when it stops shining on the screen
+            let row = rows / 2 + 5 + photography_lines.len();
+            if let (Some((latitude, longitude)), Some(now), true) = (self.geolocation, self.clock, row + 1 < rows) {
+                let (elevation, azimuth) = sun_position(latitude, longitude, now);
+                let horizon = if elevation < 0.0 { "below the horizon" } else { "above the horizon" };
+                let line = format!(
+                    "☀ {:.0}° {}, {} {:.0}° · {} {} · {} {}",
+                    elevation.abs(),
+                    translate(self.language, horizon),
+                    compass_point(azimuth),
+                    azimuth,
+                    translate(self.language, "sunrise"),
+                    time_of_day(sun_times.sunrise),
+                    translate(self.language, "sunset"),
+                    time_of_day(sun_times.sunset)
+                );
+                renderer.print_text(Text::new(&line).color_range(self.palette.golden_hour(), ..), (cols / 2).saturating_sub(display_width(&line) / 2), row);
+            }
+            let mut row = row + 1;
+            if let (Some((line, likely)), true) = (self.aurora_text(hour), row + 1 < rows) {
+                let text = if likely { Text::new(&line).color_range(self.palette.highlight(), ..) } else { Text::new(&line) };
+                renderer.print_text(text, (cols / 2).saturating_sub(display_width(&line) / 2), row);
+                row += 1;
+            }
+            if let (Some(line), true) = (self.accuracy_text(hour), row + 1 < rows) {
+                renderer.print_text(Text::new(&line), (cols / 2).saturating_sub(display_width(&line) / 2), row);
+            }
+        }
+    }
+    fn render_week_grid<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize) {
+        let days: Vec<_> = self.daily_data.range(hour / 24..).take(7).map(|(_, daily_data)| daily_data).collect();
+        let mut day_row = vec![];
+        let mut icon_row = vec![];
+        let mut high_row = vec![];
+        let mut low_row = vec![];
+        let mut precipitation_row = vec![];
+        let mut wind_row = vec![];
+        for daily_data in &days {
+            day_row.push(Text::new(day_label(&daily_data.date, self.language)).color_range(self.palette.hour(), ..));
+            icon_row.push(Text::new(daily_data.wmo_code.map(|c| c.icon()).unwrap_or("-")));
+            high_row.push(self.weekly_temperature_text(daily_data.temperature_2m_max, hour));
+            low_row.push(self.weekly_temperature_text(daily_data.temperature_2m_min, hour));
+            let precipitation_text = format!("💧 {}", daily_data.precipitation_sum.map(|p| self.locale.format_precipitation(p)).unwrap_or_else(|| "-".to_owned()));
+            precipitation_row.push(Text::new(precipitation_text).color_range(self.palette.precipitation(), ..));
+            wind_row.push(Text::new(format!("💨 {}", daily_data.wind_speed_10m_max.map(|w| self.locale.format_wind_speed(w)).unwrap_or_else(|| "-".to_owned()))));
+        }
+        let column_widths: Vec<usize> = days
+            .iter()
+            .map(|daily_data| {
+                let precipitation_len = daily_data.precipitation_sum.map(|p| display_width(&self.locale.format_precipitation(p))).unwrap_or(1);
+                let wind_len = daily_data.wind_speed_10m_max.map(|w| display_width(&self.locale.format_wind_speed(w))).unwrap_or(1);
+                display_width(&day_label(&daily_data.date, self.language)).max(precipitation_len + 3).max(wind_len + 3)
+            })
+            .collect();
+        let grid_width = column_widths.iter().sum::<usize>() + column_widths.len().saturating_sub(1);
+        let weather_grid = Table::new()
+            .add_styled_row(day_row)
+            .add_styled_row(icon_row)
+            .add_styled_row(high_row)
+            .add_styled_row(low_row)
+            .add_styled_row(precipitation_row)
+            .add_styled_row(wind_row);
+        renderer.print_table(weather_grid, (cols / 2).saturating_sub(grid_width / 2), (rows / 2).saturating_sub(3));
+    }
+    fn render_chart<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, hour: usize) {
+        // temperature line over precipitation probability bars for the next 48 hours, sized to the whole pane
+        let hours: Vec<_> = self.weather_data.range(self.first_displayed_hour(hour)..).take(48).collect();
+        if hours.len() < 2 {
+            return;
+        }
+        let (min, max) = hours
+            .iter()
+            .map(|(_, hourly_data)| hourly_data.temperature_2m)
+            .fold((f64::MAX, f64::MIN), |(min, max), temperature| (min.min(temperature), max.max(temperature)));
+        let max = if max > min { max } else { min + 1.0 };
+        let top = if shows_tab_bar(rows) { 1 } else { 0 };
+        let bottom = if self.hide_controls { 1 } else { 2 }; // hour labels and controls
+        let max_label = format!("{}{}", self.locale.format_temperature(max), self.locale.temperature_symbol());
+        let min_label = format!("{}{}", self.locale.format_temperature(min), self.locale.temperature_symbol());
+        let left = display_width(&max_label).max(display_width(&min_label)) + 1;
+        let right = 5; // " 100%"
+        let plot_height = rows.saturating_sub(top + bottom);
+        let plot_width = cols.saturating_sub(left + right);
+        if plot_height < 2 || plot_width < 2 {
+            return;
+        }
+        let mut plot = Canvas::new(plot_width, plot_height);
+        let mut hour_labels = Canvas::new(plot_width, 1);
+        let mut last_hour_index = None;
+        let mut next_free_label_column = 0;
+        for x in 0..plot_width {
+            let position = x as f64 * (hours.len() - 1) as f64 / (plot_width - 1) as f64;
+            let hour_index = position.floor() as usize;
+            let precipitation = hours[hour_index].1.precipitation_probability.unwrap_or(0) as f64 / 100.0;
+            plot.draw_bar(x, precipitation, Some(self.palette.precipitation()));
+            let first_new_hour = last_hour_index.map(|i| i + 1).unwrap_or(0);
+            for (hour, _) in hours.iter().take(hour_index + 1).skip(first_new_hour) {
+                let hour_of_day = hour_of_day(**hour);
+                if matches!(hour_of_day, 0 | 6 | 12 | 18) && x >= next_free_label_column {
+                    hour_labels.write(x, 0, &format!("{:02}", hour_of_day), Some(self.palette.hour()));
+                    next_free_label_column = x + 3;
+                }
+            }
+            last_hour_index = Some(hour_index);
+        }
+        let mut temperature_line = BrailleCanvas::new(plot_width, plot_height);
+        let mut previous_dot = None;
+        for dot_x in 0..temperature_line.dot_width() {
+            let position = dot_x as f64 * (hours.len() - 1) as f64 / (temperature_line.dot_width() - 1) as f64;
+            let hour_index = position.floor() as usize;
+            let (_, before) = hours[hour_index];
+            let (_, after) = hours[(hour_index + 1).min(hours.len() - 1)];
+            let temperature = before.temperature_2m + (after.temperature_2m - before.temperature_2m) * position.fract();
+            let dot_y = ((max - temperature) / (max - min) * (temperature_line.dot_height() - 1) as f64).round() as usize;
+            let color = Some(temperature_gradient_color(temperature, min, max, self.palette));
+            temperature_line.draw_line(previous_dot.unwrap_or((dot_x, dot_y)), (dot_x, dot_y), color);
+            previous_dot = Some((dot_x, dot_y));
+        }
+        plot.overlay(&temperature_line);
+        self.annotate_chart(&mut plot, &hours, min, max);
+        for (y, line) in plot.lines().into_iter().enumerate() {
+            renderer.print_text(line, left, top + y);
+        }
+        for line in hour_labels.lines() {
+            renderer.print_text(line, left, top + plot_height);
+        }
+        renderer.print_text(Text::new(&max_label), left - 1 - display_width(&max_label), top);
+        renderer.print_text(Text::new(&min_label), left - 1 - display_width(&min_label), top + plot_height - 1);
+        renderer.print_text(Text::new("100%").color_range(self.palette.precipitation(), ..), left + plot_width + 1, top);
+        renderer.print_text(Text::new("0%").color_range(self.palette.precipitation(), ..), left + plot_width + 1, top + plot_height - 1);
+    }
+    fn annotate_chart(&self, plot: &mut Canvas, hours: &[(&usize, &HourlyData)], min: f64, max: f64) {
+        // daily highs and lows next to the temperature line and the peak precipitation probability above its bar
+        let (plot_width, plot_height) = (plot.width(), plot.height());
+        let column_of = |i: usize| (i as f64 * (plot_width - 1) as f64 / (hours.len() - 1) as f64).round() as usize;
+        let row_of = |temperature: f64| (((max - temperature) / (max - min) * (plot_height * 4 - 1) as f64).round() as usize) / 4;
+        let write_label = |plot: &mut Canvas, column: usize, row: usize, label: &str, color: usize| {
+            let label_len = label.chars().count();
+            let x = column.saturating_sub(label_len / 2).min(plot_width.saturating_sub(label_len));
+            plot.write(x, row, label, Some(color));
+        };
+        let mut days: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (i, (hour, _)) in hours.iter().enumerate() {
+            days.entry(**hour / 24).or_default().push(i);
+        }
+        for day in days.values() {
+            let temperature = |i: &&usize| hours[**i].1.temperature_2m;
+            let high = day.iter().max_by(|a, b| temperature(a).total_cmp(&temperature(b)));
+            let low = day.iter().min_by(|a, b| temperature(a).total_cmp(&temperature(b)));
+            if let Some(high) = high {
+                let high_temperature = hours[*high].1.temperature_2m;
+                let row = row_of(high_temperature);
+                let row = if row > 0 { row - 1 } else { row + 1 };
+                let label = format!("▲{}°", self.locale.format_temperature(high_temperature));
+                write_label(plot, column_of(*high), row, &label, temperature_gradient_color(high_temperature, min, max, self.palette));
+            }
+            if let Some(low) = low {
+                let low_temperature = hours[*low].1.temperature_2m;
+                let row = row_of(low_temperature);
+                let row = if row + 1 < plot_height { row + 1 } else { row.saturating_sub(1) };
+                let label = format!("▼{}°", self.locale.format_temperature(low_temperature));
+                write_label(plot, column_of(*low), row, &label, temperature_gradient_color(low_temperature, min, max, self.palette));
+            }
+        }
+        let peak_precipitation = hours
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (hour, hourly_data))| hourly_data.precipitation_probability.map(|p| (i, **hour, p)))
+            .fold(None, |peak: Option<(usize, usize, usize)>, (i, hour, p)| match peak {
+                Some((_, _, peak_p)) if peak_p >= p => peak,
+                _ => Some((i, hour, p)),
+            });
+        if let Some((i, hour, probability)) = peak_precipitation.filter(|(_, _, p)| *p > 0) {
+            let bar_rows = (probability as f64 / 100.0 * plot_height as f64).ceil() as usize;
+            let label = format!("☂ {} {:02}:00", self.locale.format_percentage(probability), hour_of_day(hour));
+            write_label(plot, column_of(i), plot_height.saturating_sub(bar_rows + 1), &label, self.palette.precipitation());
+        }
+    }
+    fn weekly_temperature_text(&self, temperature: Option<f64>, hour: usize) -> Text {
+        match temperature {
+            Some(temperature) => {
+                let (week_min, week_max) = self.weekly_temperature_range(hour);
+                let text = format!("{}{}", self.locale.format_temperature(temperature), self.locale.temperature_symbol());
+                Text::new(text).color_range(temperature_gradient_color(temperature, week_min, week_max, self.palette), ..)
+            }
+            None => Text::new("-"),
+        }
+    }
+    fn precipitation_countdown_text(&self, hour: usize) -> Option<String> {
+        self.weather_data.range(hour..).next()?;
+        let Some((start, end)) = self.next_precipitation_window(hour) else {
+            return Some(translate(self.language, "No rain likely in the next 48h").to_owned());
+        };
+        let mut countdown = if start == hour {
+            translate(self.language, "Rain likely now").to_owned()
+        } else {
+            format!("{} ~{}h", translate(self.language, "Rain likely in"), start - hour)
+        };
+        if let Some(end) = end {
+            countdown.push_str(&format!(", {} ~{:02}:00", translate(self.language, "until"), hour_of_day(end)));
+            match self.next_precipitation_window(end) {
+                Some((next_start, _)) => countdown.push_str(&format!(", {} ~{}h", translate(self.language, "then dry for"), next_start - end)),
+                None => countdown.push_str(&format!(", {}", translate(self.language, "then dry"))),
+            }
+        }
+        Some(countdown)
+    }
+    fn soil_text(&self, hourly_data: &HourlyData) -> Option<String> {
+        if !self.gardening {
+            return None;
+        }
+        let soil_temperature = hourly_data.soil_temperature_0cm.map(|t| format!("{}{}", self.locale.format_temperature(t), self.locale.temperature_symbol()));
+        let soil_moisture = hourly_data.soil_moisture_0_to_1cm.map(|m| self.locale.format_percentage((m * 100.0).round() as usize));
+        Some(format!("🌱 {} {}", soil_temperature.unwrap_or_else(|| "-".to_owned()), soil_moisture.unwrap_or_else(|| "-".to_owned())))
+    }
+    fn wind_aloft_text(&self, hourly_data: &HourlyData) -> Option<(String, Option<usize>)> { // text + color
+        // eg. "80m 21kph 120m 25kph", colored by the strongest of them
+        if self.wind_heights.is_empty() {
+            return None;
+        }
+        let wind_speeds: Vec<String> = self
+            .wind_heights
+            .iter()
+            .map(|height| format!("{}m {}", height, hourly_data.wind_speed_at(*height).map(|w| self.locale.format_wind_speed(w)).unwrap_or_else(|| "-".to_owned())))
+            .collect();
+        let strongest = self.wind_heights.iter().filter_map(|height| hourly_data.wind_speed_at(*height)).reduce(f64::max);
+        let (_, color) = wind_label(None, strongest, &self.locale, self.palette);
+        Some((format!("🌬 {}", wind_speeds.join(" ")), color))
+    }
+    fn extra_texts(&self, hourly_data: &HourlyData) -> Vec<String> {
+        // eg. "pressure_msl 1013.2hPa", as named and measured by the API
+        self.hourly_extra
+            .iter()
+            .map(|name| {
+                let unit = self.hourly_units.get(name).map(|u| u.as_str()).unwrap_or_default();
+                let value = hourly_data.extras.get(name).map(|v| format!("{}{}", self.locale.format_decimal(*v), unit)).unwrap_or_else(|| "-".to_owned());
+                format!("{} {}", name, value)
+            })
+            .collect()
+    }
+    fn solar_text(&self, hourly_data: &HourlyData) -> Option<String> {
+        let pv_system = self.pv_system?;
+        // without the tilted irradiance, panels are assumed to get what lands on flat ground
+        let tilted_irradiance = hourly_data.global_tilted_irradiance.or(hourly_data.shortwave_radiation);
+        let radiation = hourly_data.shortwave_radiation.map(|r| format!("{}W/m²", r.round())).unwrap_or_else(|| "-".to_owned());
+        let output = tilted_irradiance.map(|i| format!("{}kW", self.locale.format_decimal(pv_system.estimated_output_kw(i)))).unwrap_or_else(|| "-".to_owned());
+        Some(format!("☀ {} ⚡ {}", radiation, output))
+    }
+    fn aurora_text(&self, hour: usize) -> Option<(String, bool)> { // text, whether there is a fair chance to see it
+        // eg. "🌌 Aurora tonight: high · Kp 5.7 · ☁ 20%"
+        let (latitude, longitude) = self.geolocation?;
+        let kp = max_kp(&self.kp_forecast, self.clock?)?;
+        let (_, _, _, cloud_cover) = self.tonight(hour)?;
+        let chance = aurora_chance(latitude, longitude, kp, cloud_cover);
+        let text = format!(
+            "🌌 {}: {} · Kp {} · ☁ {}",
+            translate(self.language, "Aurora tonight"),
+            translate(self.language, chance.label()),
+            self.locale.format_decimal(kp),
+            self.locale.format_percentage(cloud_cover.round() as usize)
+        );
+        Some((text, matches!(chance, AuroraChance::Moderate | AuroraChance::High)))
+    }
+    fn accuracy_text(&self, hour: usize) -> Option<String> {
+        // eg. "🎯 Forecast vs actual: ±1.2°C, rain right in 16 of 18 hours"
+        let accuracy = self.forecast_accuracy.as_ref().filter(|_| hour / 24 == 0)?;
+        let rain = if accuracy.rain_hours > 0 {
+            format!(", {}", translate(self.language, "rain right in {} of {} hours").replacen("{}", &accuracy.rain_right.to_string(), 1).replacen("{}", &accuracy.rain_hours.to_string(), 1))
+        } else {
+            String::new()
+        };
+        Some(format!(
+            "🎯 {}: ±{}{}{}",
+            translate(self.language, "Forecast vs actual"),
+            self.locale.format_temperature_difference(accuracy.temperature_error).trim_start_matches('+'),
+            self.locale.temperature_symbol(),
+            rain
+        ))
+    }
+    fn best_window_text(&self, best_window: Option<usize>) -> String {
+        let Some(start) = best_window else {
+            return translate(self.language, "No suitable window in the next 48h").to_owned();
+        };
+        let window_hours = self.best_window_hours();
+        let day = self.daily_data.get(&(start / 24)).map(|daily_data| format!("{} ", day_label(&daily_data.date, self.language))).unwrap_or_default();
+        format!("{} ({}h): {}{:02}:00-{:02}:00", translate(self.language, "Best window"), window_hours, day, hour_of_day(start), hour_of_day(start + window_hours))
+    }
+    fn commute_hours(&self, first_hour: usize) -> Vec<Range<usize>> {
+        // the next time each commute window comes around, starting from the first hour on screen
+        self.commute
+            .iter()
+            .filter_map(|window| {
+                let start = (first_hour..first_hour + 24).find(|h| window.contains_hour(hour_of_day(*h)))?;
+                let end = (start..start + 24).find(|h| !window.contains_hour(hour_of_day(*h)))?;
+                Some(start..end)
+            })
+            .collect()
+    }
+    fn commute_text(&self, first_hour: usize) -> Option<(String, bool)> { // text, whether rain is likely on the way
+        // eg. "Morning: dry, 5°C. Evening: 70% rain, 8°C — take the rain jacket"
+        let mut take_jacket = false;
+        let mut summaries = vec![];
+        for (hours, label) in self.commute_hours(first_hour).into_iter().zip(["Morning", "Evening"]) {
+            let hourly_data: Vec<&HourlyData> = hours.filter_map(|h| self.weather_data.get(&h)).collect();
+            if hourly_data.is_empty() {
+                continue;
+            }
+            let temperature = hourly_data.iter().map(|h| h.temperature_2m).sum::<f64>() / hourly_data.len() as f64;
+            let precipitation_probability = hourly_data.iter().filter_map(|h| h.precipitation_probability).max().unwrap_or(0);
+            take_jacket |= precipitation_probability >= self.precipitation_threshold();
+            let precipitation = if precipitation_probability < self.dry_below() {
+                translate(self.language, "dry").to_owned()
+            } else {
+                format!("{} {}", self.locale.format_percentage(precipitation_probability), translate(self.language, "rain"))
+            };
+            summaries.push(format!("{}: {}, {}{}", translate(self.language, label), precipitation, self.locale.format_temperature(temperature), self.locale.temperature_symbol()));
+        }
+        if summaries.is_empty() {
+            return None;
+        }
+        let mut text = summaries.join(". ");
+        if take_jacket {
+            text.push_str(&format!(" — {}", translate(self.language, "take the rain jacket")));
+        }
+        Some((text, take_jacket))
+    }
+    fn dry_window_text(&self, dry_window: Option<usize>) -> String {
+        // eg. "dry 3h from Mon 20 14:00"
+        let window_hours = self.dry_window_hours();
+        let Some(start) = dry_window else {
+            return format!("{} {}h: {}", translate(self.language, "dry"), window_hours, translate(self.language, "none"));
+        };
+        let day = self.daily_data.get(&(start / 24)).map(|daily_data| format!("{} ", day_label(&daily_data.date, self.language))).unwrap_or_default();
+        format!("{} {}h {} {}{:02}:00", translate(self.language, "dry"), window_hours, translate(self.language, "from"), day, hour_of_day(start))
+    }
+    fn nowcast_text(&self) -> Option<String> {
+        let peak = self.nowcast.iter().flatten().copied().reduce(f64::max)?;
+        if peak <= 0.0 {
+            return Some(format!("{}: {}", translate(self.language, "Next 2h"), translate(self.language, "dry")));
+        }
+        let bars: String = self
+            .nowcast
+            .iter()
+            .map(|precipitation| match precipitation {
+                Some(precipitation) if *precipitation > 0.0 => bar_character(precipitation / NOWCAST_FULL_BAR_MM).max('▁'),
+                _ => ' ',
+            })
+            .flat_map(|bar| [bar, bar])
+            .collect();
+        Some(format!("{} ▕{}▏ 💧 {}", translate(self.language, "Next 2h"), bars, self.locale.format_precipitation(peak)))
+    }
+    fn daily_summary_text(&self, hour: usize) -> Option<(Text, usize)> {
+        let (day_min, day_max) = self.daily_temperature_range(hour);
+        if day_min > day_max {
+            return None; // no data for this day
+        }
+        let (precipitation, peak_wind) = self.daily_precipitation_and_peak_wind(hour);
+        let high_label = translate(self.language, "High");
+        let low_label = translate(self.language, "Low");
+        let high = format!("{}{}", self.locale.format_temperature(day_max), self.locale.temperature_symbol());
+        let low = format!("{}{}", self.locale.format_temperature(day_min), self.locale.temperature_symbol());
+        let precipitation = format!("💧 {}", self.locale.format_precipitation(precipitation));
+        let day = self.daily_data.get(&(hour / 24)).map(|daily_data| format!("{}  ", day_label(&daily_data.date, self.language))).unwrap_or_default();
+        let mut comparisons = vec![];
+        if let (0, Some(yesterday_max)) = (hour / 24, self.yesterday.as_ref().and_then(|yesterday| yesterday.temperature_2m_max)) {
+            comparisons.push(format!("{}{} {}", self.locale.format_temperature_difference(day_max - yesterday_max), self.locale.temperature_symbol(), translate(self.language, "vs yesterday")));
+        }
+        // eg. "5°C above normal", against the average high of the same day in the last 10 years
+        if let Some(anomaly) = self.daily_data.get(&(hour / 24)).and_then(|daily_data| anomaly(&self.climate_normals, &daily_data.date, day_max)) {
+            let direction = if anomaly < 0.0 { "below normal" } else { "above normal" };
+            comparisons.push(format!("{}{} {}", self.locale.format_temperature_difference(anomaly.abs()).trim_start_matches('+'), self.locale.temperature_symbol(), translate(self.language, direction)));
+        }
+        let comparisons_text = if comparisons.is_empty() { String::new() } else { format!(" ({})", comparisons.join(", ")) };
+        let summary = format!("{}{} {}{}  {} {}  {}  💨 {}", day, high_label, high, comparisons_text, low_label, low, precipitation, self.locale.format_wind_speed(peak_wind));
+        let high_start = day.chars().count() + high_label.chars().count() + 1;
+        let low_start = high_start + high.chars().count() + comparisons_text.chars().count() + 2 + low_label.chars().count() + 1;
+        let precipitation_start = low_start + low.chars().count() + 2;
+        let text = Text::new(&summary)
+            .color_range(self.palette.hour(), ..day.chars().count())
+            .color_range(temperature_gradient_color(day_max, day_min, day_max, self.palette), high_start..high_start + high.chars().count())
+            .color_range(temperature_gradient_color(day_min, day_min, day_max, self.palette), low_start..low_start + low.chars().count())
+            .color_range(self.palette.precipitation(), precipitation_start..precipitation_start + precipitation.chars().count());
+        Some((text, display_width(&summary)))
+    }
+    fn render_raw_response<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize, scroll: usize) {
+        let highlight = self.palette.highlight();
+        let controls_text = translate(self.language, "<↓↑> scroll, <PgDn/PgUp> page, <←→> request, <Esc> close");
+        renderer.print_text(controls_text_with_keys_highlighted(controls_text, highlight), 0, rows);
+        let responses = self.request_log.responses();
+        let Some(response) = responses.get(self.raw_response_index) else {
+            let no_response = translate(self.language, "No response yet");
+            renderer.print_text(Text::new(no_response).color_range(highlight, ..), (cols / 2).saturating_sub(display_width(no_response) / 2), rows / 2);
+            return;
+        };
+        let lines = &response.lines;
+        let visible_rows = rows.saturating_sub(1);
+        let last_shown = (scroll + visible_rows).min(lines.len());
+        // eg. "weather 200 (1234 bytes) lines 1-15 / 320"
+        let header = format!("{} {} ({} bytes) {} {}-{} / {}", response.endpoint, response.status_code, response.bytes, translate(self.language, "lines"), (scroll + 1).min(last_shown), last_shown, lines.len());
+        renderer.print_text(Text::new(&header).color_range(highlight, ..), 0, 0);
+        for (row, line) in lines.iter().skip(scroll).take(visible_rows).enumerate() {
+            let line: String = line.chars().take(cols).collect();
+            renderer.print_text(Text::new(&line), 0, row + 1);
+        }
+    }
+    fn render_debug_overlay<R: Renderer>(&self, renderer: &mut R) {
+        let highlight = self.palette.highlight();
+        let mut requests_table = Table::new().add_styled_row(vec![
+            Text::new("REQUEST").color_range(highlight, ..),
+            Text::new("STATUS").color_range(highlight, ..),
+            Text::new("DURATION").color_range(highlight, ..),
+            Text::new("BYTES").color_range(highlight, ..),
+        ]);
+        for request in self.request_log.recent() {
+            requests_table = requests_table.add_row(vec![
+                request.endpoint.clone(),
+                request.status_code.map(|s| s.to_string()).unwrap_or_else(|| "pending".to_owned()),
+                request.duration.map(|d| format!("{}ms", d.as_millis())).unwrap_or_else(|| "-".to_owned()),
+                request.bytes.map(|b| b.to_string()).unwrap_or_else(|| "-".to_owned()),
+            ]);
+        }
+        renderer.print_table(requests_table, 0, 0);
+    }
+}
+
+pub(crate) fn shows_tab_bar(rows: usize) -> bool {
+    // only when it doesn't overlap the location header
+    rows / 2 > 6
+}
+
+pub(crate) fn day_label(date: &str, language: Language) -> String {
+    // eg. "2023-11-19" -> "Sun 19"
+    match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        Ok(date) => {
+            let weekday = match date.weekday() {
+                Weekday::Mon => "Mon",
+                Weekday::Tue => "Tue",
+                Weekday::Wed => "Wed",
+                Weekday::Thu => "Thu",
+                Weekday::Fri => "Fri",
+                Weekday::Sat => "Sat",
+                Weekday::Sun => "Sun",
+            };
+            format!("{} {}", translate(language, weekday), date.day())
+        }
+        Err(_) => date.to_owned(),
+    }
+}
+
+// lines of differently colored parts as the columns of a table, in the middle of the pane
+pub(crate) fn print_centered_lines<R: Renderer>(renderer: &mut R, lines: Vec<Vec<(Text, usize)>>, rows: usize, cols: usize) {
+    let columns = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let column_widths: Vec<usize> = (0..columns).map(|i| lines.iter().filter_map(|line| line.get(i)).map(|(_, width)| *width).max().unwrap_or(0)).collect();
+    let table_width = column_widths.iter().sum::<usize>() + columns.saturating_sub(1);
+    let top = (rows / 2).saturating_sub(lines.len().div_ceil(2));
+    let mut table = Table::new().add_row(vec![" "; columns]);
+    for line in lines {
+        let mut cells: Vec<Text> = line.into_iter().map(|(text, _)| text).collect();
+        cells.resize_with(columns, || Text::new(" "));
+        table = table.add_styled_row(cells);
+    }
+    renderer.print_table(table, (cols / 2).saturating_sub(table_width / 2), top);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::accuracy::Accuracy;
+    use crate::air_quality::{AirQuality, AirQualityThresholds};
+    use crate::alerts::{Alert, AlertLevel};
+    use crate::aurora::KpIndex;
+    use crate::commute::parse_commute;
+    use crate::favorites::Favorite;
+    use crate::error::{RequestKind, WeatherPalError};
+    use crate::input::LineInput;
+    use crate::palette::Palette;
+    use crate::parse::{parse_weather_data, PAST_DAYS};
+    use crate::profile::Profile;
+    use crate::solar::PvSystem;
+    use crate::width::char_width;
+    use std::time::Duration;
+
+    const WIDE_CHARACTER_TAIL: char = '\0';
+
+    struct SnapshotRenderer {
+        screen: Vec<Vec<char>>,
+    }
+
+    impl SnapshotRenderer {
+        fn new(rows: usize, cols: usize) -> Self {
+            SnapshotRenderer { screen: vec![vec![' '; cols]; rows] }
+        }
+        fn write(&mut self, line: &str, x: usize, y: usize) {
+            // like the terminal, clamp the cursor to the last line, wide characters cover the cell after them
+            let y = y.min(self.screen.len() - 1);
+            let row = &mut self.screen[y];
+            let mut x = x;
+            for character in line.chars() {
+                let width = char_width(character);
+                for (i, cell_character) in [character, WIDE_CHARACTER_TAIL].iter().take(width).enumerate() {
+                    if let Some(cell) = row.get_mut(x + i) {
+                        *cell = *cell_character;
+                    }
+                }
+                x += width;
+            }
+        }
+        fn snapshot(&self) -> String {
+            self.screen
+                .iter()
+                .map(|row| row.iter().filter(|c| **c != WIDE_CHARACTER_TAIL).collect::<String>().trim_end().to_owned())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+
+    fn decode_text(serialized: &str) -> String {
+        let bytes: Vec<u8> = serialized
+            .rsplit('$')
+            .next()
+            .unwrap_or("")
+            .trim_start_matches('x')
+            .split(',')
+            .filter(|b| !b.is_empty())
+            .map(|b| b.parse().unwrap())
+            .collect();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    impl Renderer for SnapshotRenderer {
+        fn print_text(&mut self, text: Text, x: usize, y: usize) {
+            self.write(&decode_text(&text.serialize()), x, y);
+        }
+        fn print_table(&mut self, table: Table, x: usize, y: usize) {
+            let serialized = table.serialize();
+            let mut parts = serialized.trim_end_matches("\u{1b}\\").split(';');
+            let columns: usize = parts.next().unwrap().parse().unwrap();
+            let cells: Vec<(bool, String)> = parts.skip(1).map(|cell| (cell.starts_with('x'), decode_text(cell))).collect();
+            let mut column_widths = vec![0; columns];
+            for (i, (_, cell)) in cells.iter().enumerate() {
+                column_widths[i % columns] = column_widths[i % columns].max(display_width(cell));
+            }
+            for (row_index, row) in cells.chunks(columns).enumerate() {
+                let line = row
+                    .iter()
+                    .zip(column_widths.iter())
+                    .map(|((_, cell), width)| format!("{}{}", cell, " ".repeat(width - display_width(cell))))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                self.write(&line, x, y + row_index);
+                // highlighted rows are marked in the gutter left of the table
+                if x > 0 && row.iter().all(|(selected, _)| *selected) {
+                    self.write("▌", x - 1, y + row_index);
+                }
+            }
+        }
+        fn print_ribbon(&mut self, text: Text, x: usize, y: usize) {
+            let serialized = text.serialize();
+            let ribbon = if serialized.starts_with('x') {
+                format!("[ {} ]", decode_text(&serialized))
+            } else {
+                format!("  {}  ", decode_text(&serialized))
+            };
+            self.write(&ribbon, x, y);
+        }
+    }
+
+    // every text and table cell as printed, whether it is in reverse video and whether it is in bold (ie. in an emphasis color)
+    #[derive(Default)]
+    struct StyleRenderer {
+        cells: Vec<(String, bool, bool)>,
+    }
+
+    fn styled_cell(serialized: &str) -> (String, bool, bool) {
+        let emphasized = serialized.trim_start_matches('x').rsplit_once('$').map(|(indices, _)| indices.split('$').any(|i| !i.is_empty())).unwrap_or(false);
+        (decode_text(serialized), serialized.starts_with('x'), emphasized)
+    }
+
+    impl Renderer for StyleRenderer {
+        fn print_text(&mut self, text: Text, _x: usize, _y: usize) {
+            self.cells.push(styled_cell(&text.serialize()));
+        }
+        fn print_table(&mut self, table: Table, _x: usize, _y: usize) {
+            let serialized = table.serialize();
+            self.cells.extend(serialized.trim_end_matches("\u{1b}\\").split(';').skip(2).map(styled_cell));
+        }
+        fn print_ribbon(&mut self, text: Text, _x: usize, _y: usize) {
+            self.cells.push(styled_cell(&text.serialize()));
+        }
+    }
+
+    fn render_styles(state: &State, rows: usize, cols: usize, hour: usize) -> Vec<(String, bool, bool)> {
+        let mut renderer = StyleRenderer::default();
+        state.render_with(&mut renderer, rows, cols, hour);
+        renderer.cells
+    }
+
+    pub(crate) fn render_snapshot(state: &State, rows: usize, cols: usize, hour: usize) -> String {
+        let mut renderer = SnapshotRenderer::new(rows, cols);
+        state.render_with(&mut renderer, rows, cols, hour);
+        renderer.snapshot()
+    }
+
+    pub(crate) fn state_with_forecast() -> State {
+        let forecast = parse_weather_data(include_bytes!("../fixtures/forecast_with_synthetic_series.json").to_vec(), PAST_DAYS).unwrap();
+        State {
+            weather_data: forecast.hourly,
+            daily_data: forecast.daily,
+            yesterday: forecast.yesterday,
+            nowcast: forecast.nowcast,
+            weather_location: Some("Vienna, Austria".to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_controls_before_first_fetch() {
+        let expected = [
+            "",
+            "",
+            "   Press <ENTER> to run, <Ctrl-w> to enter a new location",
+            "",
+            "",
+        ];
+        assert_eq!(render_snapshot(&State::default(), 5, 60, 0), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_errors_with_controls() {
+        let state = State {
+            error: Some(WeatherPalError::Http(RequestKind::Weather, 502, "Bad Gateway".to_owned())),
+            ..Default::default()
+        };
+        let expected = [
+            "",
+            "",
+            "",
+            "             Failed weather web request (502): Bad Gateway",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 6, 70, 0), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_location_suggestions() {
+        let state = State {
+            location_suggestions: vec![
+                (47.42, 9.37, "Sankt Gallen, Saint Gallen, Switzerland".to_owned()),
+                (47.37, 8.54, "Zurich, Switzerland".to_owned()),
+            ],
+            ..Default::default()
+        };
+        let expected = [
+            "",
+            "",
+            "",
+            "                    No matching location found, did you mean:",
+            "",
+            "                   <1> Sankt Gallen, Saint Gallen, Switzerland",
+            "                   <2> Zurich, Switzerland",
+            "",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 10, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_location_prompt() {
+        let state = State {
+            location_being_typed: Some(LineInput::from("Tel Av")),
+            ..Default::default()
+        };
+        let expected = [
+            "",
+            "",
+            "               Enter desired location: Tel Av_",
+            "",
+            "",
+        ];
+        assert_eq!(render_snapshot(&state, 5, 60, 0), expected.join("\n"));
+    }
+
+    #[test]
+    fn says_when_the_location_was_not_found() {
+        let state = State {
+            location_being_typed: Some(LineInput::from("Sprngfield")),
+            location_not_found: Some("Sprngfield".to_owned()),
+            ..Default::default()
+        };
+        let expected = [
+            "",
+            "       Couldn't find 'Sprngfield' — check spelling or try adding a country",
+            "",
+            "                       Enter desired location: Sprngfield_",
+            "",
+            "",
+            "",
+        ];
+        assert_eq!(render_snapshot(&state, 7, 80, 0), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_matches_below_the_location_prompt() {
+        let state = State {
+            location_being_typed: Some(LineInput::from("Zur")),
+            typeahead: vec![
+                (47.37, 8.54, "Zurich, Switzerland".to_owned()),
+                (51.83, 5.29, "Zuren, Netherlands".to_owned()),
+            ],
+            typeahead_cursor: Some(1),
+            ..Default::default()
+        };
+        let expected = [
+            "",
+            "",
+            "",
+            "",
+            "                Enter desired location: Zur_",
+            "",
+            "                      Zurich, Switzerland",
+            "                    > Zuren, Netherlands",
+        ];
+        assert_eq!(render_snapshot(&state, 8, 60, 0), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_the_cursor_in_the_middle_of_the_location_prompt() {
+        let mut location_being_typed = LineInput::from("Tel Aviv");
+        location_being_typed.handle_key(&Key::Home);
+        location_being_typed.handle_key(&Key::Right);
+        let state = State {
+            location_being_typed: Some(location_being_typed),
+            ..Default::default()
+        };
+        let expected = [
+            "",
+            "",
+            "              Enter desired location: T▏el Aviv",
+            "",
+            "",
+        ];
+        assert_eq!(render_snapshot(&state, 5, 60, 0), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_forecast_table() {
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score",
+            "              11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "              12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "              13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "              14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "              15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "              16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "              17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "              18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state_with_forecast(), 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn compares_the_high_with_the_climate_normal() {
+        let state = State {
+            climate_normals: BTreeMap::from([("11-19".to_owned(), 4.6)]),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "                                 Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday, 5.2°C above normal)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "                         Sun 19 Sky           Temp      Rain   Wind       Score",
+            "                         11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%  ↑  11.1kph ▄5",
+            "                         Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 6, 100, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn wraps_the_sentences_to_the_pane() {
+        let state = State {
+            screen_reader: true,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "Vienna, Austria. High 9.8 degrees, low 2.3 degrees, 0mm of precipitation.",
+            "11:00, 7.5 degrees, partly cloudy, 0 percent chance of precipitation, wind",
+            "11.1kph blowing from SW.",
+            "12:00, 8.7 degrees, partly cloudy, 26 percent chance of precipitation, wind",
+            "12.4kph blowing from SW.",
+            "13:00, 9.1 degrees, clear sky, 10 percent chance of precipitation, wind 8.4kph",
+            "blowing from S.",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 8, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_only_the_summary_in_a_single_line_pane() {
+        let state = State {
+            format: Some("{location}: {icon} {temp}{unit}, {desc} ({low}-{high}) 💧 {precip}% {wind}".to_owned()),
+            ..state_with_forecast()
+        };
+        assert_eq!(render_snapshot(&state, 1, 80, 10), "        Vienna, Austria: ⛅ 7.5°C, PARTLY CLOUDY (2.3-9.8) 💧 0% 11.1kph");
+        assert_eq!(render_snapshot(&state_with_forecast(), 1, 40, 10), "               ⛅ 7.5°C 0%");
+        let compact = State {
+            compact: true,
+            ..state_with_forecast()
+        };
+        assert_eq!(render_snapshot(&compact, 16, 40, 10).trim_end(), "               ⛅ 7.5°C 0%");
+    }
+
+    #[test]
+    fn renders_high_contrast_with_shape_cues() {
+        let state = State {
+            palette: Palette::HighContrast,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                      Vienna, Austria │ ☀ 9h 10m (-2 min)",
+            "               Mon 20  High 6.7°C  Low 2.3°C  💧 5.4mm  💨 22.4kph",
+            "         Mon 20 Sky                     Temp      Rain    Wind       Score",
+            "        ▌07:00  RAIN SHOWERS (SLIGHT) ! 3.2  °C → 💧 68%  ↑  15.3kph ▁1",
+            "        ▌08:00  SLIGHT RAIN !           3.9  °C ↑ 💧 65%  ↑  20.3kph ▁1",
+            "        ▌09:00  MODERATE DRIZZLE !      4.5  °C ↑ 💧 78%  ↑  13.9kph ▁1",
+            "        ▌10:00  MODERATE DRIZZLE !      4.9  °C → 💧 70%  ↗  21.1kph ▁1",
+            "         11:00  OVERCAST                5.3  °C → 💧 49%  ↑  14.1kph ▂2",
+            "         12:00  PARTLY CLOUDY           5.7  °C → 💧 38%  ↑  17.1kph ▂2",
+            "        ▌13:00  LIGHT DRIZZLE !         6.3  °C ↑ 💧 51%  ↗  18.5kph ▂2",
+            "        ▌14:00  MODERATE DRIZZLE !      6.6  °C → 💧 64%  ↗  21.5kph ▁1",
+            "                 Rain likely now, until ~11:00, then dry for ~2h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 30), expected.join("\n"));
+    }
+
+    #[test]
+    fn emphasizes_in_bold_and_reverse_video_with_high_contrast() {
+        // whether the hour and the weather of a row are in reverse video and in bold
+        let row_style = |state: &State, hour: &str| {
+            let cells = render_styles(state, 16, 80, 10);
+            let start = cells.iter().position(|(cell, _, _)| cell.trim() == hour).unwrap();
+            cells[start..start + 2].iter().map(|(_, reversed, bold)| (*reversed, *bold)).collect::<Vec<_>>()
+        };
+        let default = State {
+            precipitation_threshold: Some(20),
+            ..state_with_forecast()
+        };
+        let high_contrast = State {
+            palette: Palette::HighContrast,
+            precipitation_threshold: Some(20),
+            ..state_with_forecast()
+        };
+        // 12:00 is likely to see precipitation, 11:00 isn't
+        assert_eq!(row_style(&default, "12:00"), vec![(true, true), (true, false)]);
+        assert_eq!(row_style(&high_contrast, "12:00"), vec![(true, true), (true, true)]);
+        assert_eq!(row_style(&high_contrast, "11:00"), vec![(false, true), (false, false)]);
+        let tinted = State {
+            comfort_tint: true,
+            ..high_contrast
+        };
+        // a cold row is bold rather than in a color of its own
+        assert_eq!(row_style(&tinted, "11:00"), vec![(false, true), (false, true)]);
+    }
+
+    #[test]
+    fn reads_the_forecast_out_in_sentences() {
+        let state = State {
+            screen_reader: true,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "Vienna, Austria. High 9.8 degrees, low 2.3 degrees, 0mm of precipitation.",
+            "11:00, 7.5 degrees, partly cloudy, 0 percent chance of precipitation, wind 11.1kph blowing from SW.",
+            "12:00, 8.7 degrees, partly cloudy, 26 percent chance of precipitation, wind 12.4kph blowing from SW.",
+            "13:00, 9.1 degrees, clear sky, 10 percent chance of precipitation, wind 8.4kph blowing from S.",
+            "14:00, 9.2 degrees, mainly clear, 7 percent chance of precipitation, wind 6.5kph blowing from S.",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 6, 100, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn highlights_hours_likely_to_see_precipitation() {
+        let mut state = state_with_forecast();
+        state.precipitation_threshold = Some(20);
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score",
+            "              11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "             ▌12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "              13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "              14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "              15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "              16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "              17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "             ▌18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "               Rain likely in ~1h, until ~13:00, then dry for ~5h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn sorts_the_hours_on_screen_by_temperature() {
+        let state = State {
+            sort_order: SortOrder::Temperature,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp ▼      Rain    Wind       Score",
+            "              15:00  MAINLY CLEAR  9.8    °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "              16:00  MAINLY CLEAR  9.3    °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "              17:00  MAINLY CLEAR  9.3    °C → 💧 0%   ↑  3.2kph  ▅6",
+            "              14:00  MAINLY CLEAR  9.2    °C → 💧 7%   ↑  6.5kph  ▅6",
+            "              13:00  CLEAR SKY     9.1    °C → 💧 10%  ↑  8.4kph  ▄5",
+            "              12:00  PARTLY CLOUDY 8.7    °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "              18:00  PARTLY CLOUDY 8.6    °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "              11:00  PARTLY CLOUDY 7.5    °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn advises_on_outdoor_exercise_when_the_air_is_unhealthy() {
+        let mut state = State {
+            air_quality_guidance: true,
+            ..state_with_forecast()
+        };
+        for hour in 0..48 {
+            let ozone = if (13..17).contains(&hour) { 110.0 + hour as f64 } else { 60.0 };
+            state.air_quality.insert(hour, AirQuality { pm2_5: Some(8.0), ozone: Some(ozone), pollen: vec![] });
+        }
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "           ⚠ Limit outdoor exercise 14:00-18:00 (ozone up to 126µg/m³)",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score",
+            "              11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "              12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "              13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "              14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "              15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "              16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "              17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "              18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+        state.air_quality_thresholds = AirQualityThresholds::from_config("ozone:70");
+        // sensitive groups set lower thresholds
+        assert_eq!(render_snapshot(&state, 16, 80, 10).lines().nth(1), Some("           ⚠ Avoid outdoor exercise 14:00-18:00 (ozone up to 126µg/m³)"));
+    }
+
+    #[test]
+    fn shows_the_wind_at_turbine_and_drone_heights() {
+        let mut state = State {
+            wind_heights: vec![80, 180],
+            ..state_with_forecast()
+        };
+        for (hour, hourly_data) in state.weather_data.iter_mut() {
+            hourly_data.wind_speed_80m = Some(20.0 + *hour as f64);
+            hourly_data.wind_speed_180m = if *hour == 12 { None } else { Some(30.0 + *hour as f64 * 2.0) };
+        }
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "   Sun 19 Sky           Temp      Rain    Wind       Score Wind aloft",
+            "   11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5    🌬 80m 30kph 180m 50kp",
+            "   12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4    🌬 80m 31kph 180m 52kp",
+            "   13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5    🌬 80m 32kph 180m -",
+            "   14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6    🌬 80m 33kph 180m 56kp",
+            "   15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7    🌬 80m 34kph 180m 58kp",
+            "   16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6    🌬 80m 35kph 180m 60kp",
+            "   17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6    🌬 80m 36kph 180m 62kp",
+            "   18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4    🌬 80m 37kph 180m 64kp",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_other_hourly_variables_as_columns() {
+        let mut state = State {
+            hourly_extra: vec!["pressure_msl".to_owned(), "uv_index".to_owned()],
+            ..state_with_forecast()
+        };
+        state.hourly_units.insert("pressure_msl".to_owned(), "hPa".to_owned());
+        for (hour, hourly_data) in state.weather_data.iter_mut() {
+            hourly_data.extras.insert("pressure_msl".to_owned(), 1013.25 - *hour as f64);
+            if *hour != 12 {
+                hourly_data.extras.insert("uv_index".to_owned(), *hour as f64 / 10.0);
+            }
+        }
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                                 Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "               Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "      Sun 19 Sky           Temp      Rain    Wind       Score pressure_msl           uv_index",
+            "      11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5    pressure_msl 1003.3hPa uv_index 1",
+            "      12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4    pressure_msl 1002.3hPa uv_index 1.1",
+            "      13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5    pressure_msl 1001.3hPa uv_index -",
+            "      14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6    pressure_msl 1000.3hPa uv_index 1.3",
+            "      15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7    pressure_msl 999.3hPa  uv_index 1.4",
+            "      16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6    pressure_msl 998.3hPa  uv_index 1.5",
+            "      17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6    pressure_msl 997.3hPa  uv_index 1.6",
+            "      18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4    pressure_msl 996.3hPa  uv_index 1.7",
+            "                         Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                                 Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 100, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn highlights_the_best_window_for_activities() {
+        let mut state = state_with_forecast();
+        state.show_best_window = true;
+        assert_eq!(render_snapshot(&state, 16, 80, 10).lines().nth(13), Some("                       No suitable window in the next 48h"));
+        for hourly_data in state.weather_data.values_mut() {
+            hourly_data.temperature_2m += 5.0;
+        }
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "    Sun 19  High 14.8°C (+8.2°C vs yesterday)  Low 7.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score",
+            "              11:00  PARTLY CLOUDY 12.5 °C ↑ 💧 0%   ↑  11.1kph ▆8",
+            "              12:00  PARTLY CLOUDY 13.7 °C ↑ 💧 26%  ↗  12.4kph ▅6",
+            "              13:00  CLEAR SKY     14.1 °C → 💧 10%  ↑  8.4kph  ▆8",
+            "              14:00  MAINLY CLEAR  14.2 °C → 💧 7%   ↑  6.5kph  ▇9",
+            "              15:00  MAINLY CLEAR  14.8 °C ↑ 💧 0%   ↑  6.8kph  █10",
+            "              16:00  MAINLY CLEAR  14.3 °C ↓ 💧 0%   ↑  5.3kph  █10",
+            "              17:00  MAINLY CLEAR  14.3 °C → 💧 0%   ↑  3.2kph  █10",
+            "              18:00  PARTLY CLOUDY 13.6 °C ↓ 💧 24%  ↑  3.4kph  ▆7",
+            "                      Best window (2h): Sun 19 15:00-17:00",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_soil_columns_for_gardeners() {
+        let state = State {
+            gardening: true,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                            Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "          Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "             Sun 19 Sky           Temp      Rain    Wind       Score Soil",
+            "             11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5    🌱 6.2°C 30%",
+            "             12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4    🌱 7°C 30%",
+            "             13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5    🌱 7.7°C 30%",
+            "             14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6    🌱 8.2°C 30%",
+            "             15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7    🌱 8.8°C 30%",
+            "             16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6    🌱 9°C 30%",
+            "             17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6    🌱 9.2°C 30%",
+            "             18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4    🌱 9.1°C 30%",
+            "                    Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                            Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 90, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_evapotranspiration_for_gardeners() {
+        let state = State {
+            gardening: true,
+            view: View::Daily,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "  Hourly  [ Daily ]  Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "",
+            "",
+            "     Sun 19 OVERCAST                9.8°C  2.3°C  💧 0mm     0h  🌿 0.7mm",
+            "     Mon 20 RAIN SHOWERS (SLIGHT)   6.7°C  2.3°C  💧 5.4mm   15h 🌿 0.4mm",
+            "     Tue 21 SLIGHT RAIN             7.1°C  -1.3°C 💧 0.5mm   1h  🌿 0.5mm",
+            "     Wed 22 RAIN SHOWERS (MODERATE) 8.6°C  2.4°C  💧 29.4mm  23h 🌿 0.3mm",
+            "     Thu 23 SLIGHT RAIN             10.7°C 5.4°C  💧 2.3mm   7h  🌿 0.5mm",
+            "     Fri 24 OVERCAST                11.7°C 2.5°C  💧 0mm     0h  🌿 0.7mm",
+            "     Sat 25 SLIGHT RAIN             5.6°C  -0.5°C 💧 2.2mm   6h  🌿 0.4mm",
+            "",
+            "                      Golden hour 06:58-07:58, 15:08-16:08",
+            "                       Blue hour 06:28-06:58, 16:08-16:38",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_estimated_solar_output() {
+        let state = State {
+            pv_system: Some(PvSystem { peak_power_kw: 5.0, tilt: 35.0, azimuth: 0.0 }),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                                 Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "               Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score Solar",
+            "              11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5    ☀ 234W/m² ⚡ 1.3kW",
+            "              12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4    ☀ 247W/m² ⚡ 1.4kW",
+            "              13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5    ☀ 234W/m² ⚡ 1.3kW",
+            "              14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6    ☀ 208W/m² ⚡ 1.2kW",
+            "              15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7    ☀ 143W/m² ⚡ 0.8kW",
+            "              16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6    ☀ 78W/m² ⚡ 0.4kW",
+            "              17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6    ☀ 0W/m² ⚡ 0kW",
+            "              18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4    ☀ 0W/m² ⚡ 0kW",
+            "                         Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                                 Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 100, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_elevation_in_the_header() {
+        let state = State {
+            elevation: Some(2962.0),
+            ..state_with_forecast()
+        };
+        assert_eq!(render_snapshot(&state, 16, 80, 10).lines().nth(2), Some("                  Vienna, Austria  ⛰ 2962m  ☀ 9h 10m (-2 min)"));
+    }
+
+    #[test]
+    fn renders_forecast_in_a_narrow_pane() {
+        let expected = [
+            "",
+            "",
+            "Vienna, Austria  ☀ 9h 10m (-2",
+            "Sun 19  High 9.8°C (+3.2°C vs",
+            "Sun 19 Sky           Temp",
+            "23:00  MAINLY CLEAR  4.1  °C ↓",
+            "00:00  CLEAR SKY     3.8  °C →",
+            "01:00  SLIGHT RAIN   2.6  °C ↓",
+            "02:00  LIGHT DRIZZLE 2.6  °C →",
+            "Rain likely in ~2h, until ~05:",
+            "Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧",
+            "Press <ENTER> to reload, <Ctrl",
+        ];
+        assert_eq!(render_snapshot(&state_with_forecast(), 12, 30, 22), expected.join("\n"));
+    }
+
+    #[test]
+    fn fits_more_hours_into_a_tall_pane() {
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score",
+            "              11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "              12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "              13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "              14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "              15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "              16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "              17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "              18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "              19:00  MAINLY CLEAR  7.6  °C ↓ 💧 13%  ↖  6.6kph  ▃4",
+            "              20:00  CLEAR SKY     6.9  °C ↓ 💧 0%   ↑  6.4kph  ▄5",
+            "              21:00  PARTLY CLOUDY 5.7  °C ↓ 💧 12%  ↑  3.8kph  ▂3",
+            "              22:00  PARTLY CLOUDY 5.4  °C → 💧 17%  ↑  5.4kph  ▂3",
+            "              23:00  MAINLY CLEAR  4.1  °C ↓ 💧 0%   ↖  6kph    ▂3",
+            "              00:00  CLEAR SKY     3.8  °C → 💧 19%  ↑  12.3kph ▂2",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state_with_forecast(), 22, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn explains_the_permissions_before_asking_for_them() {
+        let state = State {
+            permission_rationale: true,
+            ..Default::default()
+        };
+        let expected = [
+            "",
+            "",
+            "                               Weather-Pal will ask for permission to:",
+            "",
+            "  • access the web, to find the location and fetch its forecast from Open-Meteo",
+            "  • run commands: timedatectl to find the location from the system timezone, and the browser with o",
+            "  • change the application state, only to close this pane with q or Esc and set its title",
+            "",
+            "            <ENTER> continue, <n> continue without running commands and type the location",
+            "",
+        ];
+        assert_eq!(render_snapshot(&state, 10, 100, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn changes_settings_in_the_pane() {
+        let state = State {
+            settings_cursor: Some(2),
+            profile: Some(Profile::Ski),
+            refresh_interval_minutes: Some(30),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "",
+            "",
+            "                          Settings",
+            "",
+            "               Units               ◂ metric ▸",
+            "               Theme               ◂ default ▸",
+            "               Columns             ◂ ski ▸",
+            "               Soil column         ◂ off ▸",
+            "               Refresh interval    ◂ 30m ▸",
+            "               Timezone discovery  ◂ on ▸",
+            "",
+            "<↓↑> select, <←→> change, <Esc> close",
+        ];
+        assert_eq!(render_snapshot(&state, 12, 60, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_the_last_raw_response_over_an_error() {
+        let mut state = State {
+            error: Some(WeatherPalError::Http(RequestKind::Geocode, 429, "Too many concurrent requests".to_owned())),
+            raw_response_scroll: Some(2),
+            ..Default::default()
+        };
+        state.request_log.keep_response("geocode", 429, br#"{"error":true,"reason":"Too many concurrent requests, please wait a moment and try again"}"#);
+        let expected = [
+            "geocode 429 (90 bytes) lines 3-4 / 4",
+            "  \"reason\": \"Too many concurrent requests, please wait a mom",
+            "}",
+            "",
+            "",
+            "<↓↑> scroll, <PgDn/PgUp> page, <←→> request, <Esc> close",
+        ];
+        assert_eq!(render_snapshot(&state, 6, 60, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_debug_overlay_over_the_forecast() {
+        let mut state = state_with_forecast();
+        state.show_debug_overlay = true;
+        state.request_log.started("geocode", "https://geocoding-api.open-meteo.com/v1/search?name=Vienna");
+        state.request_log.started("weather", "https://api.open-meteo.com/v1/forecast");
+        if let Some(geocode_request) = state.request_log.recent.front_mut() {
+            geocode_request.status_code = Some(200);
+            geocode_request.duration = Some(Duration::from_millis(312));
+            geocode_request.bytes = Some(641);
+        }
+        let expected = [
+            "REQUEST STATUS  DURATION BYTEShart",
+            "geocode 200     312ms    641",
+            "weather pending -        -     Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score",
+            "              11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "              12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "              13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "              14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "              15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "              16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "              17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "              18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_incomplete_hours() {
+        let mut state = state_with_forecast();
+        state.weather_data.remove(&11);
+        if let Some(hourly_data) = state.weather_data.get_mut(&12) {
+            hourly_data.precipitation_probability = None;
+            hourly_data.wind_speed_10m = None;
+            hourly_data.wmo_code = None;
+        }
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "               Sun 19 Sky           Temp      Rain    Wind       Score",
+            "               11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "               13:00                9.1  °C   💧 -    ↑  -       ▅6",
+            "               14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "               15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "               16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "               17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "               18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "               19:00  MAINLY CLEAR  7.6  °C ↓ 💧 13%  ↖  6.6kph  ▃4",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_daily_forecast() {
+        let state = State {
+            view: View::Daily,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "  Hourly  [ Daily ]  Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "",
+            "",
+            "          Sun 19 OVERCAST                9.8°C  2.3°C  💧 0mm     0h",
+            "          Mon 20 RAIN SHOWERS (SLIGHT)   6.7°C  2.3°C  💧 5.4mm   15h",
+            "          Tue 21 SLIGHT RAIN             7.1°C  -1.3°C 💧 0.5mm   1h",
+            "          Wed 22 RAIN SHOWERS (MODERATE) 8.6°C  2.4°C  💧 29.4mm  23h",
+            "          Thu 23 SLIGHT RAIN             10.7°C 5.4°C  💧 2.3mm   7h",
+            "          Fri 24 OVERCAST                11.7°C 2.5°C  💧 0mm     0h",
+            "          Sat 25 SLIGHT RAIN             5.6°C  -0.5°C 💧 2.2mm   6h",
+            "",
+            "                      Golden hour 06:58-07:58, 15:08-16:08",
+            "                       Blue hour 06:28-06:58, 16:08-16:38",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_where_the_sun_is_under_the_daily_forecast() {
+        let state = State {
+            view: View::Daily,
+            geolocation: Some((48.2, 16.37)),
+            clock: Some(NaiveDate::from_ymd_opt(2023, 11, 19).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc()),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "  Hourly  [ Daily ]  Week    Chart",
+            "",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "",
+            "",
+            "          Sun 19 OVERCAST                9.8°C  2.3°C  💧 0mm     0h",
+            "          Mon 20 RAIN SHOWERS (SLIGHT)   6.7°C  2.3°C  💧 5.4mm   15h",
+            "          Tue 21 SLIGHT RAIN             7.1°C  -1.3°C 💧 0.5mm   1h",
+            "          Wed 22 RAIN SHOWERS (MODERATE) 8.6°C  2.4°C  💧 29.4mm  23h",
+            "          Thu 23 SLIGHT RAIN             10.7°C 5.4°C  💧 2.3mm   7h",
+            "          Fri 24 OVERCAST                11.7°C 2.5°C  💧 0mm     0h",
+            "          Sat 25 SLIGHT RAIN             5.6°C  -0.5°C 💧 2.2mm   6h",
+            "",
+            "                      Golden hour 06:58-07:58, 15:08-16:08",
+            "                       Blue hour 06:28-06:58, 16:08-16:38",
+            "         ☀ 20° above the horizon, S 200° · sunrise 06:58 · sunset 16:08",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 18, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_how_accurate_the_forecast_was_under_the_daily_forecast() {
+        let state = State {
+            view: View::Daily,
+            forecast_accuracy: Some(Accuracy { hours: 18, temperature_error: 1.24, rain_hours: 18, rain_right: 16 }),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "  Hourly  [ Daily ]  Week    Chart",
+            "",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "",
+            "",
+            "          Sun 19 OVERCAST                9.8°C  2.3°C  💧 0mm     0h",
+            "          Mon 20 RAIN SHOWERS (SLIGHT)   6.7°C  2.3°C  💧 5.4mm   15h",
+            "          Tue 21 SLIGHT RAIN             7.1°C  -1.3°C 💧 0.5mm   1h",
+            "          Wed 22 RAIN SHOWERS (MODERATE) 8.6°C  2.4°C  💧 29.4mm  23h",
+            "          Thu 23 SLIGHT RAIN             10.7°C 5.4°C  💧 2.3mm   7h",
+            "          Fri 24 OVERCAST                11.7°C 2.5°C  💧 0mm     0h",
+            "          Sat 25 SLIGHT RAIN             5.6°C  -0.5°C 💧 2.2mm   6h",
+            "",
+            "                      Golden hour 06:58-07:58, 15:08-16:08",
+            "                       Blue hour 06:28-06:58, 16:08-16:38",
+            "",
+            "           🎯 Forecast vs actual: ±1.2°C, rain right in 16 of 18 hours",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 19, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_the_chance_to_see_the_aurora_at_high_latitudes() {
+        let mut state = State {
+            view: View::Daily,
+            weather_location: Some("Helsinki, Finland".to_owned()),
+            geolocation: Some((60.17, 24.94)),
+            clock: Some(NaiveDate::from_ymd_opt(2023, 11, 19).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc()),
+            kp_forecast: vec![KpIndex { start: 1700395200, kp: 3.33 }, KpIndex { start: 1700416800, kp: 5.67 }],
+            ..state_with_forecast()
+        };
+        for hourly_data in state.weather_data.values_mut() {
+            hourly_data.cloud_cover = Some(10);
+        }
+        let expected = [
+            "  Hourly  [ Daily ]  Week    Chart",
+            "",
+            "",
+            "",
+            "                      Helsinki, Finland  ☀ 9h 10m (-2 min)",
+            "",
+            "",
+            "          Sun 19 OVERCAST                9.8°C  2.3°C  💧 0mm     0h",
+            "          Mon 20 RAIN SHOWERS (SLIGHT)   6.7°C  2.3°C  💧 5.4mm   15h",
+            "          Tue 21 SLIGHT RAIN             7.1°C  -1.3°C 💧 0.5mm   1h",
+            "          Wed 22 RAIN SHOWERS (MODERATE) 8.6°C  2.4°C  💧 29.4mm  23h",
+            "          Thu 23 SLIGHT RAIN             10.7°C 5.4°C  💧 2.3mm   7h",
+            "          Fri 24 OVERCAST                11.7°C 2.5°C  💧 0mm     0h",
+            "          Sat 25 SLIGHT RAIN             5.6°C  -0.5°C 💧 2.2mm   6h",
+            "",
+            "                      Golden hour 06:58-07:58, 15:08-16:08",
+            "                       Blue hour 06:28-06:58, 16:08-16:38",
+            "         ☀ 7° above the horizon, SW 207° · sunrise 06:58 · sunset 16:08",
+            "                    🌌 Aurora tonight: high · Kp 5.7 · ☁ 10%",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 20, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_week_grid() {
+        let state = State {
+            view: View::Week,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "                                 Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "",
+            "",
+            "            Sun 19     Mon 20     Tue 21     Wed 22     Thu 23     Fri 24     Sat 25",
+            "            ☁         🌦         🌦         🌧         🌦         ☁         🌦",
+            "            9.8°C      6.7°C      7.1°C      8.6°C      10.7°C     11.7°C     5.6°C",
+            "            2.3°C      2.3°C      -1.3°C     2.4°C      5.4°C      2.5°C      -0.5°C",
+            "            💧 0mm     💧 5.4mm   💧 0.5mm   💧 29.4mm  💧 2.3mm   💧 0mm     💧 2.2mm",
+            "            💨 12.7kph 💨 22.4kph 💨 16.8kph 💨 30.5kph 💨 18.6kph 💨 10.9kph 💨 14.8kph",
+            "",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 12, 100, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_hours_as_columns_in_a_wide_pane() {
+        let state = State {
+            horizontal_hourly: true,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "               Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "",
+            "",
+            "     11:00      12:00      13:00     14:00     15:00     16:00     17:00     18:00     19:00",
+            "     ⛅         ⛅         ☀        🌤        🌤        🌤        🌤        ⛅        🌤",
+            "     7.5°C      8.7°C      9.1°C     9.2°C     9.8°C     9.3°C     9.3°C     8.6°C     7.6°C",
+            "     💧 0%      💧 26%     💧 10%    💧 7%     💧 0%     💧 0%     💧 0%     💧 24%    💧 13%",
+            "     ↑  11.1kph ↗  12.4kph ↑  8.4kph ↑  6.5kph ↑  6.8kph ↑  5.3kph ↑  3.2kph ↑  3.4kph ↖  6.6kph",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 10, 100, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_the_position_after_paging() {
+        let state = State {
+            scroll_offset: 8,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score",
+            "              19:00  MAINLY CLEAR  7.6  °C ↓ 💧 13%  ↖  6.6kph  ▃4",
+            "              20:00  CLEAR SKY     6.9  °C ↓ 💧 0%   ↑  6.4kph  ▄5",
+            "              21:00  PARTLY CLOUDY 5.7  °C ↓ 💧 12%  ↑  3.8kph  ▂3",
+            "              22:00  PARTLY CLOUDY 5.4  °C → 💧 17%  ↑  5.4kph  ▂3",
+            "              23:00  MAINLY CLEAR  4.1  °C ↓ 💧 0%   ↖  6kph    ▂3",
+            "              00:00  CLEAR SKY     3.8  °C → 💧 19%  ↑  12.3kph ▂2",
+            "             ▌01:00  SLIGHT RAIN   2.6  °C ↓ 💧 79%  ↗  15.8kph ▁0",
+            "             ▌02:00  LIGHT DRIZZLE 2.6  °C → 💧 59%  ↑  17.4kph ▁1",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location      hours 8–16 of 158",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn finds_the_next_dry_window() {
+        let state = State {
+            show_dry_window: true,
+            dry_window_hours: Some(2),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                                      Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "                    Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "                             Sun 19 Sky           Temp      Rain    Wind       Score",
+            "                             11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "                             12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "                             13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "                             14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "                             15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "                             16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "                             17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "                             18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "                              Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                                      Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location                             dry 2h from Sun 19 13:00",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 110, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn summarizes_the_commute() {
+        let state = State {
+            commute: parse_commute("08:00-09:00,17:30-18:30"),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score",
+            "              11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "              12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "              13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "              14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "              15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "              16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "              17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "              18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "              19:00  MAINLY CLEAR  7.6  °C ↓ 💧 13%  ↖  6.6kph  ▃4",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "     Morning: 65% rain, 3.9°C. Evening: 24% rain, 9°C — take the rain jacket",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 18, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_weather_warnings_above_the_header() {
+        let state = State {
+            alerts: vec![
+                Alert { event: "Heat".to_owned(), level: AlertLevel::Moderate },
+                Alert { event: "Storm".to_owned(), level: AlertLevel::Severe },
+                Alert { event: "Heat".to_owned(), level: AlertLevel::Minor },
+            ],
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "                                 ⚠ Storm · Heat",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "              Sun 19 Sky           Temp      Rain    Wind       Score",
+            "              11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "              12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "              13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "              14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "              15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "              16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "              17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "              18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn marks_hours_with_a_lightning_risk() {
+        let mut state = state_with_forecast();
+        state.weather_data.get_mut(&11).unwrap().cape = Some(1400.0);
+        state.weather_data.get_mut(&12).unwrap().cape = Some(2800.0);
+        let expected = [
+            "",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "     Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "             Sun 19 Sky              Temp      Rain    Wind       Score",
+            "             11:00  PARTLY CLOUDY    7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "             12:00  PARTLY CLOUDY ⚡ 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "             13:00  CLEAR SKY ⚡     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "             14:00  MAINLY CLEAR     9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "               Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                       Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 12, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn compares_two_locations_hour_by_hour() {
+        let mut compare_data = parse_weather_data(include_bytes!("../fixtures/forecast_with_synthetic_series.json").to_vec(), PAST_DAYS).unwrap().hourly;
+        for (hour, hourly_data) in compare_data.iter_mut() {
+            hourly_data.temperature_2m -= 1.5;
+            hourly_data.precipitation_probability = Some(if *hour % 3 == 0 { 70 } else { 10 });
+        }
+        let state = State {
+            favorites: vec![Favorite { query: "Bratislava".to_owned(), resolved: Some((48.15, 17.11, "Bratislava, Slovakia".to_owned())) }],
+            compare_index: Some(0),
+            compare_data,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "",
+            "            Vienna Bratislava Δ      💧 Vienna 💧 Bratislava",
+            "      11:00 7.5°C  6°C        -1.5°C 0%        10%",
+            "      12:00 8.7°C  7.2°C      -1.5°C 26%       10%",
+            "      13:00 9.1°C  7.6°C      -1.5°C 10%       70%           ☂ Bratislava",
+            "      14:00 9.2°C  7.7°C      -1.5°C 7%        10%",
+            "      15:00 9.8°C  8.3°C      -1.5°C 0%        10%",
+            "      16:00 9.3°C  7.8°C      -1.5°C 0%        70%           ☂ Bratislava",
+            "      17:00 9.3°C  7.8°C      -1.5°C 0%        10%",
+            "      18:00 8.6°C  7.1°C      -1.5°C 24%       10%",
+            "",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_the_day_picker() {
+        let state = State {
+            picking_day: true,
+            day_offset: 1,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "",
+            "",
+            "",
+            "",
+            "                                  Jump to day:",
+            "",
+            "                                   <1> Sun 19",
+            "                                   <2> Mon 20",
+            "                                   <3> Tue 21",
+            "                                   <4> Wed 22",
+            "                                   <5> Thu 23",
+            "                                   <6> Fri 24",
+            "                                   <7> Sat 25",
+            "",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_the_next_day_from_midnight() {
+        let state = State {
+            day_offset: 1,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                       Vienna, Austria  ☀ 9h 7m (-3 min)",
+            "               Mon 20  High 6.7°C  Low 2.3°C  💧 5.4mm  💨 22.4kph",
+            "          Mon 20 Sky                   Temp      Rain    Wind       Score",
+            "         ▌01:00  SLIGHT RAIN           2.6  °C ↓ 💧 79%  ↗  15.8kph ▁0",
+            "         ▌02:00  LIGHT DRIZZLE         2.6  °C → 💧 59%  ↑  17.4kph ▁1",
+            "         ▌03:00  LIGHT DRIZZLE         2.4  °C → 💧 55%  ↑  16.4kph ▁1",
+            "         ▌04:00  MODERATE DRIZZLE      2.3  °C → 💧 81%  ↗  17.9kph ▁0",
+            "          05:00  OVERCAST              2.9  °C ↑ 💧 36%  ↑  14.6kph ▁1",
+            "         ▌06:00  SLIGHT RAIN           3.2  °C → 💧 60%  ↗  20.8kph ▁1",
+            "         ▌07:00  RAIN SHOWERS (SLIGHT) 3.2  °C → 💧 68%  ↑  15.3kph ▁1",
+            "         ▌08:00  SLIGHT RAIN           3.9  °C ↑ 💧 65%  ↑  20.3kph ▁1",
+            "",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location     hours 14–22 of 158",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 80, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn hides_controls_footer() {
+        let state = State {
+            hide_controls: true,
+            error: Some(WeatherPalError::GeocodeEmpty),
+            ..Default::default()
+        };
+        let expected = [
+            "",
+            "",
+            "       No matching location found",
+            "",
+        ];
+        assert_eq!(render_snapshot(&state, 4, 40, 0), expected.join("\n"));
+    }
+
+    #[test]
+    fn shows_the_forecast_age_and_the_next_refresh() {
+        let state = State {
+            minutes_until_refresh: Some(7),
+            data_age_minutes: Some(8),
+            refresh_interval_minutes: Some(15),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "[ Hourly ]  Daily    Week    Chart",
+            "",
+            "                            Vienna, Austria  ☀ 9h 10m (-2 min)",
+            "          Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kph",
+            "                   Sun 19 Sky           Temp      Rain    Wind       Score",
+            "                   11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "                   12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "                   13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "                   14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "                   15:00  MAINLY CLEAR  9.8  °C ↑ 💧 0%   ↑  6.8kph  ▆7",
+            "                   16:00  MAINLY CLEAR  9.3  °C ↓ 💧 0%   ↑  5.3kph  ▅6",
+            "                   17:00  MAINLY CLEAR  9.3  °C → 💧 0%   ↑  3.2kph  ▅6",
+            "                   18:00  PARTLY CLOUDY 8.6  °C ↓ 💧 24%  ↑  3.4kph  ▃4",
+            "                    Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                            Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location          ↻ 8m  next update in 7m",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 90, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn replaces_location_header_with_label() {
+        let state = State {
+            location_label: Some("Office".to_owned()),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "",
+            "",
+            "                      Office  ☀ 9h 10m (-2 min)",
+            "Sun 19  High 9.8°C (+3.2°C vs yesterday)  Low 2.3°C  💧 0mm  💨 12.7kp",
+            "         Sun 19 Sky           Temp      Rain    Wind       Score",
+            "         11:00  PARTLY CLOUDY 7.5  °C ↑ 💧 0%   ↑  11.1kph ▄5",
+            "         12:00  PARTLY CLOUDY 8.7  °C ↑ 💧 26%  ↗  12.4kph ▃4",
+            "         13:00  CLEAR SKY     9.1  °C → 💧 10%  ↑  8.4kph  ▄5",
+            "         14:00  MAINLY CLEAR  9.2  °C → 💧 7%   ↑  6.5kph  ▅6",
+            "          Rain likely in ~14h, until ~05:00, then dry for ~1h",
+            "                  Next 2h ▕    ▁▁▁▁▂▂▂▂▁▁  ▏ 💧 0.6mm",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 12, 70, 10), expected.join("\n"));
+    }
+
+    #[test]
+    fn renders_chart() {
+        let state = State {
+            view: View::Chart,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "  Hourly    Daily    Week  [ Chart ]",
+            " 9.8°C   ⢀⣀⠔⠑⠤⢄                                                   100%",
+            "        ⡔⠁▲9.8°⠑⡄      ☂ 81% 04:00",
+            "       ⡜        ⠘⡄      ▂▂  ▄     ▁    ▲6.7°",
+            "                 ⠈⢆     ██  █     █▁▁  ⢀⣀⠤⣀   ▆▆    ▂▂",
+            "                  ⠈⢆    ██  █   ▇▄███⢀⠔⠁▃  ⠑⠢⢄██    ██",
+            "                    ⠙⡄  ██▅▁█  ▆██⢀⡠⠊⠁  █▁▁  ▁⠉⠢⣀   ██        ▲4°",
+            "                     ⠘⣄ █████  ██⡔⠁██▃ ▅███▅ ████⠣⡀ ██          ⢀",
+            "                       ⢱█████⢀⡠⠤⠊█████ █████▆████ ⠉⠒⢆█    ▄▄   ⢀⠎",
+            "                     ▼3.8°⠤⠤⠔⠁▅███████████████████▆▆⢸█▄   ██  ⢠⠊▄",
+            "         ▃      ▁       ██▼2.3°█████████████████████⠈⡆█  ▃██ ⢠⠃ █",
+            "         █      █    ▂ ▄█████████████████████████████⡇█  ███⡠⠃  █",
+            "         █▂     █▆  ▄█ ██████████████████████████████▼-1.3°⡜▁   █",
+            "-1.3°C   ██▇    ██  ██ ███████████████████████████████⠑⠤⡠⠊⠉██  ▅█ 0%",
+            "         12     18     00      06     12     18     00      06",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 16, 70, 10), expected.join("\n"));
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Duration, Local};
+use std::collections::BTreeMap;
+
+// more than this wait for one of them to come back
+pub const MAX_IN_FLIGHT: usize = 4;
+// between two requests, so a refresh doesn't hit the API with a burst of them at once
+pub const MIN_INTERVAL_MILLISECONDS: i64 = 150;
+
+// what is needed to show anything comes first, then the forecast, then everything around it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Location,
+    Forecast,
+    Extras,
+}
+
+impl Priority {
+    pub fn for_id(id: &str) -> Self {
+        match id {
+            "geocode" | "suggestions" | "typeahead" | "metar" => Priority::Location,
+            "weather" => Priority::Forecast,
+            _ => Priority::Extras,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    pub id: String, // comes back with the response
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub priority: Priority,
+}
+
+impl Request {
+    pub fn new(id: &str, url: String) -> Self {
+        Request {
+            id: id.to_owned(),
+            url,
+            headers: BTreeMap::new(),
+            priority: Priority::for_id(id),
+        }
+    }
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_owned(), value.to_owned());
+        self
+    }
+}
+
+// every request goes through here rather than straight to the API
+#[derive(Default)]
+pub struct RequestQueue {
+    waiting: Vec<Request>,
+    in_flight: Vec<String>, // ids
+    next_slot: Option<DateTime<Local>>, // when the next request may be sent
+    scheduled: Option<DateTime<Local>>, // the slot a timer was already set for
+}
+
+impl RequestQueue {
+    // replaces a request with the same id that wasn't sent yet, eg. when refreshing twice in a row
+    pub fn push(&mut self, request: Request) {
+        self.waiting.retain(|waiting| waiting.id != request.id);
+        self.waiting.push(request);
+    }
+    // the request to send now, the most important first, never two with the same id at once and only one in each interval
+    pub fn ready(&mut self, now: DateTime<Local>) -> Option<Request> {
+        if self.in_flight.len() >= MAX_IN_FLIGHT || self.next_slot.map(|slot| slot > now).unwrap_or(false) {
+            return None;
+        }
+        self.waiting.sort_by_key(|request| request.priority);
+        let index = self.waiting.iter().position(|waiting| !self.in_flight.contains(&waiting.id))?;
+        let request = self.waiting.remove(index);
+        self.in_flight.push(request.id.clone());
+        self.next_slot = Some(now + Duration::milliseconds(MIN_INTERVAL_MILLISECONDS));
+        Some(request)
+    }
+    // how long until the next request may be sent, when one is held back only by the interval and no timer is set for it yet
+    pub fn wait(&mut self, now: DateTime<Local>) -> Option<Duration> {
+        let slot = self.next_slot.filter(|slot| *slot > now && self.scheduled != Some(*slot))?;
+        let sendable = self.waiting.iter().any(|waiting| !self.in_flight.contains(&waiting.id));
+        if !sendable || self.in_flight.len() >= MAX_IN_FLIGHT {
+            return None;
+        }
+        self.scheduled = Some(slot);
+        Some(slot - now)
+    }
+    pub fn finished(&mut self, id: &str) {
+        if let Some(index) = self.in_flight.iter().position(|in_flight| in_flight == id) {
+            self.in_flight.remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(requests: &[Request]) -> Vec<&str> {
+        requests.iter().map(|request| request.id.as_str()).collect()
+    }
+
+    // everything that can be sent, one interval after the other
+    fn drain(queue: &mut RequestQueue, now: &mut DateTime<Local>) -> Vec<Request> {
+        let mut ready = vec![];
+        while let Some(request) = queue.ready(*now) {
+            ready.push(request);
+            *now += Duration::milliseconds(MIN_INTERVAL_MILLISECONDS);
+        }
+        ready
+    }
+
+    #[test]
+    fn sends_the_most_important_requests_first() {
+        let mut queue = RequestQueue::default();
+        let mut now = Local::now();
+        for id in ["alerts", "marine", "weather", "elevation", "air_quality", "geocode"] {
+            queue.push(Request::new(id, format!("https://example.com/{}", id)));
+        }
+        assert_eq!(ids(&drain(&mut queue, &mut now)), vec!["geocode", "weather", "alerts", "marine"]);
+        assert!(queue.ready(now).is_none());
+        queue.finished("weather");
+        assert_eq!(ids(&drain(&mut queue, &mut now)), vec!["elevation"]);
+        queue.finished("geocode");
+        queue.finished("alerts");
+        assert_eq!(ids(&drain(&mut queue, &mut now)), vec!["air_quality"]);
+        assert!(queue.ready(now).is_none());
+    }
+
+    #[test]
+    fn sends_one_request_with_the_same_id_at_a_time() {
+        let mut queue = RequestQueue::default();
+        let mut now = Local::now();
+        queue.push(Request::new("weather", "https://example.com/1".to_owned()));
+        assert_eq!(drain(&mut queue, &mut now).len(), 1);
+        queue.push(Request::new("weather", "https://example.com/2".to_owned()));
+        queue.push(Request::new("weather", "https://example.com/3".to_owned()));
+        assert!(queue.ready(now).is_none());
+        queue.finished("weather");
+        let ready = drain(&mut queue, &mut now);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].url, "https://example.com/3");
+    }
+
+    #[test]
+    fn spaces_requests_out() {
+        let mut queue = RequestQueue::default();
+        let now = Local::now();
+        queue.push(Request::new("geocode", "https://example.com/geocode".to_owned()));
+        queue.push(Request::new("weather", "https://example.com/weather".to_owned()));
+        assert_eq!(queue.ready(now).map(|r| r.id), Some("geocode".to_owned()));
+        assert!(queue.ready(now).is_none());
+        assert_eq!(queue.wait(now), Some(Duration::milliseconds(MIN_INTERVAL_MILLISECONDS)));
+        assert_eq!(queue.wait(now), None); // the timer is already set
+        let later = now + Duration::milliseconds(MIN_INTERVAL_MILLISECONDS);
+        assert_eq!(queue.ready(later).map(|r| r.id), Some("weather".to_owned()));
+        assert_eq!(queue.wait(later), None); // nothing left to send
+    }
+}
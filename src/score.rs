@@ -0,0 +1,130 @@
+use crate::parse::HourlyData;
+
+const COMFORTABLE_TEMPERATURE: (f64, f64) = (15.0, 22.0); // celsius
+const UNCOMFORTABLE_TEMPERATURE_DISTANCE: f64 = 15.0; // degrees outside of the comfortable range that make an hour unbearable
+const CALM_WIND: f64 = 10.0; // kph
+const STORMY_WIND: f64 = 50.0;
+const LOW_UV_INDEX: f64 = 3.0;
+const EXTREME_UV_INDEX: f64 = 11.0;
+const PLEASANT_TEMPERATURE: (f64, f64) = (10.0, 25.0); // celsius, wider than comfortable since it only tells rows apart
+const WINDY: f64 = 30.0; // kph
+
+// an overall impression of the hour, in order of precedence
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comfort {
+    Wet,
+    Windy,
+    Hot,
+    Cold,
+    Pleasant,
+}
+
+pub fn comfort(hourly_data: &HourlyData, precipitation_threshold: usize) -> Comfort {
+    let (pleasant_min, pleasant_max) = PLEASANT_TEMPERATURE;
+    if hourly_data.precipitation_probability.map(|p| p >= precipitation_threshold).unwrap_or(false) {
+        Comfort::Wet
+    } else if hourly_data.wind_speed_10m.map(|w| w >= WINDY).unwrap_or(false) {
+        Comfort::Windy
+    } else if hourly_data.temperature_2m > pleasant_max {
+        Comfort::Hot
+    } else if hourly_data.temperature_2m < pleasant_min {
+        Comfort::Cold
+    } else {
+        Comfort::Pleasant
+    }
+}
+
+// how much each factor counts towards the score, 0 ignores it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActivityWeights {
+    pub temperature: f64,
+    pub wind: f64,
+    pub precipitation: f64,
+    pub uv: f64,
+}
+
+impl Default for ActivityWeights {
+    fn default() -> Self {
+        ActivityWeights { temperature: 1.0, wind: 1.0, precipitation: 1.0, uv: 1.0 }
+    }
+}
+
+impl ActivityWeights {
+    pub fn from_config(weights: &str) -> Self {
+        // eg. "wind:2,uv:0.5", factors that aren't mentioned keep their default weight
+        let mut activity_weights = ActivityWeights::default();
+        for (factor, weight) in weights.split(',').filter_map(|w| w.split_once(':')) {
+            let Ok(weight) = weight.trim().parse::<f64>() else {
+                continue;
+            };
+            let weight = weight.max(0.0);
+            match factor.trim() {
+                "temperature" => activity_weights.temperature = weight,
+                "wind" => activity_weights.wind = weight,
+                "precipitation" => activity_weights.precipitation = weight,
+                "uv" => activity_weights.uv = weight,
+                _ => {}
+            }
+        }
+        activity_weights
+    }
+}
+
+// 0 (stay inside) to 10 (perfect), each factor scales the score down by how uncomfortable it makes the hour
+pub fn activity_score(hourly_data: &HourlyData, weights: ActivityWeights) -> usize {
+    let (comfortable_min, comfortable_max) = COMFORTABLE_TEMPERATURE;
+    let temperature_distance = (comfortable_min - hourly_data.temperature_2m).max(hourly_data.temperature_2m - comfortable_max).max(0.0);
+    let penalties = [
+        (temperature_distance / UNCOMFORTABLE_TEMPERATURE_DISTANCE, weights.temperature),
+        (hourly_data.wind_speed_10m.map(|w| (w - CALM_WIND) / (STORMY_WIND - CALM_WIND)).unwrap_or(0.0), weights.wind),
+        (hourly_data.precipitation_probability.map(|p| p as f64 / 100.0).unwrap_or(0.0), weights.precipitation),
+        (hourly_data.uv_index.map(|uv| (uv - LOW_UV_INDEX) / (EXTREME_UV_INDEX - LOW_UV_INDEX)).unwrap_or(0.0), weights.uv),
+    ];
+    let comfort = penalties
+        .iter()
+        .map(|(penalty, weight)| (1.0 - penalty.clamp(0.0, 1.0)).powf(*weight))
+        .product::<f64>();
+    (comfort * 10.0).round() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hour(temperature_2m: f64, wind_speed_10m: f64, precipitation_probability: usize, uv_index: f64) -> HourlyData {
+        HourlyData {
+            temperature_2m,
+            wind_speed_10m: Some(wind_speed_10m),
+            precipitation_probability: Some(precipitation_probability),
+            uv_index: Some(uv_index),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn scores_hours_by_comfort() {
+        let weights = ActivityWeights::default();
+        assert_eq!(activity_score(&hour(18.0, 5.0, 0, 2.0), weights), 10);
+        assert_eq!(activity_score(&hour(18.0, 5.0, 50, 2.0), weights), 5);
+        assert_eq!(activity_score(&hour(18.0, 5.0, 100, 2.0), weights), 0);
+        assert_eq!(activity_score(&hour(7.5, 30.0, 0, 0.0), weights), 3);
+        assert_eq!(activity_score(&HourlyData { temperature_2m: 20.0, ..Default::default() }, weights), 10);
+    }
+
+    #[test]
+    fn classifies_hours_by_comfort() {
+        assert_eq!(comfort(&hour(18.0, 5.0, 60, 2.0), 50), Comfort::Wet);
+        assert_eq!(comfort(&hour(30.0, 35.0, 10, 2.0), 50), Comfort::Windy);
+        assert_eq!(comfort(&hour(30.0, 5.0, 10, 2.0), 50), Comfort::Hot);
+        assert_eq!(comfort(&hour(2.7, 5.0, 10, 2.0), 50), Comfort::Cold);
+        assert_eq!(comfort(&hour(18.0, 5.0, 60, 2.0), 70), Comfort::Pleasant);
+    }
+
+    #[test]
+    fn weighs_factors_from_config() {
+        let weights = ActivityWeights::from_config("wind:2, uv:0,foo:3,precipitation:x");
+        assert_eq!(weights, ActivityWeights { temperature: 1.0, wind: 2.0, precipitation: 1.0, uv: 0.0 });
+        assert_eq!(activity_score(&hour(18.0, 30.0, 0, 11.0), weights), 3);
+        assert_eq!(activity_score(&hour(18.0, 30.0, 0, 11.0), ActivityWeights::default()), 0);
+    }
+}
@@ -0,0 +1,100 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+const PREFIX_LENGTH: usize = 4; // the geocoder needs at least 3 characters to match anything
+const QUERY_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'.').remove(b'_').remove(b'~');
+
+// a location as a query string value, dashes are searched for as spaces (eg. "Wien-Döbling")
+pub fn encode_query(query: &str) -> String {
+    let query = query.replace('-', " ");
+    utf8_percent_encode(query.trim(), QUERY_ENCODE_SET).to_string()
+}
+
+pub fn strip_diacritics(query: &str) -> String {
+    // eg. "Zürich" to "Zurich", only latin letters lose their marks since elsewhere they tell letters apart (eg. "Й", "ギ")
+    let mut stripped = String::new();
+    for c in query.nfc() {
+        let mut decomposed = c.to_string().nfd().collect::<Vec<_>>().into_iter();
+        match decomposed.next() {
+            Some(base) if base.is_ascii_alphabetic() => {
+                stripped.push(base);
+                stripped.extend(decomposed.filter(|c| !is_combining_mark(*c)));
+            }
+            _ => stripped.push(c),
+        }
+    }
+    stripped.nfc().collect()
+}
+
+// the location as typed and without diacritics, the latter also matches names typed without them
+pub fn query_forms(query: &str) -> Vec<String> {
+    let mut forms = vec![query.to_owned()];
+    let without_diacritics = strip_diacritics(query);
+    if !forms.contains(&without_diacritics) {
+        forms.push(without_diacritics);
+    }
+    forms.retain(|form| !form.trim().is_empty());
+    forms
+}
+
+// progressively looser forms of a location that wasn't found, to be tried in order
+pub fn relaxed_queries(query: &str) -> Vec<String> {
+    let without_diacritics = strip_diacritics(query);
+    let without_punctuation = without_diacritics
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let first_word = without_punctuation.split_whitespace().next().unwrap_or("").to_owned();
+    let prefix: String = first_word.chars().take(PREFIX_LENGTH).collect();
+    let mut queries: Vec<String> = vec![];
+    for relaxed_query in [without_diacritics, without_punctuation, first_word, prefix].iter() {
+        if !relaxed_query.is_empty() && relaxed_query != query && !queries.contains(relaxed_query) {
+            queries.push(relaxed_query.clone());
+        }
+    }
+    queries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_diacritics() {
+        assert_eq!(strip_diacritics("Zürich"), "Zurich");
+        assert_eq!(strip_diacritics("São Paulo"), "Sao Paulo");
+        assert_eq!(strip_diacritics("東京"), "東京");
+        assert_eq!(strip_diacritics("Йошкар-Ола"), "Йошкар-Ола");
+        assert_eq!(strip_diacritics("ギョーム"), "ギョーム");
+        assert_eq!(strip_diacritics("서울"), "서울");
+        assert_eq!(strip_diacritics("Ελλάδα"), "Ελλάδα");
+    }
+
+    #[test]
+    fn queries_with_and_without_diacritics() {
+        assert_eq!(query_forms("Zürich"), vec!["Zürich", "Zurich"]);
+        assert_eq!(query_forms("Zurich"), vec!["Zurich"]);
+        assert_eq!(query_forms("Йошкар-Ола"), vec!["Йошкар-Ола"]);
+        assert!(query_forms("").is_empty());
+    }
+
+    #[test]
+    fn encodes_queries() {
+        assert_eq!(encode_query("Tel Aviv"), "Tel%20Aviv");
+        assert_eq!(encode_query("Wien-Döbling"), "Wien%20D%C3%B6bling");
+        assert_eq!(encode_query("Val-d'Or & co."), "Val%20d%27Or%20%26%20co.");
+        assert_eq!(encode_query("東京"), "%E6%9D%B1%E4%BA%AC");
+    }
+
+    #[test]
+    fn relaxes_queries_step_by_step() {
+        assert_eq!(relaxed_queries("St. Gällen!"), vec!["St. Gallen!", "St Gallen", "St"]);
+        assert_eq!(relaxed_queries("Vienna"), vec!["Vien"]);
+        assert_eq!(relaxed_queries("Wien-Döbling"), vec!["Wien-Dobling", "Wien Dobling", "Wien"]);
+        assert!(relaxed_queries("...").is_empty());
+    }
+}
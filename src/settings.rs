@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+const SETTINGS_PATH: &str = "/data/settings.conf";
+
+// what can be changed from the settings view, each saved under its key of the configuration
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Setting {
+    Units,
+    Theme,
+    Columns, // the columns of a profile
+    Soil, // the soil column of gardening
+    RefreshInterval,
+    TimezoneCommand, // finding the location from the system timezone, also answered on the permission rationale
+}
+
+impl Setting {
+    pub const ALL: &'static [Setting] = &[Setting::Units, Setting::Theme, Setting::Columns, Setting::Soil, Setting::RefreshInterval, Setting::TimezoneCommand];
+    pub fn key(&self) -> &'static str {
+        match self {
+            Setting::Units => "units",
+            Setting::Theme => "palette",
+            Setting::Columns => "profile",
+            Setting::Soil => "gardening",
+            Setting::RefreshInterval => "refresh_interval",
+            Setting::TimezoneCommand => "timezone_command",
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Setting::Units => "Units",
+            Setting::Theme => "Theme",
+            Setting::Columns => "Columns",
+            Setting::Soil => "Soil column",
+            Setting::RefreshInterval => "Refresh interval",
+            Setting::TimezoneCommand => "Timezone discovery",
+        }
+    }
+    pub fn choices(&self) -> &'static [&'static str] {
+        match self {
+            Setting::Units => &["metric", "imperial"],
+            Setting::Theme => &["default", "colorblind", "high-contrast"],
+            Setting::Columns => &["none", "mountain", "ski", "surf", "sailing", "stargazing", "allergy"],
+            Setting::Soil => &["false", "true"],
+            Setting::RefreshInterval => &["0", "15", "30", "60"], // minutes, 0 is off
+            Setting::TimezoneCommand => &["true", "false"],
+        }
+    }
+    fn accepts(&self, value: &str) -> bool {
+        // the refresh interval can also be any other number of minutes from the layout
+        self.choices().contains(&value) || (*self == Setting::RefreshInterval && value.parse::<i64>().is_ok())
+    }
+    // the choice before or after the current one, wrapping around
+    pub fn cycle(&self, current: &str, forward: bool) -> &'static str {
+        let choices = self.choices();
+        match choices.iter().position(|c| *c == current) {
+            Some(i) if forward => choices[(i + 1) % choices.len()],
+            Some(i) => choices[(i + choices.len() - 1) % choices.len()],
+            None => choices[0],
+        }
+    }
+}
+
+// bumped whenever saved settings change their meaning, with a migration from the version before
+pub const SETTINGS_VERSION: usize = 1;
+// each one brings the settings from a version to the next, the first one from version 1 to 2
+const MIGRATIONS: &[Migration] = &[];
+
+type Migration = fn(&mut BTreeMap<String, String>);
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SavedSettings {
+    pub settings: BTreeMap<String, String>,
+    pub version: usize, // as saved, files from before versions are version 1
+    pub notes: Vec<String>, // what couldn't be used, for the debug log
+}
+
+// eg. "version=1\nunits=imperial", a line for each setting
+pub fn parse_settings(text: &str) -> SavedSettings {
+    let mut saved = SavedSettings { version: 1, ..Default::default() };
+    let mut settings = BTreeMap::new();
+    for line in text.lines().map(|line| line.trim()).filter(|line| !line.is_empty()) {
+        match line.split_once('=').map(|(key, value)| (key.trim(), value.trim())) {
+            Some(("version", version)) => match version.parse() {
+                Ok(version) if version > 0 => saved.version = version,
+                _ => saved.notes.push(format!("ignored the unknown settings version {}", version)),
+            },
+            Some((key, value)) => {
+                settings.insert(key.to_owned(), value.to_owned());
+            }
+            None => saved.notes.push(format!("ignored the malformed setting {}", line)),
+        }
+    }
+    if saved.version > SETTINGS_VERSION {
+        saved.notes.push(format!("settings were saved by a newer version ({}), only the ones known to this one are used", saved.version));
+    } else {
+        migrate(&mut settings, saved.version, MIGRATIONS);
+    }
+    for (key, value) in settings {
+        match Setting::ALL.iter().find(|s| s.key() == key) {
+            Some(setting) if setting.accepts(&value) => {
+                saved.settings.insert(key, value);
+            }
+            _ => saved.notes.push(format!("ignored the setting {}={}", key, value)),
+        }
+    }
+    saved
+}
+
+fn migrate(settings: &mut BTreeMap<String, String>, version: usize, migrations: &[Migration]) {
+    for migration in migrations.iter().skip(version - 1) {
+        migration(settings);
+    }
+}
+
+pub fn format_settings(settings: &BTreeMap<String, String>) -> String {
+    let settings: String = settings.iter().map(|(key, value)| format!("{}={}\n", key, value)).collect();
+    format!("version={}\n{}", SETTINGS_VERSION, settings)
+}
+
+// nothing saved yet is the same as an empty file, older files are saved again once migrated
+pub fn read_settings() -> SavedSettings {
+    let Ok(text) = fs::read_to_string(SETTINGS_PATH) else {
+        return SavedSettings { version: SETTINGS_VERSION, ..Default::default() };
+    };
+    let mut saved = parse_settings(&text);
+    if saved.version < SETTINGS_VERSION {
+        match save_settings(&saved.settings) {
+            Ok(()) => saved.notes.push(format!("migrated the settings from version {} to {}", saved.version, SETTINGS_VERSION)),
+            Err(e) => saved.notes.push(format!("failed to save the migrated settings: {}", e)),
+        }
+    }
+    saved
+}
+
+pub fn save_settings(settings: &BTreeMap<String, String>) -> io::Result<()> {
+    fs::write(SETTINGS_PATH, format_settings(settings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_back_saved_settings() {
+        let mut settings = BTreeMap::new();
+        settings.insert("units".to_owned(), "imperial".to_owned());
+        settings.insert("refresh_interval".to_owned(), "15".to_owned());
+        assert_eq!(format_settings(&settings), "version=1\nrefresh_interval=15\nunits=imperial\n");
+        assert_eq!(parse_settings(&format_settings(&settings)), SavedSettings { settings, version: 1, notes: vec![] });
+    }
+
+    #[test]
+    fn notes_the_settings_that_cant_be_used() {
+        let saved = parse_settings("units = metric\nlocation=Rome\nbroken\npalette=neon\nrefresh_interval=45");
+        assert_eq!(saved.version, 1);
+        assert_eq!(saved.settings, BTreeMap::from([("refresh_interval".to_owned(), "45".to_owned()), ("units".to_owned(), "metric".to_owned())]));
+        assert_eq!(saved.notes, vec!["ignored the malformed setting broken", "ignored the setting location=Rome", "ignored the setting palette=neon"]);
+        let newer = parse_settings("version=3\nunits=imperial\nwind_unit=knots");
+        assert_eq!(newer.version, 3);
+        assert_eq!(newer.settings.len(), 1);
+        assert_eq!(newer.notes.len(), 2);
+    }
+
+    #[test]
+    fn migrates_from_the_saved_version() {
+        fn rename_theme(settings: &mut BTreeMap<String, String>) {
+            if let Some(theme) = settings.remove("theme") {
+                settings.insert("palette".to_owned(), theme);
+            }
+        }
+        fn drop_columns(settings: &mut BTreeMap<String, String>) {
+            settings.remove("columns");
+        }
+        let mut settings = BTreeMap::from([("theme".to_owned(), "colorblind".to_owned()), ("columns".to_owned(), "ski".to_owned())]);
+        migrate(&mut settings, 2, &[rename_theme, drop_columns]);
+        assert_eq!(settings.get("theme").map(|t| t.as_str()), Some("colorblind"));
+        assert_eq!(settings.get("columns"), None);
+        migrate(&mut settings, 1, &[rename_theme, drop_columns]);
+        assert_eq!(settings, BTreeMap::from([("palette".to_owned(), "colorblind".to_owned())]));
+    }
+
+    #[test]
+    fn cycles_through_the_choices() {
+        assert_eq!(Setting::Units.cycle("metric", true), "imperial");
+        assert_eq!(Setting::Units.cycle("imperial", true), "metric");
+        assert_eq!(Setting::Columns.cycle("none", false), "allergy");
+        assert_eq!(Setting::RefreshInterval.cycle("45", true), "0");
+    }
+}
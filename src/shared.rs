@@ -0,0 +1,104 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{ErrorKind, Write};
+
+// unlike /data, /tmp is the same directory for every weather-pal pane
+const SHARED_DIR: &str = "/tmp/weather-pal";
+pub const SHARED_MAX_AGE_SECONDS: i64 = 5 * 60;
+// a pane that claimed a fetch and didn't share the response by then is not waited for anymore
+pub const PENDING_MAX_AGE_SECONDS: i64 = 10;
+
+// the url on the first line, when it was fetched (unix seconds) on the second, then the body
+pub fn encode_shared_response(url: &str, fetched_at: i64, body: &[u8]) -> Vec<u8> {
+    let mut contents = format!("{}\n{}\n", url, fetched_at).into_bytes();
+    contents.extend_from_slice(body);
+    contents
+}
+
+// only a recent response to the very same request, eg. with the same variables and elevation
+pub fn decode_shared_response(contents: &[u8], url: &str, now: i64) -> Option<Vec<u8>> {
+    let mut parts = contents.splitn(3, |b| *b == b'\n');
+    let shared_url = parts.next()?;
+    let fetched_at: i64 = std::str::from_utf8(parts.next()?).ok()?.parse().ok()?;
+    let body = parts.next()?;
+    if shared_url != url.as_bytes() || !(0..SHARED_MAX_AGE_SECONDS).contains(&(now - fetched_at)) {
+        return None;
+    }
+    Some(body.to_vec())
+}
+
+// the marker of a fetch is when it was claimed (unix seconds)
+pub fn decode_pending(contents: &[u8], now: i64) -> bool {
+    let claimed_at: Option<i64> = std::str::from_utf8(contents).ok().and_then(|c| c.trim().parse().ok());
+    claimed_at.map(|claimed_at| (0..PENDING_MAX_AGE_SECONDS).contains(&(now - claimed_at))).unwrap_or(false)
+}
+
+fn shared_path(url: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    format!("{}/{:x}", SHARED_DIR, hasher.finish())
+}
+
+pub fn shared_response(url: &str, now: i64) -> Option<Vec<u8>> {
+    let contents = fs::read(shared_path(url)).ok()?;
+    decode_shared_response(&contents, url, now)
+}
+
+fn pending_path(url: &str) -> String {
+    format!("{}.pending", shared_path(url))
+}
+
+// whether this pane is the one to fetch, the others wait for it to share the response
+pub fn claim_fetch(url: &str, now: i64) -> bool {
+    let path = pending_path(url);
+    match fs::read(&path) {
+        Ok(contents) if decode_pending(&contents, now) => return false,
+        Ok(_) => {
+            let _ = fs::remove_file(&path);
+        }
+        Err(_) => {}
+    }
+    // only one of the panes starting together creates the marker, the others find it there
+    let _ = fs::create_dir_all(SHARED_DIR);
+    match OpenOptions::new().write(true).create_new(true).open(&path) {
+        Ok(mut file) => {
+            let _ = file.write_all(now.to_string().as_bytes());
+            true
+        }
+        Err(e) => e.kind() != ErrorKind::AlreadyExists,
+    }
+}
+
+// eg. when the fetch failed, so the other panes don't wait for it
+pub fn release_fetch(url: &str) {
+    let _ = fs::remove_file(pending_path(url));
+}
+
+// sharing is best effort, a pane that can't write just fetches on its own
+pub fn share_response(url: &str, fetched_at: i64, body: &[u8]) {
+    let _ = fs::create_dir_all(SHARED_DIR).and_then(|_| fs::write(shared_path(url), encode_shared_response(url, fetched_at, body)));
+    release_fetch(url);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_only_recent_responses_to_the_same_request() {
+        let url = "https://api.open-meteo.com/v1/forecast?latitude=48.2&longitude=16.37";
+        let contents = encode_shared_response(url, 1_700_000_000, b"{\"hourly\":\n{}}");
+        assert_eq!(decode_shared_response(&contents, url, 1_700_000_060), Some(b"{\"hourly\":\n{}}".to_vec()));
+        assert_eq!(decode_shared_response(&contents, url, 1_700_000_000 + SHARED_MAX_AGE_SECONDS), None);
+        assert_eq!(decode_shared_response(&contents, "https://api.open-meteo.com/v1/forecast?latitude=52.52&longitude=13.41", 1_700_000_060), None);
+        assert_eq!(decode_shared_response(b"garbage", url, 1_700_000_060), None);
+    }
+
+    #[test]
+    fn waits_only_for_a_recent_claim() {
+        assert!(decode_pending(b"1700000000", 1_700_000_005));
+        assert!(!decode_pending(b"1700000000", 1_700_000_000 + PENDING_MAX_AGE_SECONDS));
+        assert!(!decode_pending(b"", 1_700_000_005));
+    }
+}
@@ -0,0 +1,32 @@
+pub const DEFAULT_PANEL_TILT: f64 = 35.0;
+pub const DEFAULT_PANEL_AZIMUTH: f64 = 0.0;
+// inverter, cabling, heat and soiling losses of a typical home installation
+const PERFORMANCE_RATIO: f64 = 0.85;
+const STANDARD_TEST_IRRADIANCE: f64 = 1000.0; // W/m², at which panels deliver their peak power
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PvSystem {
+    pub peak_power_kw: f64,
+    pub tilt: f64, // degrees from horizontal
+    pub azimuth: f64, // degrees from south, east is negative
+}
+
+impl PvSystem {
+    // irradiance on the tilted panels in W/m²
+    pub fn estimated_output_kw(&self, tilted_irradiance: f64) -> f64 {
+        self.peak_power_kw * tilted_irradiance.max(0.0) / STANDARD_TEST_IRRADIANCE * PERFORMANCE_RATIO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_output_from_irradiance_on_the_panels() {
+        let pv_system = PvSystem { peak_power_kw: 5.0, tilt: DEFAULT_PANEL_TILT, azimuth: DEFAULT_PANEL_AZIMUTH };
+        assert_eq!(pv_system.estimated_output_kw(1000.0), 4.25);
+        assert_eq!(pv_system.estimated_output_kw(200.0), 0.85);
+        assert_eq!(pv_system.estimated_output_kw(0.0), 0.0);
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+
+const SYNODIC_MONTH_DAYS: f64 = 29.530588853;
+const KNOWN_NEW_MOON: i64 = 947182440; // 2000-01-06 18:14 UTC
+
+// how much of the moon is lit, from 0 (new moon) to 1 (full moon), and whether it is growing,
+// from the mean length of the lunar month, which is within a few percent of the real phase
+pub fn moon_phase(time: DateTime<Utc>) -> (f64, bool) { // illumination, waxing
+    let age = ((time.timestamp() - KNOWN_NEW_MOON) as f64 / 86400.0).rem_euclid(SYNODIC_MONTH_DAYS);
+    let illumination = (1.0 - (2.0 * std::f64::consts::PI * age / SYNODIC_MONTH_DAYS).cos()) / 2.0;
+    (illumination, age < SYNODIC_MONTH_DAYS / 2.0)
+}
+
+// from 0 (stay inside) to 10 (perfect): clouds hide everything, a bright moon washes out faint objects
+// and without a fully dark sky only the brighter stars and planets show
+pub fn observing_rating(cloud_cover: f64, moon_illumination: f64, astronomical_night: bool) -> usize {
+    let clear_sky = 1.0 - (cloud_cover / 100.0).clamp(0.0, 1.0);
+    let moonlight = 1.0 - moon_illumination.clamp(0.0, 1.0) / 2.0;
+    let darkness = if astronomical_night { 1.0 } else { 0.6 };
+    (10.0 * clear_sky * moonlight * darkness).round() as usize
+}
+
+pub fn rating_label(rating: usize) -> &'static str {
+    match rating {
+        8.. => "excellent",
+        6..=7 => "good",
+        4..=5 => "fair",
+        _ => "poor",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn follows_the_phases_of_the_moon() {
+        let at = |time: &str| NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").unwrap().and_utc();
+        // new moon on 2023-11-13, first quarter on the 20th and full moon on the 27th
+        let (illumination, _) = moon_phase(at("2023-11-13T09:27"));
+        assert!(illumination < 0.01, "{}", illumination);
+        let (illumination, waxing) = moon_phase(at("2023-11-20T10:50"));
+        assert!((0.4..0.6).contains(&illumination), "{}", illumination);
+        assert!(waxing);
+        let (illumination, waxing) = moon_phase(at("2023-11-29T00:00"));
+        assert!(illumination > 0.9, "{}", illumination);
+        assert!(!waxing);
+    }
+
+    #[test]
+    fn rates_the_observing_conditions() {
+        assert_eq!(observing_rating(0.0, 0.0, true), 10);
+        assert_eq!(observing_rating(0.0, 1.0, true), 5);
+        assert_eq!(observing_rating(100.0, 0.0, true), 0);
+        assert_eq!(observing_rating(20.0, 0.3, false), 4);
+        assert_eq!(rating_label(10), "excellent");
+        assert_eq!(rating_label(7), "good");
+        assert_eq!(rating_label(4), "fair");
+        assert_eq!(rating_label(1), "poor");
+    }
+}
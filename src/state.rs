@@ -0,0 +1,1822 @@
+use zellij_tile::prelude::*;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use chrono::{self, DateTime, Duration, Local, Timelike, Utc};
+use crate::accuracy::{forecast_accuracy, hour_key, read_history, record_forecast, save_history, Accuracy};
+use crate::air_quality::{air_quality_hourly_variables, AirQuality, AirQualityThresholds, AIR_QUALITY_REQUEST_ID};
+use crate::alerts::{meteoalarm_request_id, Alert, AlertSource, METEOALARM_REQUEST_PREFIX, NWS_ALERTS_REQUEST_ID};
+use crate::aurora::{is_aurora_latitude, KpIndex, AURORA_REQUEST_ID, KP_FORECAST_URL};
+use crate::aviation::{is_airport_code, Metar};
+use crate::browser::{forecast_site_url, open_command, DEFAULT_FORECAST_SITE, OPEN_COMMAND_ID};
+use crate::climate::{climate_url, CLIMATE_REQUEST_ID};
+use crate::commute::{parse_commute, CommuteWindow};
+use crate::debug::RequestLog;
+use crate::error::{body_excerpt, RequestKind, WeatherPalError};
+use crate::favorites::{parse_favorites, Favorite, FAVORITE_FORECAST_PREFIX};
+use crate::hooks::{conditions, hook_environment, parse_hook_temperatures, transitions, Conditions, DEFAULT_HOOK_TEMPERATURES, HOOK_COMMAND_ID};
+use crate::i18n::Language;
+use crate::input::LineInput;
+use crate::locale::{Locale, UnitSystem};
+use crate::map::grid_points;
+use crate::marine::{MarineData, MARINE_HOURLY_VARIABLES};
+use crate::palette::Palette;
+use crate::parse::{parse_lat_lon_and_location, parse_weather_data, DailyData, Forecast, HourlyData, PAST_DAYS};
+use crate::profile::Profile;
+use crate::render::ZellijRenderer;
+use crate::requests::{Request, RequestQueue};
+use crate::score::{activity_score, ActivityWeights};
+use crate::solar::{PvSystem, DEFAULT_PANEL_AZIMUTH, DEFAULT_PANEL_TILT};
+use crate::search::{encode_query, query_forms, relaxed_queries};
+use crate::settings::{read_settings, save_settings, Setting};
+use crate::shared::{claim_fetch, release_fetch, share_response, shared_response, PENDING_MAX_AGE_SECONDS};
+use crate::team::{parse_team, CurrentWeather, TeamMember, TEAM_REQUEST_ID};
+use crate::trip::{parse_trip, TripLeg, TRIP_FORECAST_DAYS};
+use crate::worker::{
+    decode, parse_in_worker, TeamLocation, AIR_QUALITY_PARSED_MESSAGE, ALERTS_PARSED_MESSAGE, AURORA_PARSED_MESSAGE, CLIMATE_PARSED_MESSAGE, COMPARE_PARSED_MESSAGE, ELEVATION_PARSED_MESSAGE, FAVORITE_PARSED_MESSAGE,
+    FAVORITE_REQUEST_PREFIX, GEOCODE_PARSED_MESSAGE, MAP_PARSED_MESSAGE, MARINE_PARSED_MESSAGE, METAR_PARSED_MESSAGE, METAR_UPDATED_MESSAGE, SUGGESTIONS_PARSED_MESSAGE, TEAM_GEOCODE_PREFIX, TEAM_LOCATION_PARSED_MESSAGE, TEAM_PARSED_MESSAGE,
+    TRIP_FORECAST_PARSED_MESSAGE, TRIP_FORECAST_PREFIX, TRIP_GEOCODE_PREFIX, TAF_PARSED_MESSAGE, TRIP_LOCATION_PARSED_MESSAGE, TYPEAHEAD_PARSED_MESSAGE, WEATHER_PARSED_MESSAGE,
+};
+
+const DEFAULT_PRECIPITATION_THRESHOLD: usize = 50;
+pub(crate) const PRECIPITATION_LOOKAHEAD_HOURS: usize = 48;
+const DEFAULT_BEST_WINDOW_HOURS: usize = 2;
+const BEST_WINDOW_LOOKAHEAD_HOURS: usize = 48;
+const BEST_WINDOW_MAX_WIND: f64 = 20.0; // kph
+const BEST_WINDOW_TEMPERATURE: (f64, f64) = (10.0, 25.0); // celsius
+const DEFAULT_DRY_WINDOW_HOURS: usize = 3;
+const DEFAULT_DRY_BELOW: usize = 20; // precipitation probability
+const NOWCAST_STEPS: usize = 8; // 2 hours
+// min latitude, max latitude, min longitude, max longitude of models with a 15 minute resolution
+const NOWCAST_REGIONS: &[(f64, f64, f64, f64)] = &[
+    (43.0, 58.0, -4.0, 20.0), // central europe (DWD ICON-D2)
+    (21.0, 53.0, -134.0, -60.0), // north america (NOAA HRRR)
+];
+const HOURLY_VARIABLES: &[&str] = &["temperature_2m", "precipitation_probability", "precipitation", "wind_speed_10m", "wind_direction_10m", "uv_index", "cape", "weather_code"];
+const GARDENING_HOURLY_VARIABLES: &[&str] = &["soil_temperature_0cm", "soil_moisture_0_to_1cm"];
+const DAILY_VARIABLES: &[&str] = &["weather_code", "temperature_2m_max", "temperature_2m_min", "precipitation_sum", "precipitation_hours", "wind_speed_10m_max", "sunrise", "sunset"];
+const SOLAR_HOURLY_VARIABLES: &[&str] = &["shortwave_radiation", "global_tilted_irradiance"];
+const WIND_HEIGHT_VARIABLES: &[(usize, &str)] = &[(80, "wind_speed_80m"), (120, "wind_speed_120m"), (180, "wind_speed_180m")];
+const GARDENING_DAILY_VARIABLES: &[&str] = &["et0_fao_evapotranspiration"];
+const DEFAULT_STALE_AFTER_MINUTES: i64 = 30;
+const TICK_SECONDS: f64 = 60.0;
+const RESUME_GAP_MINUTES: i64 = 5; // ticks further apart than this mean the machine was asleep
+const SUGGESTION_COUNT: usize = 5;
+const TYPEAHEAD_DELAY_MILLISECONDS: i64 = 400; // looked up once typing pauses for this long
+const SHARED_POLL_MILLISECONDS: i64 = 500; // how often to look for the forecast another pane is fetching
+const TYPEAHEAD_MIN_CHARS: usize = 2;
+const RAW_RESPONSE_PAGE_LINES: usize = 20;
+const DEFAULT_CANDIDATE_GEOCODE_COUNT: usize = 10;
+const MAX_GEOCODE_COUNT: usize = 100; // the most the geocoding API returns
+const TIMEZONE_COMMAND_ID: &str = "TIMEZONE_COMMAND_ID";
+const DEMO_FORECAST: &[u8] = include_bytes!("../fixtures/forecast_with_synthetic_series.json");
+const DEMO_GEOCODE: &[u8] = include_bytes!("../fixtures/geocode.json");
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum View {
+    #[default]
+    Hourly,
+    Daily,
+    Week,
+    Chart,
+    Map,
+    Trip,
+    Team,
+    Aviation,
+}
+
+impl View {
+    pub const ALL: &'static [View] = &[View::Hourly, View::Daily, View::Week, View::Chart];
+    pub fn from_config(view: &str) -> Option<Self> {
+        // the views that need setting up too, they are only opened when they are
+        View::ALL.iter().chain(&[View::Map, View::Trip, View::Team, View::Aviation]).find(|v| v.name().eq_ignore_ascii_case(view.trim())).copied()
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            View::Hourly => "Hourly",
+            View::Daily => "Daily",
+            View::Week => "Week",
+            View::Chart => "Chart",
+            View::Map => "Map",
+            View::Trip => "Trip",
+            View::Team => "Team",
+            View::Aviation => "METAR",
+        }
+    }
+    pub fn next(&self, views: &[View]) -> Self {
+        let index = views.iter().position(|v| v == self).unwrap_or(0);
+        views[(index + 1) % views.len()]
+    }
+    pub fn previous(&self, views: &[View]) -> Self {
+        let index = views.iter().position(|v| v == self).unwrap_or(0);
+        views[(index + views.len() - 1) % views.len()]
+    }
+}
+
+// the order of the hours on screen, to find eg. the warmest one at a glance
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    #[default]
+    Time,
+    Temperature, // warmest first
+    Precipitation, // likeliest first
+}
+
+impl SortOrder {
+    pub fn next(&self) -> Self {
+        match self {
+            SortOrder::Time => SortOrder::Temperature,
+            SortOrder::Temperature => SortOrder::Precipitation,
+            SortOrder::Precipitation => SortOrder::Time,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct State {
+    pub(crate) weather_data: BTreeMap<usize, HourlyData>,
+    pub(crate) daily_data: BTreeMap<usize, DailyData>,
+    pub(crate) yesterday: Option<DailyData>,
+    pub(crate) forecast_accuracy: Option<Accuracy>, // of the forecasts recorded on earlier fetches, for the hours since yesterday
+    pub(crate) nowcast: Vec<Option<f64>>,
+    pub(crate) fetched_at: Option<DateTime<Local>>,
+    pub(crate) stale_after_minutes: Option<i64>,
+    pub(crate) last_tick: Option<DateTime<Local>>,
+    pub(crate) refresh_interval_minutes: Option<i64>, // automatic refreshes are off without it
+    pub(crate) minutes_until_refresh: Option<i64>,
+    pub(crate) data_age_minutes: Option<i64>,
+    pub(crate) requested_timezone: Option<String>,
+    pub(crate) weather_location: Option<String>,
+    pub(crate) geolocation: Option<(f64, f64)>, // lat, lon
+    pub(crate) configured_coordinates: Option<(f64, f64)>, // lat, lon, used instead of geocoding
+    pub(crate) favorites: Vec<Favorite>,
+    pub(crate) favorite_index: Option<usize>, // the favorite being shown
+    pub(crate) compare_index: Option<usize>, // the favorite the hourly view is compared with
+    pub(crate) compare_data: BTreeMap<usize, HourlyData>,
+    pub(crate) geocode_count: Option<usize>,
+    pub(crate) prefer_largest: bool,
+    pub(crate) elevation: Option<f64>, // meters
+    pub(crate) requested_elevation: Option<f64>,
+    pub(crate) error: Option<WeatherPalError>,
+    pub(crate) fetching_data: bool,
+    pub(crate) location_being_typed: Option<LineInput>,
+    pub(crate) location_not_found: Option<String>, // shown above the location input until something else is looked up
+    pub(crate) typeahead: Vec<(f64, f64, String)>, // matches for what is being typed, lat, lon, location
+    pub(crate) typeahead_cursor: Option<usize>, // the match picked with the arrow keys, none for the text as typed
+    pub(crate) typeahead_due: Option<DateTime<Local>>, // when to look up what is being typed
+    pub(crate) shared_wait: Option<(String, DateTime<Local>)>, // the url another pane is fetching, and when to stop waiting for it
+    pub(crate) geocode_queries: Vec<String>, // forms of the location still to be geocoded, as typed and then without diacritics
+    pub(crate) relaxed_location_queries: Vec<String>, // still to be tried after the location wasn't found
+    pub(crate) location_suggestions: Vec<(f64, f64, String)>, // lat, lon, location
+    pub(crate) palette: Palette,
+    pub(crate) language: Language,
+    pub(crate) locale: Locale,
+    pub(crate) demo: bool,
+    pub(crate) request_log: RequestLog,
+    pub(crate) requests: RequestQueue,
+    pub(crate) show_debug_overlay: bool,
+    pub(crate) permission_rationale: bool, // shown until it is answered, before asking for permissions
+    pub(crate) timezone_command: Option<bool>, // none until answered
+    pub(crate) forecast_site: Option<String>, // opened in the browser with o
+    pub(crate) open_command: Option<String>,
+    pub(crate) format: Option<String>, // of the one-line summary
+    pub(crate) screen_reader: bool, // sentences instead of tables
+    pub(crate) compact: bool, // only the one-line summary whatever the size of the pane, until another view is picked
+    pub(crate) pane_title: bool, // the one-line summary as the title of the pane
+    pub(crate) pane_title_shown: Option<String>,
+    pub(crate) hook_command: Option<String>, // run when the conditions of the current hour change
+    pub(crate) hook_temperatures: Vec<f64>, // celsius
+    pub(crate) hook_conditions: Option<Conditions>, // of the current hour when last checked
+    pub(crate) settings_cursor: Option<usize>, // the selected setting, while the settings view is open
+    pub(crate) saved_settings: BTreeMap<String, String>, // changed from the settings view
+    pub(crate) raw_response_scroll: Option<usize>, // the first line shown of the last response, while it is open
+    pub(crate) raw_response_index: usize, // which of the requests it is the response to, the forecast first
+    pub(crate) view: View,
+    pub(crate) sort_order: SortOrder,
+    pub(crate) day_offset: usize,
+    pub(crate) scroll_offset: usize, // hours past the start of the day being shown
+    pub(crate) page_size: usize, // hours that fit the pane when it was last rendered
+    pub(crate) hide_controls: bool,
+    pub(crate) hide_alerts: bool,
+    pub(crate) map_view: bool, // experimental, requests a grid of points around the location
+    pub(crate) temperature_map: Vec<Option<f64>>, // row by row from the north west, empty until requested
+    pub(crate) trip_legs: Vec<TripLeg>,
+    pub(crate) team: Vec<TeamMember>,
+    pub(crate) metar: Option<Metar>, // when the location is an airport
+    pub(crate) taf: Option<String>,
+    pub(crate) clock: Option<DateTime<Utc>>, // when the pane was last rendered, for the local times of the team and the sun position
+    pub(crate) alerts: Vec<Alert>,
+    pub(crate) kp_forecast: Vec<KpIndex>, // only requested at high latitudes
+    pub(crate) climate_normals: BTreeMap<String, f64>, // the average high of each day of the year, eg. "11-19"
+    pub(crate) hide_location: bool,
+    pub(crate) location_label: Option<String>,
+    pub(crate) activity_weights: ActivityWeights,
+    pub(crate) precipitation_threshold: Option<usize>, // percent, rows at or above it are highlighted
+    pub(crate) gardening: bool,
+    pub(crate) wind_heights: Vec<usize>, // meters above the ground, eg. for drones and wind turbines
+    pub(crate) hourly_extra: Vec<String>, // any other variables of the API, shown as they come
+    pub(crate) hourly_units: BTreeMap<String, String>,
+    pub(crate) profile: Option<Profile>,
+    pub(crate) marine_data: BTreeMap<usize, MarineData>, // by the same hours as the forecast, for profiles at sea
+    pub(crate) air_quality: BTreeMap<usize, AirQuality>, // likewise, for the allergy profile
+    pub(crate) air_quality_guidance: bool, // requests the air quality without the allergy profile
+    pub(crate) air_quality_thresholds: AirQualityThresholds,
+    pub(crate) pv_system: Option<PvSystem>,
+    pub(crate) show_best_window: bool,
+    pub(crate) picking_day: bool,
+    pub(crate) comfort_tint: bool,
+    pub(crate) horizontal_hourly: bool, // hours as columns, for wide and short panes
+    pub(crate) commute: Vec<CommuteWindow>,
+    pub(crate) best_window_hours: Option<usize>,
+    pub(crate) show_dry_window: bool,
+    pub(crate) dry_window_hours: Option<usize>,
+    pub(crate) dry_below: Option<usize>,
+}
+
+impl ZellijPlugin for State {
+    fn load(&mut self, mut configuration: BTreeMap<String, String>) {
+        // what was changed from the settings view wins over the layout
+        let saved_settings = read_settings();
+        self.saved_settings = saved_settings.settings;
+        configuration.extend(self.saved_settings.clone());
+        if let Some(location) = configuration.get("location") {
+            self.requested_timezone = Some(location.clone());
+        }
+        let latitude = configuration.get("latitude").and_then(|l| l.trim().parse::<f64>().ok()).filter(|l| (-90.0..=90.0).contains(l));
+        let longitude = configuration.get("longitude").and_then(|l| l.trim().parse::<f64>().ok()).filter(|l| (-180.0..=180.0).contains(l));
+        self.configured_coordinates = latitude.zip(longitude);
+        self.favorites = configuration.get("locations").map(|l| parse_favorites(l)).unwrap_or_default();
+        if self.requested_timezone.is_none() && self.configured_coordinates.is_none() && !self.favorites.is_empty() {
+            self.favorite_index = Some(0);
+        }
+        if let Some(palette) = configuration.get("palette") {
+            self.palette = Palette::from_config(palette);
+        }
+        if configuration.get("high_contrast").map(|h| h == "true").unwrap_or(false) {
+            self.palette = Palette::HighContrast;
+        }
+        if let Some(locale) = configuration.get("locale") {
+            self.locale = Locale::from_config(locale);
+            self.language = Language::from_config(locale);
+        }
+        if let Some(language) = configuration.get("language") {
+            self.language = Language::from_config(language);
+        }
+        if let Some(units) = configuration.get("units").and_then(|u| UnitSystem::from_config(u)) {
+            self.locale.units = units;
+        }
+        self.request_log.enabled = configuration.get("debug").map(|d| d == "true").unwrap_or(false);
+        for note in saved_settings.notes {
+            self.request_log.log(&note);
+        }
+        self.hide_controls = configuration.get("show_controls").map(|s| s == "false").unwrap_or(false);
+        self.hide_alerts = configuration.get("alerts").map(|a| a == "false").unwrap_or(false);
+        self.map_view = configuration.get("map_view").map(|m| m == "true").unwrap_or(false);
+        self.trip_legs = configuration.get("trip").map(|t| parse_trip(t, Local::now().date_naive())).unwrap_or_default();
+        self.team = configuration.get("team").map(|t| parse_team(t)).unwrap_or_default();
+        if let Some(default_view) = configuration.get("default_view") {
+            let is_airport = self.location_query().map(|query| is_airport_code(&query)).unwrap_or(false);
+            match View::from_config(default_view) {
+                _ if default_view.trim().eq_ignore_ascii_case("compact") => self.compact = true,
+                Some(view) if self.views().contains(&view) || (view == View::Aviation && is_airport) => self.view = view,
+                _ => self.request_log.log(&format!("default_view {} is unknown or not set up, opening the hourly forecast", default_view)),
+            }
+        }
+        self.hide_location = configuration.get("show_location").map(|s| s == "false").unwrap_or(false);
+        self.location_label = configuration.get("label").cloned();
+        self.air_quality_guidance = configuration.get("air_quality").map(|a| a == "true").unwrap_or(false);
+        if let Some(air_quality_thresholds) = configuration.get("air_quality_thresholds") {
+            self.air_quality_thresholds = AirQualityThresholds::from_config(air_quality_thresholds);
+        }
+        if let Some(activity_weights) = configuration.get("activity_weights") {
+            self.activity_weights = ActivityWeights::from_config(activity_weights);
+        }
+        self.precipitation_threshold = configuration.get("precipitation_threshold").and_then(|t| t.trim_end_matches('%').parse().ok());
+        self.best_window_hours = configuration.get("window_hours").and_then(|h| h.parse().ok()).filter(|h| *h > 0);
+        self.dry_window_hours = configuration.get("dry_hours").and_then(|h| h.parse().ok()).filter(|h| *h > 0);
+        self.dry_below = configuration.get("dry_below").and_then(|t| t.trim_end_matches('%').parse().ok());
+        self.geocode_count = configuration.get("geocode_count").and_then(|c| c.parse().ok());
+        self.prefer_largest = configuration.get("prefer_largest").map(|p| p == "true").unwrap_or(false);
+        self.requested_elevation = configuration.get("elevation").and_then(|e| e.parse().ok());
+        self.refresh_interval_minutes = configuration.get("refresh_interval").and_then(|r| r.parse().ok()).filter(|r| *r > 0);
+        self.stale_after_minutes = configuration.get("stale_after").and_then(|s| s.parse().ok()).filter(|s| *s > 0);
+        self.horizontal_hourly = configuration.get("hourly_layout").map(|l| l == "horizontal").unwrap_or(false);
+        self.commute = configuration.get("commute").map(|c| parse_commute(c)).unwrap_or_default();
+        self.comfort_tint = configuration.get("comfort_tint").map(|c| c == "true").unwrap_or(false);
+        self.gardening = configuration.get("gardening").map(|g| g == "true").unwrap_or(false);
+        self.profile = configuration.get("profile").and_then(|p| Profile::from_config(p));
+        self.wind_heights = configuration.get("wind_heights").map(|w| parse_wind_heights(w)).unwrap_or_default();
+        self.hourly_extra = configuration.get("hourly_extra").map(|h| parse_hourly_extra(h)).unwrap_or_default();
+        self.forecast_site = configuration.get("forecast_site").cloned();
+        self.open_command = configuration.get("open_command").cloned();
+        self.format = configuration.get("format").cloned();
+        self.screen_reader = configuration.get("screen_reader").map(|s| s == "true").unwrap_or(false);
+        self.pane_title = configuration.get("pane_title").map(|p| p == "true").unwrap_or(false);
+        self.hook_command = configuration.get("hook_command").cloned();
+        self.hook_temperatures = configuration.get("hook_temperatures").map(|t| parse_hook_temperatures(t)).unwrap_or_else(|| DEFAULT_HOOK_TEMPERATURES.to_vec());
+        if let Some(peak_power_kw) = configuration.get("pv_kwp").and_then(|k| k.parse().ok()) {
+            self.pv_system = Some(PvSystem {
+                peak_power_kw,
+                tilt: configuration.get("pv_tilt").and_then(|t| t.parse().ok()).unwrap_or(DEFAULT_PANEL_TILT),
+                azimuth: configuration.get("pv_azimuth").and_then(|a| a.parse().ok()).unwrap_or(DEFAULT_PANEL_AZIMUTH),
+            });
+        }
+        self.demo = configuration.get("demo").map(|d| d == "true").unwrap_or(false);
+        if self.demo {
+            self.load_demo_data();
+            self.request_permissions();
+            subscribe(&[EventType::Key]);
+        } else {
+            self.timezone_command = configuration.get("timezone_command").map(|t| t != "false");
+            if self.timezone_command.is_some() {
+                self.request_permissions();
+            } else {
+                self.permission_rationale = true;
+            }
+            subscribe(&[
+                EventType::Key,
+                EventType::WebRequestResult,
+                EventType::RunCommandResult,
+                EventType::CustomMessage,
+                EventType::Visible,
+                EventType::Timer
+            ]);
+            set_timeout(TICK_SECONDS);
+        }
+    }
+
+    fn update(&mut self, event: Event) -> bool {
+        let mut should_render = false;
+        match event {
+            Event::PermissionRequestResult(..) => {
+                self.resolve_favorites();
+                self.request_next_trip_leg();
+                self.resolve_team();
+                self.discover_local_timezone_or_make_geocode_request();
+            }
+            Event::Timer(seconds) if seconds < TICK_SECONDS => {
+                // the shorter timers wait for typing to pause or for the next request to be due
+                self.look_up_typed_location(Local::now());
+                self.wait_for_shared_forecast(Local::now());
+                self.send_ready_requests();
+            }
+            Event::Timer(_) => {
+                should_render = self.tick(Local::now());
+                set_timeout(TICK_SECONDS);
+            }
+            Event::Visible(true) if self.is_stale(Local::now()) => {
+                // eg. a pane that was hidden in a stack or another tab for a while
+                self.refresh();
+            }
+            Event::RunCommandResult(exit_code, _stdout, stderr, context) if context.get("id").map(|s| s.as_str()) == Some(OPEN_COMMAND_ID) => {
+                match exit_code {
+                    Some(0) => (),
+                    _ => self.request_log.log(&format!("opening the forecast in the browser failed with exit code {:?}: {}", exit_code, String::from_utf8_lossy(&stderr))),
+                }
+            }
+            Event::RunCommandResult(exit_code, _stdout, stderr, context) if context.get("id").map(|s| s.as_str()) == Some(HOOK_COMMAND_ID) => {
+                match exit_code {
+                    Some(0) => (),
+                    _ => self.request_log.log(&format!("hook command failed with exit code {:?}: {}", exit_code, String::from_utf8_lossy(&stderr))),
+                }
+            }
+            Event::RunCommandResult(exit_code, stdout, stderr, context) => {
+                if !stderr.is_empty() {
+                    let error = String::from_utf8(stderr).unwrap_or("".to_owned());
+                    self.request_log.log(&format!("timezone command failed with exit code {:?}: {}", exit_code, error));
+                    self.error = Some(WeatherPalError::Command(error));
+                }
+                if &context.get("id").map(|s| s.as_str()) == &Some(TIMEZONE_COMMAND_ID) && exit_code == Some(0) {
+                    self.requested_timezone = String::from_utf8(stdout).ok().map(|s| s.trim().to_owned());
+                    self.request_log.log(&format!("discovered timezone: {:?}", self.requested_timezone));
+                }
+                self.make_geocode_request();
+            }
+            Event::WebRequestResult(status_code, _headers, body, context) => {
+                if let Some(id) = context.get("id") {
+                    self.request_log.finished(id, status_code, body.len());
+                    self.request_log.keep_response(id, status_code, &body);
+                    self.requests.finished(id);
+                    self.send_ready_requests();
+                }
+                match context.get("id").map(|s| s.as_str()) {
+                    Some("weather") => {
+                        if status_code != 200 {
+                            self.error = Some(WeatherPalError::Http(RequestKind::Weather, status_code, body_excerpt(&body)));
+                            if let Some(url) = context.get("url") {
+                                release_fetch(url);
+                            }
+                            should_render = true;
+                        } else {
+                            if let Some(url) = context.get("url") {
+                                share_response(url, Utc::now().timestamp(), &body);
+                            }
+                            parse_in_worker("weather", &body);
+                        }
+                    }
+                    Some("compare") => {
+                        // the comparison stays empty rather than taking over the pane with an error
+                        self.handle_response(RequestKind::Weather, "compare", status_code, &body);
+                    }
+                    Some("geocode") => {
+                        if status_code != 200 {
+                            self.error = Some(WeatherPalError::Http(RequestKind::Geocode, status_code, body_excerpt(&body)));
+                            should_render = true;
+                        } else {
+                            parse_in_worker(if self.prefer_largest { "geocode_prefer_largest" } else { "geocode" }, &body);
+                        }
+                    }
+                    Some("suggestions") => {
+                        if status_code != 200 {
+                            self.try_relaxed_location_query();
+                        } else {
+                            parse_in_worker("suggestions", &body);
+                        }
+                        should_render = true;
+                    }
+                    Some("typeahead") => {
+                        // the matches of before stay until the next look up
+                        self.handle_response(RequestKind::Geocode, "typeahead", status_code, &body);
+                    }
+                    Some("elevation") => {
+                        // the forecast is still useful without the altitude, so this is not shown as an error
+                        self.handle_response(RequestKind::Elevation, "elevation", status_code, &body);
+                    }
+                    Some("metar") => {
+                        let parsed = self.handle_response(RequestKind::Aviation, "metar", status_code, &body);
+                        if !parsed {
+                            self.geocode_location_query();
+                            should_render = true;
+                        }
+                    }
+                    Some(id) if id == "metar_update" || id == "taf" => {
+                        self.handle_response(RequestKind::Aviation, id, status_code, &body);
+                    }
+                    Some(AURORA_REQUEST_ID) => {
+                        self.handle_response(RequestKind::Aurora, AURORA_REQUEST_ID, status_code, &body);
+                    }
+                    Some(CLIMATE_REQUEST_ID) => {
+                        self.handle_response(RequestKind::Climate, CLIMATE_REQUEST_ID, status_code, &body);
+                    }
+                    Some(AIR_QUALITY_REQUEST_ID) => {
+                        self.handle_response(RequestKind::AirQuality, AIR_QUALITY_REQUEST_ID, status_code, &body);
+                    }
+                    Some("marine") => {
+                        // eg. for a location too far from the sea
+                        self.handle_response(RequestKind::Marine, "marine", status_code, &body);
+                    }
+                    Some("map") => {
+                        self.handle_response(RequestKind::Map, "map", status_code, &body);
+                    }
+                    Some(id) if id == NWS_ALERTS_REQUEST_ID || id.starts_with(METEOALARM_REQUEST_PREFIX) => {
+                        // like the altitude, warnings are an extra on top of the forecast
+                        self.handle_response(RequestKind::Alerts, id, status_code, &body);
+                    }
+                    Some(id) if id.starts_with(TRIP_GEOCODE_PREFIX) || id.starts_with(TRIP_FORECAST_PREFIX) => {
+                        let kind = if id.starts_with(TRIP_GEOCODE_PREFIX) { RequestKind::Geocode } else { RequestKind::Weather };
+                        let parsed = self.handle_response(kind, id, status_code, &body);
+                        if !parsed {
+                            self.fail_trip_leg();
+                            should_render = true;
+                        }
+                    }
+                    Some(TEAM_REQUEST_ID) => {
+                        self.handle_response(RequestKind::Team, TEAM_REQUEST_ID, status_code, &body);
+                    }
+                    Some(id) if id.starts_with(TEAM_GEOCODE_PREFIX) => {
+                        let parsed = self.handle_response(RequestKind::Geocode, id, status_code, &body);
+                        if !parsed {
+                            if let Some(member) = id.strip_prefix(TEAM_GEOCODE_PREFIX).and_then(|i| i.parse::<usize>().ok()).and_then(|i| self.team.get_mut(i)) {
+                                member.failed = true;
+                            }
+                            self.make_team_request();
+                            should_render = true;
+                        }
+                    }
+                    Some(id) if id.starts_with(FAVORITE_FORECAST_PREFIX) => {
+                        if status_code != 200 {
+                            self.request_log.log(&WeatherPalError::Http(RequestKind::Weather, status_code, body_excerpt(&body)).to_string());
+                        } else if let Some(url) = context.get("url") {
+                            share_response(url, Utc::now().timestamp(), &body);
+                        }
+                    }
+                    Some(id) if id.starts_with(FAVORITE_REQUEST_PREFIX) => {
+                        // unresolved favorites are geocoded when switching to them instead
+                        self.handle_response(RequestKind::Geocode, id, status_code, &body);
+                    }
+                    _ => {}
+                }
+            }
+            Event::CustomMessage(message, payload) => {
+                if message == WEATHER_PARSED_MESSAGE {
+                    match decode::<Forecast>(&payload, RequestKind::Weather) {
+                        Ok(forecast) => {
+                            self.request_log.log(&format!("parsed {} hours and {} days of forecast data", forecast.hourly.len(), forecast.daily.len()));
+                            self.weather_data = forecast.hourly;
+                            self.daily_data = forecast.daily;
+                            self.yesterday = forecast.yesterday;
+                            self.nowcast = forecast.nowcast;
+                            self.hourly_units = forecast.hourly_units;
+                            self.track_forecast_accuracy(&forecast.past_hourly, Local::now().hour() as usize);
+                            self.run_hook_command(Local::now().hour() as usize);
+                            self.fetched_at = Some(Local::now());
+                            self.minutes_until_refresh = self.refresh_interval_minutes;
+                            self.data_age_minutes = Some(0);
+                            self.fetching_data = false;
+                        }
+                        Err(e) => {
+                            self.request_log.log(&e.to_string());
+                            self.error = Some(e);
+                        }
+                    }
+                    should_render = true;
+                } else if message == GEOCODE_PARSED_MESSAGE {
+                    match decode(&payload, RequestKind::Geocode) {
+                        Ok((latitude, longitude, location)) => {
+                            self.request_log.log(&format!("geocoded {} to {}, {}", location, latitude, longitude));
+                            self.use_location(latitude, longitude, location);
+                        },
+                        Err(WeatherPalError::GeocodeEmpty) if !self.geocode_queries.is_empty() => {
+                            self.try_next_geocode_query();
+                        }
+                        Err(WeatherPalError::GeocodeEmpty) => {
+                            self.relaxed_location_queries = self
+                                .location_query()
+                                .map(|query| relaxed_queries(&query).into_iter().filter(|q| !query_forms(&query).contains(q)).collect())
+                                .unwrap_or_default();
+                            self.try_relaxed_location_query();
+                        }
+                        Err(e) => {
+                            self.request_log.log(&e.to_string());
+                            self.error = Some(e);
+                        }
+                    }
+                    should_render = true;
+                } else if message == SUGGESTIONS_PARSED_MESSAGE {
+                    match decode::<Vec<(f64, f64, String)>>(&payload, RequestKind::Geocode) {
+                        Ok(suggestions) if !suggestions.is_empty() => {
+                            self.request_log.log(&format!("found {} location suggestions", suggestions.len()));
+                            self.location_suggestions = suggestions;
+                            self.fetching_data = false;
+                        }
+                        _ => self.try_relaxed_location_query(),
+                    }
+                    should_render = true;
+                } else if message == TYPEAHEAD_PARSED_MESSAGE {
+                    // the location might have been entered in the meantime
+                    if self.location_being_typed.is_some() {
+                        self.typeahead = decode(&payload, RequestKind::Geocode).unwrap_or_default();
+                        self.typeahead_cursor = None;
+                    }
+                    should_render = true;
+                } else if message == FAVORITE_PARSED_MESSAGE {
+                    match decode::<(usize, (f64, f64, String))>(&payload, RequestKind::Geocode) {
+                        Ok((index, location)) => {
+                            if let Some(favorite) = self.favorites.get_mut(index) {
+                                favorite.resolved = Some(location);
+                            }
+                        }
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                } else if message == AURORA_PARSED_MESSAGE {
+                    match decode(&payload, RequestKind::Aurora) {
+                        Ok(kp_forecast) => self.kp_forecast = kp_forecast,
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == CLIMATE_PARSED_MESSAGE {
+                    match decode(&payload, RequestKind::Climate) {
+                        Ok(climate_normals) => self.climate_normals = climate_normals,
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == ALERTS_PARSED_MESSAGE {
+                    match decode(&payload, RequestKind::Alerts) {
+                        Ok(alerts) => self.alerts = alerts,
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == TRIP_LOCATION_PARSED_MESSAGE {
+                    match decode::<(usize, (f64, f64, String))>(&payload, RequestKind::Geocode) {
+                        Ok((index, location)) => {
+                            if let Some(leg) = self.trip_legs.get_mut(index) {
+                                leg.location = Some(location);
+                            }
+                            self.request_next_trip_leg();
+                        }
+                        Err(e) => {
+                            self.request_log.log(&e.to_string());
+                            self.fail_trip_leg();
+                        }
+                    }
+                    should_render = true;
+                } else if message == TRIP_FORECAST_PARSED_MESSAGE {
+                    match decode::<(usize, Forecast)>(&payload, RequestKind::Weather) {
+                        Ok((index, mut forecast)) => {
+                            if let Some(leg) = self.trip_legs.get_mut(index) {
+                                let days_ahead = (leg.date - Local::now().date_naive()).num_days().max(0) as usize;
+                                leg.day = forecast.daily.remove(&days_ahead);
+                                leg.failed = leg.day.is_none();
+                            }
+                            self.request_next_trip_leg();
+                        }
+                        Err(e) => {
+                            self.request_log.log(&e.to_string());
+                            self.fail_trip_leg();
+                        }
+                    }
+                    should_render = true;
+                } else if message == TEAM_LOCATION_PARSED_MESSAGE {
+                    match decode::<TeamLocation>(&payload, RequestKind::Geocode) {
+                        Ok((index, location)) => {
+                            if let Some(member) = self.team.get_mut(index) {
+                                match location {
+                                    Ok(location) => member.place.resolved = Some(location),
+                                    Err(e) => {
+                                        self.request_log.log(&e.to_string());
+                                        member.failed = true;
+                                    }
+                                }
+                            }
+                            self.make_team_request();
+                        }
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == TEAM_PARSED_MESSAGE {
+                    match decode::<Vec<CurrentWeather>>(&payload, RequestKind::Team) {
+                        Ok(current_weather) => {
+                            // in the order the places were asked for, which skips the colleagues that weren't found
+                            for (member, current) in self.team.iter_mut().filter(|m| m.place.resolved.is_some()).zip(current_weather) {
+                                member.current = Some(current);
+                            }
+                        }
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == COMPARE_PARSED_MESSAGE {
+                    match decode::<Forecast>(&payload, RequestKind::Weather) {
+                        Ok(forecast) => self.compare_data = forecast.hourly,
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == METAR_PARSED_MESSAGE {
+                    match decode::<Option<Metar>>(&payload, RequestKind::Aviation) {
+                        Ok(Some(metar)) => {
+                            self.request_log.log(&format!("found airport {} at {}, {}", metar.station, metar.latitude, metar.longitude));
+                            let location = if metar.name.is_empty() { metar.station.clone() } else { format!("{} ({})", metar.name, metar.station) };
+                            self.use_location(metar.latitude, metar.longitude, location);
+                            self.send(make_aviation_request("taf", &metar.station, "taf"));
+                            self.metar = Some(metar);
+                        }
+                        Ok(None) => self.geocode_location_query(),
+                        Err(e) => {
+                            self.request_log.log(&e.to_string());
+                            self.geocode_location_query();
+                        }
+                    }
+                    should_render = true;
+                } else if message == METAR_UPDATED_MESSAGE {
+                    match decode::<Option<Metar>>(&payload, RequestKind::Aviation) {
+                        Ok(Some(metar)) => self.metar = Some(metar),
+                        Ok(None) => {}
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == TAF_PARSED_MESSAGE {
+                    match decode(&payload, RequestKind::Aviation) {
+                        Ok(taf) => self.taf = taf,
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == AIR_QUALITY_PARSED_MESSAGE {
+                    match decode(&payload, RequestKind::AirQuality) {
+                        Ok(air_quality) => self.air_quality = air_quality,
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == MARINE_PARSED_MESSAGE {
+                    match decode(&payload, RequestKind::Marine) {
+                        Ok(marine_data) => self.marine_data = marine_data,
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == MAP_PARSED_MESSAGE {
+                    match decode(&payload, RequestKind::Map) {
+                        Ok(temperature_map) => self.temperature_map = temperature_map,
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                } else if message == ELEVATION_PARSED_MESSAGE {
+                    match decode(&payload, RequestKind::Elevation) {
+                        Ok(elevation) => self.elevation = Some(elevation),
+                        Err(e) => self.request_log.log(&e.to_string()),
+                    }
+                    should_render = true;
+                }
+            }
+            Event::Key(key) => {
+                if self.permission_rationale {
+                    match key {
+                        Key::Char('\n') => self.answer_permission_rationale(true),
+                        Key::Char('n') => self.answer_permission_rationale(false),
+                        _ => (),
+                    }
+                    should_render = true;
+                } else if let Some(cursor) = self.settings_cursor {
+                    self.handle_settings_key(key, cursor);
+                    should_render = true;
+                } else if key == Key::Char('\n') && !self.location_being_typed.as_ref().map(|l| l.is_pasting()).unwrap_or(false) {
+                    self.location_suggestions.clear();
+                    self.location_not_found = None;
+                    let typeahead_match = self.typeahead_cursor.take().and_then(|i| self.typeahead.get(i).cloned());
+                    self.typeahead.clear();
+                    self.typeahead_due = None;
+                    if let Some(_error) = self.error.take() {
+                        self.fetching_data = false;
+                    } else if let (Some((latitude, longitude, location)), Some(typed)) = (typeahead_match, self.location_being_typed.as_ref()) {
+                        self.requested_timezone = Some(typed.text().to_owned());
+                        self.location_being_typed = None;
+                        self.configured_coordinates = None;
+                        self.favorite_index = None;
+                        self.fetching_data = true;
+                        self.use_location(latitude, longitude, location);
+                    } else if self.refreshes_on_enter() {
+                        self.refresh();
+                    } else {
+                        if let Some(location) = self.location_being_typed.take() {
+                            self.requested_timezone = Some(location.into_text());
+                            self.configured_coordinates = None;
+                            self.favorite_index = None;
+                        }
+                        self.fetching_data = true;
+                        self.discover_local_timezone_or_make_geocode_request();
+                    }
+                    should_render = true;
+                } else if let Key::Ctrl('w') = key {
+                    self.error = None;
+                    self.location_suggestions.clear();
+                    self.location_not_found = None;
+                    self.typeahead.clear();
+                    self.typeahead_cursor = None;
+                    self.location_being_typed = Some(LineInput::default());
+                    should_render = true;
+                } else if let Key::Ctrl('d') = key {
+                    self.show_debug_overlay = !self.show_debug_overlay;
+                    should_render = true;
+                } else if let Key::Ctrl('r') = key {
+                    self.raw_response_scroll = if self.raw_response_scroll.is_some() { None } else { Some(0) };
+                    self.raw_response_index = 0;
+                    should_render = true;
+                } else if let (Some(_), Key::Left | Key::Right) = (self.raw_response_scroll, key) {
+                    let response_count = self.request_log.responses().len().max(1);
+                    self.raw_response_index = if key == Key::Right { (self.raw_response_index + 1) % response_count } else { (self.raw_response_index + response_count - 1) % response_count };
+                    self.raw_response_scroll = Some(0);
+                    should_render = true;
+                } else if let Some(scroll) = self.raw_response_scroll {
+                    let last_line = self.request_log.responses().get(self.raw_response_index).map(|r| r.lines.len().saturating_sub(1)).unwrap_or(0);
+                    self.raw_response_scroll = match key {
+                        Key::Down | Key::Char('j') => Some((scroll + 1).min(last_line)),
+                        Key::Up | Key::Char('k') => Some(scroll.saturating_sub(1)),
+                        Key::PageDown => Some((scroll + RAW_RESPONSE_PAGE_LINES).min(last_line)),
+                        Key::PageUp => Some(scroll.saturating_sub(RAW_RESPONSE_PAGE_LINES)),
+                        Key::Esc => None,
+                        _ => Some(scroll),
+                    };
+                    should_render = true;
+                } else if self.location_being_typed.is_some() && matches!(key, Key::Down | Key::Up) && !self.typeahead.is_empty() {
+                    // up from the first match goes back to the text as typed
+                    self.typeahead_cursor = match (key, self.typeahead_cursor) {
+                        (Key::Down, None) => Some(0),
+                        (Key::Down, Some(i)) => Some((i + 1).min(self.typeahead.len() - 1)),
+                        (_, cursor) => cursor.and_then(|i| i.checked_sub(1)),
+                    };
+                    should_render = true;
+                } else if let Some(location_being_typed) = self.location_being_typed.as_mut() {
+                    let typed = location_being_typed.text().to_owned();
+                    should_render = location_being_typed.handle_key(&key);
+                    if location_being_typed.text() != typed {
+                        self.location_typed(Local::now());
+                    }
+                } else if self.picking_day {
+                    if let Some(day) = suggestion_index(&key, self.pickable_days().len()) {
+                        self.day_offset = day;
+                        self.scroll_offset = 0;
+                    }
+                    self.picking_day = false;
+                    should_render = true;
+                } else if key == Key::Char('\t') && self.compact {
+                    self.compact = false;
+                    should_render = true;
+                } else if key == Key::Char('\t') {
+                    self.view = self.view.next(&self.views());
+                    self.request_temperature_map_if_needed();
+                    should_render = true;
+                } else if key == Key::BackTab {
+                    self.view = self.view.previous(&self.views());
+                    self.request_temperature_map_if_needed();
+                    should_render = true;
+                } else if key == Key::Right || key == Key::Char('n') {
+                    let last_day = self.weather_data.keys().last().map(|h| h / 24).unwrap_or(0);
+                    self.day_offset = (self.day_offset + 1).min(last_day);
+                    self.scroll_offset = 0;
+                    should_render = true;
+                } else if key == Key::Left || key == Key::Char('p') {
+                    self.day_offset = self.day_offset.saturating_sub(1);
+                    self.scroll_offset = 0;
+                    should_render = true;
+                } else if key == Key::PageDown {
+                    let first_hour = self.first_displayed_hour(chrono::Local::now().hour() as usize) - self.scroll_offset;
+                    let last_hour = self.weather_data.keys().next_back().copied().unwrap_or(0);
+                    self.scroll_offset = (self.scroll_offset + self.page_size.max(1)).min(last_hour.saturating_sub(first_hour));
+                    should_render = true;
+                } else if key == Key::Char('t') {
+                    self.day_offset = 0;
+                    self.scroll_offset = 0;
+                    should_render = true;
+                } else if key == Key::Char('g') && !self.weather_data.is_empty() {
+                    self.picking_day = true;
+                    should_render = true;
+                } else if key == Key::PageUp {
+                    self.scroll_offset = self.scroll_offset.saturating_sub(self.page_size.max(1));
+                    should_render = true;
+                } else if let Some(index) = suggestion_index(&key, self.location_suggestions.len()) {
+                    let (latitude, longitude, location) = self.location_suggestions.remove(index);
+                    self.location_suggestions.clear();
+                    self.fetching_data = true;
+                    self.use_location(latitude, longitude, location);
+                    should_render = true;
+                } else if key == Key::Char('q') || key == Key::Esc {
+                    close_plugin_pane(get_plugin_ids().plugin_id);
+                } else if key == Key::Char('o') {
+                    self.open_forecast_site();
+                } else if key == Key::Char('R') {
+                    self.refresh_all();
+                    should_render = true;
+                } else if key == Key::Char('u') {
+                    // everything is converted when rendering, so nothing needs to be fetched again
+                    self.locale.units = match self.locale.units {
+                        UnitSystem::Metric => UnitSystem::Imperial,
+                        UnitSystem::Imperial => UnitSystem::Metric,
+                    };
+                    should_render = true;
+                } else if key == Key::Char('s') {
+                    self.settings_cursor = Some(0);
+                    should_render = true;
+                } else if key == Key::Char('S') {
+                    self.sort_order = self.sort_order.next();
+                    should_render = true;
+                } else if key == Key::Char('b') {
+                    self.show_best_window = !self.show_best_window;
+                    should_render = true;
+                } else if key == Key::Char('c') && !self.favorites.is_empty() {
+                    self.compare_with_next_favorite();
+                    should_render = true;
+                } else if key == Key::Char('d') {
+                    // scroll the next dry window into view, it stays highlighted until pressed again
+                    self.show_dry_window = !self.show_dry_window;
+                    let hour = chrono::Local::now().hour() as usize;
+                    if let (true, Some(start)) = (self.show_dry_window, self.next_dry_window(hour)) {
+                        self.day_offset = 0;
+                        self.scroll_offset = start - hour;
+                    }
+                    should_render = true;
+                } else if key == Key::Char('f') && !self.favorites.is_empty() {
+                    let index = self.favorite_index.map(|i| (i + 1) % self.favorites.len()).unwrap_or(0);
+                    self.switch_to_favorite(index);
+                    should_render = true;
+                }
+            }
+            _ => (),
+        };
+        should_render
+    }
+
+    fn render(&mut self, rows: usize, cols: usize) {
+        let hour = chrono::Local::now().hour() as usize;
+        self.page_size = self.hourly_layout(rows).1;
+        self.clock = Some(Utc::now());
+        if self.pane_title && !self.weather_data.is_empty() {
+            let title = self.summary_line(hour);
+            if self.pane_title_shown.as_ref() != Some(&title) {
+                rename_plugin_pane(get_plugin_ids().plugin_id, &title);
+                self.pane_title_shown = Some(title);
+            }
+        }
+        self.render_with(&mut ZellijRenderer, rows, cols, hour);
+    }
+}
+
+impl State {
+    fn discover_local_timezone_or_make_geocode_request(&mut self) {
+        if self.demo {
+            self.load_demo_data();
+        } else if let Some(index) = self.favorite_index {
+            self.switch_to_favorite(index);
+        } else if let Some((latitude, longitude)) = self.configured_coordinates {
+            self.use_location(latitude, longitude, format!("{:.2}, {:.2}", latitude, longitude));
+        } else if self.requested_timezone.is_some() {
+            self.make_geocode_request();
+        } else if self.timezone_command == Some(false) {
+            // without running commands, the location has to be typed
+            self.fetching_data = false;
+            self.location_being_typed = Some(LineInput::default());
+        } else {
+            self.request_log.log("discovering local timezone");
+            let mut run_command_context = BTreeMap::new();
+            run_command_context.insert("id".to_owned(), "TIMEZONE_COMMAND_ID".to_owned());
+            run_command(&vec!["bash", "-c", "timedatectl | grep \"Time zone\" | awk \'{print $3}\'"], run_command_context);
+        }
+    }
+
+    fn load_demo_data(&mut self) {
+        let demo_data = parse_lat_lon_and_location(DEMO_GEOCODE.to_vec(), false)
+            .and_then(|geocode| parse_weather_data(DEMO_FORECAST.to_vec(), PAST_DAYS).map(|forecast| (geocode, forecast)));
+        match demo_data {
+            Ok(((latitude, longitude, location), forecast)) => {
+                self.geolocation = Some((latitude, longitude));
+                self.weather_location = Some(location);
+                self.weather_data = forecast.hourly;
+                self.daily_data = forecast.daily;
+                self.yesterday = forecast.yesterday;
+                self.nowcast = forecast.nowcast;
+                self.hourly_units = forecast.hourly_units;
+                self.fetching_data = false;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    pub(crate) fn daily_temperature_range(&self, hour: usize) -> (f64, f64) { // min, max
+        let day_start = hour - (hour % 24);
+        self.weather_data
+            .range(day_start..day_start + 24)
+            .map(|(_, hourly_data)| hourly_data.temperature_2m)
+            .fold((f64::MAX, f64::MIN), |(min, max), temperature| (min.min(temperature), max.max(temperature)))
+    }
+
+    // the days the forecast reaches, as dates, for jumping to one of them with the number keys
+    pub(crate) fn pickable_days(&self) -> Vec<&str> {
+        let last_day = self.weather_data.keys().last().map(|h| h / 24).unwrap_or(0);
+        self.daily_data.range(0..=last_day).take(9).map(|(_, daily_data)| daily_data.date.as_str()).collect()
+    }
+
+    pub(crate) fn precipitation_threshold(&self) -> usize {
+        self.precipitation_threshold.unwrap_or(DEFAULT_PRECIPITATION_THRESHOLD)
+    }
+    fn resolve_favorites(&mut self) {
+        let mut requests = vec![];
+        for (index, favorite) in self.favorites.iter().enumerate().filter(|(_, f)| f.resolved.is_none()) {
+            let id = format!("{}{}", FAVORITE_REQUEST_PREFIX, index);
+            if let Some(request) = make_geocode_request(&favorite.query, self.language, 1, &id) {
+                requests.push(request);
+            }
+        }
+        for request in requests {
+            self.send(request);
+        }
+    }
+    fn request_next_trip_leg(&mut self) {
+        // one leg at a time, each answer moves on to the next one
+        if self.demo {
+            return;
+        }
+        let today = Local::now().date_naive();
+        while let Some(index) = self.trip_legs.iter().position(|leg| !leg.failed && leg.day.is_none()) {
+            let leg = &mut self.trip_legs[index];
+            if !(0..TRIP_FORECAST_DAYS).contains(&(leg.date - today).num_days()) {
+                leg.failed = true;
+                continue;
+            }
+            let request = match leg.location {
+                None => {
+                    let id = format!("{}{}", TRIP_GEOCODE_PREFIX, index);
+                    make_geocode_request(&leg.query, self.language, 1, &id)
+                }
+                Some((latitude, longitude, _)) => {
+                    let id = format!("{}{}", TRIP_FORECAST_PREFIX, index);
+                    Some(make_trip_forecast_request(latitude, longitude, &id))
+                }
+            };
+            match request {
+                Some(request) => {
+                    self.send(request);
+                    return;
+                }
+                None => self.trip_legs[index].failed = true,
+            }
+        }
+    }
+    // a response the pane does without when it fails, so the failure only goes to the debug log
+    fn handle_response(&mut self, kind: RequestKind, id: &str, status_code: u16, body: &[u8]) -> bool {
+        if status_code != 200 {
+            self.request_log.log(&WeatherPalError::Http(kind, status_code, body_excerpt(body)).to_string());
+            return false;
+        }
+        parse_in_worker(id, body);
+        true
+    }
+    fn fail_trip_leg(&mut self) {
+        // the leg being fetched is skipped so the rest of the trip still comes in
+        if let Some(leg) = self.trip_legs.iter_mut().find(|leg| !leg.failed && leg.day.is_none()) {
+            leg.failed = true;
+        }
+        self.request_next_trip_leg();
+    }
+    fn resolve_team(&mut self) {
+        // everyone is looked up at once, the weather of the whole team comes in a single request after that
+        let mut requests = vec![];
+        for (index, member) in self.team.iter().enumerate().filter(|(_, m)| m.place.resolved.is_none()) {
+            let id = format!("{}{}", TEAM_GEOCODE_PREFIX, index);
+            if let Some(request) = make_geocode_request(&member.place.query, self.language, 1, &id) {
+                requests.push(request);
+            }
+        }
+        for request in requests {
+            self.send(request);
+        }
+        self.make_team_request();
+    }
+    fn make_team_request(&mut self) {
+        if self.demo || self.team.iter().any(|m| m.place.resolved.is_none() && !m.failed) {
+            return;
+        }
+        let coordinates: Vec<(f64, f64)> = self.team.iter().filter_map(|m| m.place.resolved.as_ref()).map(|(latitude, longitude, _)| (*latitude, *longitude)).collect();
+        if coordinates.is_empty() {
+            return;
+        }
+        self.send(make_team_request(&coordinates));
+    }
+    fn compare_with_next_favorite(&mut self) {
+        // cycles through the favorites that are already geocoded, other than the one being shown, and then back to no comparison
+        let first_candidate = self.compare_index.map(|i| i + 1).unwrap_or(0);
+        self.compare_index = (first_candidate..self.favorites.len()).find(|i| self.favorites[*i].resolved.is_some() && Some(*i) != self.favorite_index);
+        self.compare_data.clear();
+        self.make_compare_request();
+    }
+    fn make_compare_request(&mut self) {
+        let Some((latitude, longitude, _)) = self.compare_index.and_then(|i| self.favorites.get(i)).and_then(|f| f.resolved.clone()) else {
+            return;
+        };
+        let url = weather_request_url(latitude, longitude, HOURLY_VARIABLES, DAILY_VARIABLES, None, None);
+        self.send(Request::new("compare", url));
+    }
+    pub(crate) fn compare_location(&self) -> Option<&str> {
+        self.compare_index.and_then(|i| self.favorites.get(i)).and_then(|f| f.resolved.as_ref()).map(|(_, _, location)| location.as_str())
+    }
+    fn switch_to_favorite(&mut self, index: usize) {
+        let Some(favorite) = self.favorites.get(index).cloned() else {
+            return;
+        };
+        self.favorite_index = Some(index);
+        self.day_offset = 0;
+        self.location_suggestions.clear();
+        self.error = None;
+        self.fetching_data = true;
+        match favorite.resolved {
+            Some((latitude, longitude, location)) => self.use_location(latitude, longitude, location),
+            None => {
+                self.requested_timezone = Some(favorite.query);
+                self.make_geocode_request();
+            }
+        }
+    }
+    fn make_geocode_request(&mut self) {
+        // airports are looked up by their latest report, which has the coordinates of the station
+        match self.location_query().filter(|query| is_airport_code(query)) {
+            Some(station) => {
+                self.send(make_aviation_request("metar", &station, "metar"));
+            }
+            None => self.geocode_location_query(),
+        }
+    }
+    fn geocode_location_query(&mut self) {
+        self.geocode_queries = self.location_query().map(|query| query_forms(&query)).unwrap_or_default();
+        self.try_next_geocode_query();
+    }
+    fn try_next_geocode_query(&mut self) {
+        if self.geocode_queries.is_empty() {
+            return;
+        }
+        let query = self.geocode_queries.remove(0);
+        if let Some(request) = make_geocode_request(&query, self.language, self.geocode_count(), "geocode") {
+            self.send(request);
+        }
+    }
+    fn location_query(&self) -> Option<String> {
+        // either a typed location or a timezone like "Europe/Vienna"
+        self.requested_timezone.as_ref().and_then(|t| t.split('/').last()).map(|c| c.to_owned())
+    }
+    fn location_typed(&mut self, now: DateTime<Local>) {
+        let typed = self.location_being_typed.as_ref().map(|l| l.text().trim().chars().count()).unwrap_or(0);
+        self.typeahead_cursor = None;
+        if typed < TYPEAHEAD_MIN_CHARS || self.demo {
+            self.typeahead.clear();
+            self.typeahead_due = None;
+            return;
+        }
+        // every key pushes the look up back, so only the last timer finds it due
+        self.typeahead_due = Some(now + Duration::milliseconds(TYPEAHEAD_DELAY_MILLISECONDS));
+        set_timeout(TYPEAHEAD_DELAY_MILLISECONDS as f64 / 1000.0);
+    }
+    fn wait_for_shared_forecast(&mut self, now: DateTime<Local>) {
+        let Some((url, give_up_at)) = self.shared_wait.clone() else {
+            return;
+        };
+        if let Some(body) = shared_response(&url, now.timestamp()) {
+            self.shared_wait = None;
+            self.request_log.log(&format!("using the forecast another pane fetched: {}", url));
+            parse_in_worker("weather", &body);
+        } else if now >= give_up_at {
+            self.shared_wait = None;
+            self.send(Request::new("weather", url));
+        } else {
+            set_timeout(SHARED_POLL_MILLISECONDS as f64 / 1000.0);
+        }
+    }
+    fn look_up_typed_location(&mut self, now: DateTime<Local>) {
+        if !matches!(self.typeahead_due, Some(due) if due <= now) {
+            return;
+        }
+        self.typeahead_due = None;
+        let typed = self.location_being_typed.as_ref().map(|l| l.text().to_owned()).unwrap_or_default();
+        if let Some(request) = make_geocode_request(&typed, self.language, SUGGESTION_COUNT, "typeahead") {
+            self.send(request);
+        }
+    }
+    fn track_forecast_accuracy(&mut self, past_hourly: &BTreeMap<usize, HourlyData>, hour: usize) {
+        let (Some((latitude, longitude)), Some(yesterday)) = (self.geolocation, self.yesterday.as_ref()) else {
+            return;
+        };
+        let (elapsed, ahead): (Vec<_>, Vec<_>) = self
+            .weather_data
+            .iter()
+            .filter_map(|(i, hourly_data)| self.daily_data.get(&(i / 24)).map(|d| (*i, hour_key(&d.date, i % 24), hourly_data)))
+            .partition(|(i, _, _)| *i < hour);
+        let mut history = read_history(latitude, longitude);
+        // what the model now says happened yesterday and earlier today
+        let happened = past_hourly.iter().map(|(i, hourly_data)| (hour_key(&yesterday.date, *i), hourly_data)).chain(elapsed.into_iter().map(|(_, key, hourly_data)| (key, hourly_data)));
+        self.forecast_accuracy = forecast_accuracy(&history, happened);
+        record_forecast(&mut history, ahead.into_iter().map(|(_, key, hourly_data)| (key, hourly_data)), &hour_key(&yesterday.date, 0));
+        if let Err(e) = save_history(latitude, longitude, &history) {
+            self.request_log.log(&format!("failed to save the forecast history: {}", e));
+        }
+    }
+    fn try_relaxed_location_query(&mut self) {
+        if self.relaxed_location_queries.is_empty() {
+            // back to the input with what was looked up, to fix the spelling
+            let query = self.location_query().unwrap_or_default();
+            self.fetching_data = false;
+            self.location_being_typed = Some(LineInput::from(query.as_str()));
+            self.location_not_found = Some(query);
+            return;
+        }
+        let query = self.relaxed_location_queries.remove(0);
+        self.request_log.log(&format!("no matching location found, looking for suggestions with {}", query));
+        if let Some(request) = make_geocode_request(&query, self.language, SUGGESTION_COUNT, "suggestions") {
+            self.send(request);
+        }
+    }
+    fn send(&mut self, request: Request) {
+        self.requests.push(request);
+        self.send_ready_requests();
+    }
+    fn send_ready_requests(&mut self) {
+        let now = Local::now();
+        if let Some(request) = self.requests.ready(now) {
+            // the url comes back with the response, eg. to share the forecast with other panes
+            let mut context = BTreeMap::new();
+            context.insert("id".to_owned(), request.id.clone());
+            context.insert("url".to_owned(), request.url.clone());
+            web_request(
+                &request.url,
+                HttpVerb::Get,
+                request.headers,
+                vec![],
+                context,
+            );
+            self.request_log.started(&request.id, &request.url);
+        }
+        if let Some(wait) = self.requests.wait(now) {
+            // the rest go out one at a time as the timers fire
+            set_timeout(wait.num_microseconds().unwrap_or(0) as f64 / 1_000_000.0);
+        }
+    }
+    fn use_location(&mut self, latitude: f64, longitude: f64, location: String) {
+        self.geolocation = Some((latitude, longitude));
+        self.weather_location = Some(location);
+        self.marine_data.clear();
+        self.air_quality.clear();
+        self.metar = None;
+        self.taf = None;
+        self.make_weather_request(latitude, longitude);
+        self.elevation = self.requested_elevation;
+        if self.requested_elevation.is_none() {
+            self.send(make_elevation_request(latitude, longitude));
+        }
+        self.alerts.clear();
+        self.hook_conditions = None;
+        self.make_alerts_request(latitude, longitude);
+        // the normals don't change with a refresh, so they are only requested for a new location
+        self.climate_normals.clear();
+        self.send(Request::new(CLIMATE_REQUEST_ID, climate_url(latitude, longitude, Local::now().date_naive())));
+        self.temperature_map.clear();
+        self.request_temperature_map_if_needed();
+    }
+    fn make_weather_request(&mut self, latitude: f64, longitude: f64) {
+        // other panes showing the same location may have just fetched the very same forecast
+        let url = self.weather_url(latitude, longitude);
+        match shared_response(&url, Utc::now().timestamp()) {
+            Some(body) => {
+                self.request_log.log(&format!("using the forecast another pane fetched: {}", url));
+                parse_in_worker("weather", &body);
+            }
+            None if claim_fetch(&url, Utc::now().timestamp()) => {
+                self.send(Request::new("weather", url));
+            }
+            None => {
+                // one of the panes starting together fetches it, the others pick it up when it's shared
+                self.request_log.log(&format!("waiting for another pane to fetch: {}", url));
+                self.shared_wait = Some((url, Local::now() + Duration::seconds(PENDING_MAX_AGE_SECONDS)));
+                set_timeout(SHARED_POLL_MILLISECONDS as f64 / 1000.0);
+            }
+        }
+        if self.profile.map(|p| p.uses_marine_data()).unwrap_or(false) {
+            self.send(make_marine_request(latitude, longitude));
+        }
+        if self.air_quality_guidance || self.profile.map(|p| p.uses_air_quality_data()).unwrap_or(false) {
+            self.send(make_air_quality_request(latitude, longitude));
+        }
+        if is_aurora_latitude(latitude, longitude) {
+            self.send(make_aurora_request());
+        } else {
+            self.kp_forecast.clear();
+        }
+    }
+    fn weather_url(&self, latitude: f64, longitude: f64) -> String {
+        weather_request_url(latitude, longitude, &self.hourly_variables(latitude, longitude), &self.daily_variables(), self.pv_system, self.requested_elevation)
+    }
+    fn favorite_forecast_requests(&self) -> Vec<Request> {
+        // the same url as when switching to the favorite, so it finds the shared response
+        self.favorites
+            .iter()
+            .enumerate()
+            .filter_map(|(index, favorite)| favorite.resolved.as_ref().map(|resolved| (index, resolved)))
+            .filter(|(_, (latitude, longitude, _))| self.geolocation != Some((*latitude, *longitude)))
+            .map(|(index, (latitude, longitude, _))| Request::new(&format!("{}{}", FAVORITE_FORECAST_PREFIX, index), self.weather_url(*latitude, *longitude)))
+            .collect()
+    }
+    fn prefetch_favorites(&mut self) {
+        for request in self.favorite_forecast_requests() {
+            self.send(request);
+        }
+    }
+    fn refreshes_on_enter(&self) -> bool {
+        // the location on screen is already known, only its forecast is fetched again
+        self.location_being_typed.is_none() && !self.demo && self.geolocation.is_some()
+    }
+    fn refresh(&mut self) {
+        // the current forecast stays on screen until the new one arrives
+        if let Some((latitude, longitude)) = self.geolocation {
+            self.request_log.log("refreshing the forecast");
+            self.make_weather_request(latitude, longitude);
+            self.make_alerts_request(latitude, longitude);
+            self.make_compare_request();
+            self.make_team_request();
+            if let Some(station) = self.metar.as_ref().map(|metar| metar.station.clone()) {
+                self.send(make_aviation_request("metar", &station, "metar_update"));
+                self.send(make_aviation_request("taf", &station, "taf"));
+            }
+            if !self.temperature_map.is_empty() {
+                self.make_temperature_map_request(latitude, longitude);
+            }
+        }
+    }
+    fn open_forecast_site(&mut self) {
+        // nothing changes in the pane, so what goes wrong is only in the debug log
+        let Some((latitude, longitude)) = self.geolocation else {
+            return;
+        };
+        if self.demo || self.timezone_command == Some(false) {
+            self.request_log.log("not opening the forecast in the browser without the permission to run commands");
+            return;
+        }
+        let site = self.forecast_site.as_deref().unwrap_or(DEFAULT_FORECAST_SITE);
+        match forecast_site_url(site, latitude, longitude) {
+            Some(url) => {
+                self.request_log.log(&format!("opening {}", url));
+                let command = open_command(self.open_command.as_deref(), &url);
+                let context = BTreeMap::from([("id".to_owned(), OPEN_COMMAND_ID.to_owned())]);
+                run_command(&command.iter().map(|c| c.as_str()).collect::<Vec<_>>(), context);
+            }
+            None => self.request_log.log(&format!("unknown forecast_site {}, expected windy, open-meteo, nws or a url with {{lat}} and {{lon}}", site)),
+        }
+    }
+    fn run_hook_command(&mut self, hour: usize) {
+        let (Some(command), Some(hourly_data)) = (self.hook_command.as_ref(), self.weather_data.get(&hour)) else {
+            return;
+        };
+        let now = conditions(hourly_data, &self.hook_temperatures, self.precipitation_threshold());
+        // the first conditions of a location are only remembered, nothing changed yet
+        let Some(before) = self.hook_conditions.replace(now.clone()) else {
+            return;
+        };
+        for (event, threshold) in transitions(&before, &now, &self.hook_temperatures) {
+            if self.demo || self.timezone_command == Some(false) {
+                self.request_log.log(&format!("not running the hook command for {} without the permission to run commands", event));
+                continue;
+            }
+            self.request_log.log(&format!("running the hook command for {}", event));
+            let environment = hook_environment(event, threshold, hourly_data, self.weather_location.as_deref());
+            let context = BTreeMap::from([("id".to_owned(), HOOK_COMMAND_ID.to_owned())]);
+            run_command_with_env_variables_and_cwd(&["bash", "-c", command], environment, PathBuf::from("."), context);
+        }
+    }
+    fn permissions(&self) -> Vec<PermissionType> {
+        // the demo fetches and runs nothing, but still closes and renames its pane
+        let mut permissions = vec![PermissionType::ReadApplicationState, PermissionType::ChangeApplicationState];
+        if !self.demo {
+            if self.timezone_command != Some(false) {
+                permissions.push(PermissionType::RunCommands);
+            }
+            permissions.push(PermissionType::WebAccess);
+        }
+        permissions
+    }
+    fn request_permissions(&self) {
+        request_permission(&self.permissions());
+    }
+    fn answer_permission_rationale(&mut self, timezone_command: bool) {
+        // remembered, so the rationale is only shown once
+        self.permission_rationale = false;
+        self.timezone_command = Some(timezone_command);
+        self.saved_settings.insert(Setting::TimezoneCommand.key().to_owned(), timezone_command.to_string());
+        if let Err(e) = save_settings(&self.saved_settings) {
+            self.request_log.log(&format!("failed to save the settings: {}", e));
+        }
+        self.request_permissions();
+    }
+    fn handle_settings_key(&mut self, key: Key, cursor: usize) {
+        let setting_count = Setting::ALL.len();
+        match key {
+            Key::Down | Key::Char('j') => self.settings_cursor = Some((cursor + 1) % setting_count),
+            Key::Up | Key::Char('k') => self.settings_cursor = Some((cursor + setting_count - 1) % setting_count),
+            Key::Right | Key::Char('\n') | Key::Char(' ') => self.change_setting(Setting::ALL[cursor], true),
+            Key::Left => self.change_setting(Setting::ALL[cursor], false),
+            Key::Esc | Key::Char('s') => self.settings_cursor = None,
+            _ => (),
+        }
+    }
+    pub(crate) fn setting_value(&self, setting: Setting) -> String {
+        // what is in effect, whether it comes from the layout or the settings view
+        match setting {
+            Setting::Units => match self.locale.units {
+                UnitSystem::Metric => "metric".to_owned(),
+                UnitSystem::Imperial => "imperial".to_owned(),
+            },
+            Setting::Theme => match self.palette {
+                Palette::Default => "default".to_owned(),
+                Palette::Colorblind => "colorblind".to_owned(),
+                Palette::HighContrast => "high-contrast".to_owned(),
+            },
+            Setting::Columns => setting.choices().iter().find(|c| Profile::from_config(c) == self.profile).unwrap_or(&"none").to_string(),
+            Setting::Soil => self.gardening.to_string(),
+            Setting::RefreshInterval => self.refresh_interval_minutes.unwrap_or(0).to_string(),
+            Setting::TimezoneCommand => self.timezone_command.unwrap_or(true).to_string(),
+        }
+    }
+    fn change_setting(&mut self, setting: Setting, forward: bool) {
+        let value = setting.cycle(&self.setting_value(setting), forward);
+        match setting {
+            Setting::Units => self.locale.units = UnitSystem::from_config(value).unwrap_or_default(),
+            Setting::Theme => self.palette = Palette::from_config(value),
+            Setting::Columns => {
+                // the columns of a profile need data of their own
+                self.profile = Profile::from_config(value);
+                self.refresh();
+            }
+            Setting::Soil => {
+                self.gardening = value == "true";
+                self.refresh();
+            }
+            Setting::RefreshInterval => self.refresh_interval_minutes = value.parse().ok().filter(|r| *r > 0),
+            // the permissions are only asked for again the next time the pane is opened
+            Setting::TimezoneCommand => self.timezone_command = Some(value == "true"),
+        }
+        self.saved_settings.insert(setting.key().to_owned(), value.to_owned());
+        if let Err(e) = save_settings(&self.saved_settings) {
+            self.request_log.log(&format!("failed to save the settings: {}", e));
+        }
+    }
+    fn refresh_all(&mut self) {
+        // in the background, along with another try at the places that weren't found before
+        if self.demo {
+            return;
+        }
+        self.refresh();
+        self.prefetch_favorites();
+        self.resolve_favorites();
+        if self.team.iter().any(|m| m.failed) {
+            for member in self.team.iter_mut() {
+                member.failed = false;
+            }
+            self.resolve_team();
+        }
+        // a leg still being fetched moves on to the next ones by itself
+        let trip_in_progress = self.trip_legs.iter().any(|leg| !leg.failed && leg.day.is_none());
+        if !trip_in_progress && self.trip_legs.iter().any(|leg| leg.failed) {
+            for leg in self.trip_legs.iter_mut() {
+                leg.failed = false;
+            }
+            self.request_next_trip_leg();
+        }
+    }
+    pub(crate) fn views(&self) -> Vec<View> {
+        // the map, the trip, the team and the airport reports only have a tab once they are set up
+        let map = if self.map_view { Some(View::Map) } else { None };
+        let trip = if self.trip_legs.is_empty() { None } else { Some(View::Trip) };
+        let team = if self.team.is_empty() { None } else { Some(View::Team) };
+        let aviation = self.metar.as_ref().map(|_| View::Aviation);
+        View::ALL.iter().copied().chain(map).chain(trip).chain(team).chain(aviation).collect()
+    }
+    fn request_temperature_map_if_needed(&mut self) {
+        // only once the map is looked at, it takes a request for every point of the grid
+        if let (View::Map, true, false, Some((latitude, longitude))) = (self.view, self.temperature_map.is_empty(), self.demo, self.geolocation) {
+            self.make_temperature_map_request(latitude, longitude);
+        }
+    }
+    fn make_temperature_map_request(&mut self, latitude: f64, longitude: f64) {
+        self.send(make_temperature_map_request(latitude, longitude));
+    }
+    fn make_alerts_request(&mut self, latitude: f64, longitude: f64) {
+        let Some(location) = self.weather_location.as_ref().filter(|_| !self.hide_alerts) else {
+            return;
+        };
+        match AlertSource::for_location(latitude, longitude, location) {
+            Some(source) => {
+                let place = location.split(", ").next().unwrap_or_default();
+                self.send(make_alerts_request(source, latitude, longitude, place));
+            }
+            None => self.request_log.log("no weather warnings available for this location"),
+        }
+    }
+    fn tick(&mut self, now: DateTime<Local>) -> bool {
+        // true if the current hour row or the refresh countdown need to change
+        let last_tick = self.last_tick.replace(now);
+        let mut should_render = match last_tick {
+            Some(last_tick) if now - last_tick > Duration::minutes(RESUME_GAP_MINUTES) => {
+                self.request_log.log(&format!("resumed after {} minutes", (now - last_tick).num_minutes()));
+                self.refresh();
+                true
+            }
+            Some(last_tick) => last_tick.hour() != now.hour(),
+            None => false,
+        };
+        if last_tick.map(|last_tick| last_tick.hour() != now.hour()).unwrap_or(false) {
+            self.run_hook_command(now.hour() as usize);
+        }
+        if self.view == View::Team {
+            // the local times of the team move on every minute
+            should_render = true;
+        }
+        if self.next_refresh_in(now).map(|next_refresh_in| next_refresh_in <= Duration::zero()).unwrap_or(false) {
+            self.refresh();
+        }
+        let minutes_until_refresh = self.next_refresh_in(now).map(|next_refresh_in| ((next_refresh_in.num_seconds() + 59) / 60).max(1));
+        let data_age_minutes = self.fetched_at.map(|fetched_at| (now - fetched_at).num_minutes());
+        if minutes_until_refresh != self.minutes_until_refresh || data_age_minutes != self.data_age_minutes {
+            self.minutes_until_refresh = minutes_until_refresh;
+            self.data_age_minutes = data_age_minutes;
+            should_render = true;
+        }
+        should_render
+    }
+    pub(crate) fn refresh_minutes(&self) -> i64 {
+        // how old the forecast is expected to get
+        self.refresh_interval_minutes.or(self.stale_after_minutes).unwrap_or(DEFAULT_STALE_AFTER_MINUTES)
+    }
+    fn next_refresh_in(&self, now: DateTime<Local>) -> Option<Duration> {
+        let refresh_interval = Duration::minutes(self.refresh_interval_minutes?);
+        self.fetched_at.map(|fetched_at| refresh_interval - (now - fetched_at))
+    }
+    fn is_stale(&self, now: DateTime<Local>) -> bool {
+        let stale_after = Duration::minutes(self.stale_after_minutes.unwrap_or(DEFAULT_STALE_AFTER_MINUTES));
+        self.fetched_at.map(|fetched_at| now - fetched_at >= stale_after).unwrap_or(false)
+    }
+    fn geocode_count(&self) -> usize {
+        // picking the largest needs some candidates to pick from
+        let default_count = if self.prefer_largest { DEFAULT_CANDIDATE_GEOCODE_COUNT } else { 1 };
+        self.geocode_count.unwrap_or(default_count).clamp(1, MAX_GEOCODE_COUNT)
+    }
+    fn hourly_variables(&self, latitude: f64, longitude: f64) -> Vec<&str> {
+        let mut hourly_variables = HOURLY_VARIABLES.to_vec();
+        if self.gardening {
+            hourly_variables.extend_from_slice(GARDENING_HOURLY_VARIABLES);
+        }
+        if self.pv_system.is_some() {
+            hourly_variables.extend_from_slice(SOLAR_HOURLY_VARIABLES);
+        }
+        if let Some(profile) = self.profile {
+            hourly_variables.extend_from_slice(profile.hourly_variables());
+        }
+        hourly_variables.extend(WIND_HEIGHT_VARIABLES.iter().filter(|(height, _)| self.wind_heights.contains(height)).map(|(_, variable)| *variable));
+        // for the chance to see the aurora tonight
+        if is_aurora_latitude(latitude, longitude) && !hourly_variables.contains(&"cloud_cover") {
+            hourly_variables.push("cloud_cover");
+        }
+        for variable in &self.hourly_extra {
+            if !hourly_variables.contains(&variable.as_str()) {
+                hourly_variables.push(variable);
+            }
+        }
+        hourly_variables
+    }
+    fn daily_variables(&self) -> Vec<&'static str> {
+        let mut daily_variables = DAILY_VARIABLES.to_vec();
+        if self.gardening {
+            daily_variables.extend_from_slice(GARDENING_DAILY_VARIABLES);
+        }
+        daily_variables
+    }
+    pub(crate) fn best_window_hours(&self) -> usize {
+        self.best_window_hours.unwrap_or(DEFAULT_BEST_WINDOW_HOURS)
+    }
+    pub(crate) fn best_window(&self, hour: usize) -> Option<usize> { // first hour of the window
+        // dry, calm and mild hours in a row, the one with the highest activity score wins
+        let window_hours = self.best_window_hours();
+        let (min_temperature, max_temperature) = BEST_WINDOW_TEMPERATURE;
+        let is_suitable = |hourly_data: &HourlyData| {
+            hourly_data.precipitation_probability.map(|p| p < self.precipitation_threshold()).unwrap_or(true)
+                && hourly_data.precipitation.unwrap_or(0.0) == 0.0
+                && hourly_data.wind_speed_10m.map(|w| w < BEST_WINDOW_MAX_WIND).unwrap_or(true)
+                && (min_temperature..=max_temperature).contains(&hourly_data.temperature_2m)
+        };
+        let mut best_window: Option<(usize, usize)> = None; // start, total score
+        for start in hour..(hour + BEST_WINDOW_LOOKAHEAD_HOURS).saturating_sub(window_hours - 1) {
+            let window: Vec<_> = (start..start + window_hours).filter_map(|h| self.weather_data.get(&h)).collect();
+            if window.len() < window_hours || !window.iter().all(|hourly_data| is_suitable(hourly_data)) {
+                continue;
+            }
+            let score = window.iter().map(|hourly_data| activity_score(hourly_data, self.activity_weights)).sum();
+            if best_window.map(|(_, best_score)| score > best_score).unwrap_or(true) {
+                best_window = Some((start, score));
+            }
+        }
+        best_window.map(|(start, _)| start)
+    }
+    pub(crate) fn dry_window_hours(&self) -> usize {
+        self.dry_window_hours.unwrap_or(DEFAULT_DRY_WINDOW_HOURS)
+    }
+    pub(crate) fn dry_below(&self) -> usize {
+        self.dry_below.unwrap_or(DEFAULT_DRY_BELOW)
+    }
+    pub(crate) fn next_dry_window(&self, hour: usize) -> Option<usize> { // first hour of the window
+        // the earliest hours in a row that are all unlikely to see rain, anywhere in the forecast
+        let window_hours = self.dry_window_hours();
+        let dry_below = self.dry_below();
+        let is_dry = |h: usize| self.weather_data.get(&h).and_then(|hourly_data| hourly_data.precipitation_probability).map(|p| p < dry_below).unwrap_or(false);
+        let last_hour = self.weather_data.keys().next_back().copied()?;
+        (hour..=last_hour.saturating_sub(window_hours - 1)).find(|start| (*start..start + window_hours).all(is_dry))
+    }
+    pub(crate) fn first_displayed_hour(&self, hour: usize) -> usize {
+        // today starts at the current hour, other days at midnight
+        let day_start = if self.day_offset == 0 { hour } else { hour - (hour % 24) + self.day_offset * 24 };
+        day_start + self.scroll_offset
+    }
+
+    pub(crate) fn weekly_temperature_range(&self, hour: usize) -> (f64, f64) { // min, max
+        self.daily_data
+            .range(hour / 24..)
+            .take(7)
+            .flat_map(|(_, daily_data)| daily_data.temperature_2m_min.into_iter().chain(daily_data.temperature_2m_max))
+            .fold((f64::MAX, f64::MIN), |(min, max), temperature| (min.min(temperature), max.max(temperature)))
+    }
+
+    pub(crate) fn next_precipitation_window(&self, hour: usize) -> Option<(usize, Option<usize>)> { // first likely hour, first dry hour after it
+        let threshold = self.precipitation_threshold();
+        let is_likely = |hourly_data: &HourlyData| hourly_data.precipitation_probability.map(|p| p >= threshold).unwrap_or(false);
+        let upcoming_hours = || self.weather_data.range(hour..hour + PRECIPITATION_LOOKAHEAD_HOURS);
+        let start = upcoming_hours().find(|(_, hourly_data)| is_likely(hourly_data)).map(|(hour, _)| *hour)?;
+        let end = upcoming_hours().find(|(hour, hourly_data)| **hour > start && !is_likely(hourly_data)).map(|(hour, _)| *hour);
+        Some((start, end))
+    }
+    pub(crate) fn daily_precipitation_and_peak_wind(&self, hour: usize) -> (f64, f64) { // total mm, max kph
+        let day_start = hour - (hour % 24);
+        self.weather_data
+            .range(day_start..day_start + 24)
+            .fold((0.0, 0.0), |(precipitation, peak_wind), (_, hourly_data)| {
+                (precipitation + hourly_data.precipitation.unwrap_or(0.0), hourly_data.wind_speed_10m.unwrap_or(0.0).max(peak_wind))
+            })
+    }
+}
+
+fn weather_request_url(latitude: f64, longitude: f64, hourly_variables: &[&str], daily_variables: &[&str], pv_system: Option<PvSystem>, elevation: Option<f64>) -> String {
+    let mut url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&hourly={}&daily={}&past_days={}",
+        latitude,
+        longitude,
+        hourly_variables.join(","),
+        daily_variables.join(","),
+        PAST_DAYS
+    );
+    if has_native_nowcast(latitude, longitude) {
+        url.push_str(&format!("&minutely_15=precipitation&past_minutely_15=0&forecast_minutely_15={}", NOWCAST_STEPS));
+    }
+    if let Some(pv_system) = pv_system {
+        // the orientation of the panels for global_tilted_irradiance
+        url.push_str(&format!("&tilt={}&azimuth={}", pv_system.tilt, pv_system.azimuth));
+    }
+    if let Some(elevation) = elevation {
+        // instead of the terrain elevation, eg. for a mountain hut above the valley
+        url.push_str(&format!("&elevation={}", elevation));
+    }
+    url
+}
+
+// elsewhere the 15 minute data is interpolated from hourly models and doesn't add anything
+fn has_native_nowcast(latitude: f64, longitude: f64) -> bool {
+    NOWCAST_REGIONS
+        .iter()
+        .any(|(min_latitude, max_latitude, min_longitude, max_longitude)| (*min_latitude..=*max_latitude).contains(&latitude) && (*min_longitude..=*max_longitude).contains(&longitude))
+}
+
+fn make_elevation_request(latitude: f64, longitude: f64) -> Request {
+    let url = format!("https://api.open-meteo.com/v1/elevation?latitude={}&longitude={}", latitude, longitude);
+    Request::new("elevation", url)
+}
+
+fn make_aviation_request(product: &str, station: &str, id: &str) -> Request { // product is "metar" or "taf"
+    let url = format!("https://aviationweather.gov/api/data/{}?ids={}&format=json", product, station);
+    Request::new(id, url)
+}
+
+fn parse_hourly_extra(hourly_extra: &str) -> Vec<String> {
+    // eg. "dew_point_2m, pressure_msl", names are checked to keep them from changing the rest of the url
+    let mut variables: Vec<String> = vec![];
+    for variable in hourly_extra.split(',').map(|v| v.trim()) {
+        let valid = !variable.is_empty() && variable.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if valid && !variables.iter().any(|v| v == variable) {
+            variables.push(variable.to_owned());
+        }
+    }
+    variables
+}
+
+fn parse_wind_heights(wind_heights: &str) -> Vec<usize> {
+    // eg. "80,120m", only the heights the API has, lowest first
+    let requested: Vec<usize> = wind_heights.split(',').filter_map(|h| h.trim().trim_end_matches('m').parse().ok()).collect();
+    WIND_HEIGHT_VARIABLES.iter().map(|(height, _)| *height).filter(|height| requested.contains(height)).collect()
+}
+
+fn make_aurora_request() -> Request {
+    Request::new(AURORA_REQUEST_ID, KP_FORECAST_URL.to_owned())
+}
+
+fn make_air_quality_request(latitude: f64, longitude: f64) -> Request {
+    let url = format!(
+        "https://air-quality-api.open-meteo.com/v1/air-quality?latitude={}&longitude={}&hourly={}&past_days={}&forecast_days=5",
+        latitude,
+        longitude,
+        air_quality_hourly_variables().join(","),
+        PAST_DAYS
+    );
+    Request::new(AIR_QUALITY_REQUEST_ID, url)
+}
+
+fn make_marine_request(latitude: f64, longitude: f64) -> Request {
+    let url = format!(
+        "https://marine-api.open-meteo.com/v1/marine?latitude={}&longitude={}&hourly={}&past_days={}",
+        latitude,
+        longitude,
+        MARINE_HOURLY_VARIABLES.join(","),
+        PAST_DAYS
+    );
+    Request::new("marine", url)
+}
+
+fn make_trip_forecast_request(latitude: f64, longitude: f64, id: &str) -> Request {
+    let url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&hourly=temperature_2m&daily={}&forecast_days={}",
+        latitude,
+        longitude,
+        DAILY_VARIABLES.join(","),
+        TRIP_FORECAST_DAYS
+    );
+    Request::new(id, url)
+}
+
+fn make_team_request(coordinates: &[(f64, f64)]) -> Request {
+    let latitudes: Vec<String> = coordinates.iter().map(|(latitude, _)| latitude.to_string()).collect();
+    let longitudes: Vec<String> = coordinates.iter().map(|(_, longitude)| longitude.to_string()).collect();
+    let url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m,weather_code&timezone=auto",
+        latitudes.join(","),
+        longitudes.join(",")
+    );
+    Request::new(TEAM_REQUEST_ID, url)
+}
+
+fn make_temperature_map_request(latitude: f64, longitude: f64) -> Request {
+    let points = grid_points(latitude, longitude);
+    let latitudes: Vec<String> = points.iter().map(|(latitude, _)| format!("{:.2}", latitude)).collect();
+    let longitudes: Vec<String> = points.iter().map(|(_, longitude)| format!("{:.2}", longitude)).collect();
+    let url = format!("https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m", latitudes.join(","), longitudes.join(","));
+    Request::new("map", url)
+}
+
+fn make_alerts_request(source: AlertSource, latitude: f64, longitude: f64, place: &str) -> Request {
+    let url = source.url(latitude, longitude);
+    match source {
+        // the NWS turns away requests that don't say who is asking
+        AlertSource::Nws => Request::new(NWS_ALERTS_REQUEST_ID, url)
+            .with_header("User-Agent", "weather-pal (https://github.com/imsnif/weather-pal)")
+            .with_header("Accept", "application/geo+json"),
+        AlertSource::MeteoAlarm(_) => Request::new(&meteoalarm_request_id(latitude, longitude, place), url),
+    }
+}
+
+fn make_geocode_request(query: &str, language: Language, count: usize, id: &str) -> Option<Request> {
+    let city = encode_query(query);
+    if city.is_empty() {
+        return None;
+    }
+    let url = format!("https://geocoding-api.open-meteo.com/v1/search?name={}&count={}&language={}&format=json", city, count, language.code());
+    Some(Request::new(id, url))
+}
+
+// the suggestions are picked with the number keys, starting at 1
+fn suggestion_index(key: &Key, suggestion_count: usize) -> Option<usize> {
+    match key {
+        Key::Char(c) => c.to_digit(10).map(|d| d as usize).filter(|d| (1..=suggestion_count).contains(d)).map(|d| d - 1),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requests::RequestQueue;
+
+    fn favorite(query: &str, latitude: f64, longitude: f64) -> Favorite {
+        Favorite {
+            query: query.to_owned(),
+            resolved: Some((latitude, longitude, query.to_owned())),
+        }
+    }
+
+    #[test]
+    fn prefetches_every_other_favorite_with_its_own_url() {
+        let state = State {
+            favorites: vec![
+                favorite("Vienna", 48.21, 16.37),
+                favorite("Berlin", 52.52, 13.41),
+                favorite("Tromsø", 69.65, 18.96),
+                Favorite { query: "Nowhere".to_owned(), resolved: None },
+                favorite("Lisbon", 38.72, -9.14),
+            ],
+            geolocation: Some((48.21, 16.37)),
+            ..Default::default()
+        };
+        let requests = state.favorite_forecast_requests();
+        let mut queue = RequestQueue::default();
+        for request in requests.clone() {
+            queue.push(request);
+        }
+        let mut now = Local::now();
+        let mut sent = vec![];
+        while let Some(request) = queue.ready(now) {
+            sent.push(request.id);
+            now += chrono::Duration::seconds(1);
+        }
+        assert_eq!(sent, vec!["favorite_forecast:1", "favorite_forecast:2", "favorite_forecast:4"]);
+        // the aurora up north is only looked for where it can be seen, whichever location is on screen
+        assert_eq!(requests[1].url, state.weather_url(69.65, 18.96));
+        assert!(requests[1].url.contains("cloud_cover"));
+        assert!(!requests[2].url.contains("cloud_cover"));
+    }
+
+    #[test]
+    fn asks_only_for_what_is_needed() {
+        let mut state = State::default();
+        assert_eq!(state.permissions(), vec![PermissionType::ReadApplicationState, PermissionType::ChangeApplicationState, PermissionType::RunCommands, PermissionType::WebAccess]);
+        state.timezone_command = Some(false);
+        assert_eq!(state.permissions(), vec![PermissionType::ReadApplicationState, PermissionType::ChangeApplicationState, PermissionType::WebAccess]);
+        state.demo = true;
+        assert_eq!(state.permissions(), vec![PermissionType::ReadApplicationState, PermissionType::ChangeApplicationState]);
+    }
+
+    #[test]
+    fn refreshes_the_location_on_screen_on_enter() {
+        let mut state = State {
+            geolocation: Some((48.21, 16.37)),
+            ..Default::default()
+        };
+        assert!(state.refreshes_on_enter());
+        state.location_being_typed = Some(LineInput::default());
+        assert!(!state.refreshes_on_enter());
+        state.location_being_typed = None;
+        state.demo = true;
+        assert!(!state.refreshes_on_enter());
+        assert!(!State::default().refreshes_on_enter());
+    }
+}
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike, Utc};
+
+use crate::parse::DailyData;
+
+// rough durations, the real ones depend on latitude and season
+const GOLDEN_HOUR_MINUTES: usize = 60;
+const BLUE_HOUR_MINUTES: usize = 30;
+const SUNSET_ELEVATION: f64 = -0.833; // the upper edge of the sun, with the refraction of the atmosphere
+const ASTRONOMICAL_TWILIGHT_ELEVATION: f64 = -18.0;
+const COMPASS_POINTS: &[&str] = &["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+
+pub struct SunTimes {
+    pub sunrise: usize, // minutes since midnight
+    pub sunset: usize,
+}
+
+impl SunTimes {
+    pub fn from_daily(daily_data: &DailyData) -> Option<Self> {
+        Some(SunTimes {
+            sunrise: minute_of_day(daily_data.sunrise.as_ref()?)?,
+            sunset: minute_of_day(daily_data.sunset.as_ref()?)?,
+        })
+    }
+    pub fn daylight_minutes(&self) -> usize {
+        self.sunset.saturating_sub(self.sunrise)
+    }
+    // morning and evening, as start and end minutes
+    pub fn golden_hours(&self) -> [(usize, usize); 2] {
+        [(self.sunrise, self.sunrise + GOLDEN_HOUR_MINUTES), (self.sunset.saturating_sub(GOLDEN_HOUR_MINUTES), self.sunset)]
+    }
+    pub fn blue_hours(&self) -> [(usize, usize); 2] {
+        [(self.sunrise.saturating_sub(BLUE_HOUR_MINUTES), self.sunrise), (self.sunset, self.sunset + BLUE_HOUR_MINUTES)]
+    }
+}
+
+// where the sun is in the sky, in degrees above the horizon and clockwise from north,
+// with the low precision formulas of the astronomical almanac, well within a degree for a couple of centuries
+pub fn sun_position(latitude: f64, longitude: f64, time: DateTime<Utc>) -> (f64, f64) { // elevation, azimuth
+    let days = time.timestamp() as f64 / 86400.0 - 10957.5; // since the J2000 epoch
+    let mean_longitude = (280.460 + 0.9856474 * days).rem_euclid(360.0);
+    let mean_anomaly = (357.528 + 0.9856003 * days).rem_euclid(360.0).to_radians();
+    let ecliptic_longitude = (mean_longitude + 1.915 * mean_anomaly.sin() + 0.020 * (2.0 * mean_anomaly).sin()).to_radians();
+    let obliquity = (23.439 - 0.0000004 * days).to_radians();
+    let right_ascension = (obliquity.cos() * ecliptic_longitude.sin()).atan2(ecliptic_longitude.cos());
+    let declination = (obliquity.sin() * ecliptic_longitude.sin()).asin();
+    let sidereal_time = (280.46061837 + 360.98564736629 * days + longitude).rem_euclid(360.0).to_radians();
+    let hour_angle = sidereal_time - right_ascension;
+    let latitude = latitude.to_radians();
+    let elevation = (latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * hour_angle.cos()).asin();
+    let azimuth = (-hour_angle.sin()).atan2(declination.tan() * latitude.cos() - latitude.sin() * hour_angle.cos());
+    (elevation.to_degrees(), azimuth.to_degrees().rem_euclid(360.0))
+}
+
+// how long after sunset the sky gets fully dark, and before sunrise it starts to get light,
+// none when the sun doesn't get 18° below the horizon, as in summer at high latitudes
+pub fn astronomical_twilight_minutes(latitude: f64, date: NaiveDate) -> Option<usize> {
+    let declination = (-23.44 * (360.0 / 365.0 * (date.ordinal() as f64 + 10.0)).to_radians().cos()).to_radians();
+    let latitude = latitude.to_radians();
+    let hour_angle = |elevation: f64| (elevation.to_radians().sin() - latitude.sin() * declination.sin()) / (latitude.cos() * declination.cos());
+    let (sunset, dark) = (hour_angle(SUNSET_ELEVATION), hour_angle(ASTRONOMICAL_TWILIGHT_ELEVATION));
+    if !(-1.0..=1.0).contains(&sunset) || dark < -1.0 {
+        return None;
+    }
+    // the earth turns by a degree every 4 minutes
+    Some(((dark.min(1.0).acos() - sunset.acos()).to_degrees() * 4.0).round() as usize)
+}
+
+pub fn compass_point(azimuth: f64) -> &'static str {
+    COMPASS_POINTS[((azimuth.rem_euclid(360.0) + 22.5) / 45.0) as usize % COMPASS_POINTS.len()]
+}
+
+fn minute_of_day(time: &str) -> Option<usize> {
+    // eg. "2023-11-19T06:57" as sent by the API
+    let time = NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").ok()?;
+    Some(time.hour() as usize * 60 + time.minute() as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_golden_and_blue_hours_from_sunrise_and_sunset() {
+        let daily_data = DailyData {
+            sunrise: Some("2023-11-19T05:58".to_owned()),
+            sunset: Some("2023-11-19T15:08".to_owned()),
+            ..Default::default()
+        };
+        let sun_times = SunTimes::from_daily(&daily_data).unwrap();
+        assert_eq!(sun_times.golden_hours(), [(358, 418), (848, 908)]);
+        assert_eq!(sun_times.blue_hours(), [(328, 358), (908, 938)]);
+        assert_eq!(sun_times.daylight_minutes(), 550);
+        assert!(SunTimes::from_daily(&DailyData { sunrise: Some("06:00".to_owned()), ..daily_data }).is_none());
+    }
+
+    #[test]
+    fn finds_the_sun_in_the_sky() {
+        let at = |time: &str| NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").unwrap().and_utc();
+        // vienna around solar noon of the summer solstice, 90° - 48.2° + 23.44°
+        let (elevation, azimuth) = sun_position(48.2, 16.37, at("2023-06-21T10:56"));
+        assert!((elevation - 65.2).abs() < 0.3, "{}", elevation);
+        assert!((azimuth - 180.0).abs() < 1.0, "{}", azimuth);
+        // just set in the north west that evening, at 20:58 local time, and well below the horizon at midnight
+        let (elevation, azimuth) = sun_position(48.2, 16.37, at("2023-06-21T19:00"));
+        assert!((-1.5..-0.5).contains(&elevation), "{}", elevation);
+        assert_eq!(compass_point(azimuth), "NW");
+        assert!(sun_position(48.2, 16.37, at("2023-06-21T23:00")).0 < -15.0);
+        assert_eq!(compass_point(0.0), "N");
+        assert_eq!(compass_point(350.0), "N");
+        assert_eq!(compass_point(100.0), "E");
+        assert_eq!(compass_point(225.0), "SW");
+    }
+
+    #[test]
+    fn finds_how_long_astronomical_twilight_lasts() {
+        let date = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        // about an hour and three quarters in vienna in november, and it never gets fully dark in berlin around midsummer
+        assert!((95..115).contains(&astronomical_twilight_minutes(48.2, date("2023-11-19")).unwrap()));
+        assert_eq!(astronomical_twilight_minutes(52.5, date("2023-06-21")), None);
+        // short near the equator
+        assert!((60..80).contains(&astronomical_twilight_minutes(0.0, date("2023-03-21")).unwrap()));
+        assert_eq!(astronomical_twilight_minutes(78.2, date("2023-06-21")), None);
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RequestKind, WeatherPalError};
+use crate::favorites::{parse_favorites, Favorite};
+use crate::format::{colored_text, wmo_code_label};
+use crate::i18n::translate;
+use crate::render::{print_centered_lines, Renderer};
+use crate::state::State;
+use crate::wmo::WmoCode;
+use std::convert::TryFrom;
+
+pub const TEAM_REQUEST_ID: &str = "team";
+
+// a colleague somewhere else, the place is looked up like a favorite location
+#[derive(Clone, Debug, PartialEq)]
+pub struct TeamMember {
+    pub name: Option<String>,
+    pub place: Favorite,
+    pub failed: bool, // the place wasn't found
+    pub current: Option<CurrentWeather>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CurrentWeather {
+    pub temperature_2m: f64,
+    pub wmo_code: Option<WmoCode>,
+    pub utc_offset_seconds: i64,
+}
+
+impl CurrentWeather {
+    pub fn local_time(&self, now: DateTime<Utc>) -> String {
+        (now + Duration::seconds(self.utc_offset_seconds)).format("%H:%M").to_string()
+    }
+}
+
+// eg. "Alice: Berlin; Bob: Tel Aviv; 52.2,21.0", the names are optional
+pub fn parse_team(team: &str) -> Vec<TeamMember> {
+    team.split(';')
+        .filter_map(|member| {
+            let (name, place) = match member.split_once(':') {
+                Some((name, place)) => (Some(name.trim().to_owned()).filter(|n| !n.is_empty()), place),
+                None => (None, member),
+            };
+            let place = parse_favorites(place).into_iter().next()?;
+            Some(TeamMember { name, place, failed: false, current: None })
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CurrentResponses {
+    Many(Vec<CurrentResponse>),
+    One(CurrentResponse), // the API only answers with a list when asked for several places
+}
+
+#[derive(Deserialize)]
+struct CurrentResponse {
+    utc_offset_seconds: i64,
+    current: CurrentValues,
+}
+
+#[derive(Deserialize)]
+struct CurrentValues {
+    temperature_2m: f64,
+    #[serde(default)]
+    weather_code: Option<usize>,
+}
+
+pub fn parse_current_weather(body: Vec<u8>) -> Result<Vec<CurrentWeather>, WeatherPalError> {
+    let responses: CurrentResponses = serde_json::from_slice(&body).map_err(|e| WeatherPalError::Parse(RequestKind::Team, e.to_string()))?;
+    let responses = match responses {
+        CurrentResponses::Many(responses) => responses,
+        CurrentResponses::One(response) => vec![response],
+    };
+    Ok(responses
+        .into_iter()
+        .map(|response| CurrentWeather {
+            temperature_2m: response.current.temperature_2m,
+            wmo_code: response.current.weather_code.and_then(|c| WmoCode::try_from(c).ok()),
+            utc_offset_seconds: response.utc_offset_seconds,
+        })
+        .collect())
+}
+
+impl State {
+    pub(crate) fn render_team<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize) {
+        // a line for each colleague with their local time and the weather outside their window
+        let named = self.team.iter().any(|member| member.name.is_some());
+        let mut lines = vec![];
+        for member in &self.team {
+            let location = member.place.resolved.as_ref().map(|(_, _, location)| location.clone()).unwrap_or_else(|| member.place.query.clone());
+            let mut line = vec![];
+            if named {
+                line.push(colored_text(member.name.clone().unwrap_or_else(|| " ".to_owned()), None));
+            }
+            line.push(colored_text(location, Some(self.palette.highlight())));
+            match (&member.current, self.clock) {
+                (Some(current), Some(now)) => {
+                    let (wmo_code_label, wmo_code_color) = wmo_code_label(current.wmo_code, self.palette, self.language);
+                    line.push(colored_text(current.local_time(now), Some(self.palette.hour())));
+                    line.push(colored_text(format!("{}{}", self.locale.format_temperature(current.temperature_2m), self.locale.temperature_symbol()), None));
+                    line.push(colored_text(if wmo_code_label.is_empty() { " ".to_owned() } else { wmo_code_label }, wmo_code_color));
+                }
+                _ => {
+                    // in the last column, which is the widest anyway, so it doesn't push the times apart
+                    let status = if member.failed { "No matching location found" } else { "Fetching data..." };
+                    line.push(colored_text(" ".to_owned(), None));
+                    line.push(colored_text(" ".to_owned(), None));
+                    line.push(colored_text(translate(self.language, status).to_owned(), None));
+                }
+            }
+            lines.push(line);
+        }
+        print_centered_lines(renderer, lines, rows, cols);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::tests::{render_snapshot, state_with_forecast};
+    use crate::state::View;
+    use chrono::{NaiveDate, TimeZone};
+
+    #[test]
+    fn parses_named_and_unnamed_colleagues() {
+        let team = parse_team("Alice: Berlin; Tel Aviv;Carol:52.2,21.0;;Dave:");
+        let names: Vec<(Option<&str>, &str)> = team.iter().map(|m| (m.name.as_deref(), m.place.query.as_str())).collect();
+        assert_eq!(names, vec![(Some("Alice"), "Berlin"), (None, "Tel Aviv"), (Some("Carol"), "52.2,21.0")]);
+        assert_eq!(team[2].place.resolved, Some((52.2, 21.0, "52.20, 21.00".to_owned())));
+    }
+
+    #[test]
+    fn parses_the_current_weather_of_one_or_more_places() {
+        let one = br#"{"utc_offset_seconds":3600,"current":{"time":"2023-11-19T10:00","temperature_2m":7.5,"weather_code":3}}"#;
+        let weather = parse_current_weather(one.to_vec()).unwrap();
+        assert_eq!(weather, vec![CurrentWeather { temperature_2m: 7.5, wmo_code: Some(WmoCode::Overcast), utc_offset_seconds: 3600 }]);
+        let many = format!("[{},{}]", String::from_utf8_lossy(one), r#"{"utc_offset_seconds":-18000,"current":{"temperature_2m":-2.0}}"#);
+        let weather = parse_current_weather(many.into_bytes()).unwrap();
+        assert_eq!(weather.len(), 2);
+        assert_eq!(weather[1].wmo_code, None);
+        let now = Utc.with_ymd_and_hms(2023, 11, 19, 9, 30, 0).unwrap();
+        assert_eq!(weather[0].local_time(now), "10:30");
+        assert_eq!(weather[1].local_time(now), "04:30");
+    }
+
+    #[test]
+    fn shows_the_local_time_and_weather_of_each_colleague() {
+        let mut team = parse_team("Alice: Tel Aviv; Bob: New York; Atlantis; Carol: 52.2,21.0");
+        team[0].place.resolved = Some((32.08, 34.78, "Tel Aviv, Israel".to_owned()));
+        team[0].current = Some(CurrentWeather { temperature_2m: 24.3, wmo_code: Some(WmoCode::ClearSky), utc_offset_seconds: 7200 });
+        team[1].place.resolved = Some((40.71, -74.01, "New York, United States".to_owned()));
+        team[1].current = Some(CurrentWeather { temperature_2m: -1.2, wmo_code: Some(WmoCode::SlightSnow), utc_offset_seconds: -18000 });
+        team[2].failed = true;
+        let state = State {
+            view: View::Team,
+            team,
+            clock: Some(NaiveDate::from_ymd_opt(2023, 11, 19).unwrap().and_hms_opt(14, 5, 0).unwrap().and_utc()),
+            ..state_with_forecast()
+        };
+        let expected = [
+            "",
+            "",
+            "",
+            "",
+            "",
+            "      Alice Tel Aviv, Israel        16:05 24.3°C CLEAR SKY",
+            "      Bob   New York, United States 09:05 -1.2°C SLIGHT SNOW",
+            "            Atlantis                             No matching location found",
+            "      Carol 52.20, 21.00                         Fetching data...",
+            "",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 12, 80, 10), expected.join("\n"));
+    }
+}
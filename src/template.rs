@@ -0,0 +1,36 @@
+use std::collections::BTreeMap;
+
+// the one-line summary of the compact mode and the pane title
+pub const DEFAULT_FORMAT: &str = "{icon} {temp}{unit} {precip}%";
+pub const FIELDS: &[&str] = &["icon", "temp", "unit", "precip", "desc", "wind", "high", "low", "location"];
+
+// eg. "{icon} {temp}{unit}", a field without a value is left out and anything else in braces is kept as it is
+pub fn render_template(template: &str, fields: &BTreeMap<&str, String>) -> String {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some((start, end)) = rest.find('{').and_then(|start| rest[start..].find('}').map(|length| (start, start + length))) {
+        rendered.push_str(&rest[..start]);
+        let name = &rest[start + 1..end];
+        match fields.get(name) {
+            Some(value) => rendered.push_str(value),
+            None if FIELDS.contains(&name) => (),
+            None => rendered.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_the_fields() {
+        let fields = BTreeMap::from([("icon", "☀".to_owned()), ("temp", "9.8".to_owned()), ("unit", "°C".to_owned())]);
+        assert_eq!(render_template(DEFAULT_FORMAT, &fields), "☀ 9.8°C %");
+        assert_eq!(render_template("{location}: {temp}{unit} {wat} {temp", &fields), ": 9.8°C {wat} {temp");
+        assert_eq!(render_template("", &fields), "");
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::format::{colored_text, wmo_code_label};
+use crate::i18n::translate;
+use crate::parse::DailyData;
+use crate::render::{day_label, print_centered_lines, Renderer};
+use crate::state::State;
+
+pub const TRIP_FORECAST_DAYS: i64 = 16; // as far ahead as the forecast API goes
+
+// a stop of the trip, looked up and fetched one after the other and kept for as long as the plugin runs
+#[derive(Default)]
+pub struct TripLeg {
+    pub query: String,
+    pub date: NaiveDate,
+    pub location: Option<(f64, f64, String)>, // lat, lon, location
+    pub day: Option<DailyData>,
+    pub failed: bool, // not found, or the date is out of the forecast's range
+}
+
+// eg. "Rome 12.6, Florence 14.6, Venice 2024-06-16", dates without a year are the next time that day comes around
+pub fn parse_trip(trip: &str, today: NaiveDate) -> Vec<TripLeg> {
+    trip.split(',')
+        .filter_map(|leg| {
+            let (query, date) = leg.trim().rsplit_once(' ')?;
+            let date = parse_date(date, today)?;
+            Some(TripLeg { query: query.trim().to_owned(), date, ..Default::default() })
+        })
+        .filter(|leg| !leg.query.is_empty())
+        .collect()
+}
+
+fn parse_date(date: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let (day, month) = date.trim_end_matches('.').split_once('.')?;
+    let (day, month) = (day.parse().ok()?, month.parse().ok()?);
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if this_year < today {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    } else {
+        Some(this_year)
+    }
+}
+
+impl State {
+    pub(crate) fn render_trip<R: Renderer>(&self, renderer: &mut R, rows: usize, cols: usize) {
+        // a line for each leg with the forecast for the day of the visit, in the order of the trip
+        let mut lines = vec![];
+        for leg in &self.trip_legs {
+            let date = day_label(&leg.date.to_string(), self.language);
+            let location = leg.location.as_ref().map(|(_, _, location)| location.clone()).unwrap_or_else(|| leg.query.clone());
+            let mut line = vec![colored_text(date, Some(self.palette.hour())), colored_text(location, Some(self.palette.highlight()))];
+            match &leg.day {
+                Some(day) => {
+                    let (wmo_code_label, wmo_code_color) = wmo_code_label(day.wmo_code, self.palette, self.language);
+                    let temperature = |t: Option<f64>| t.map(|t| format!("{}{}", self.locale.format_temperature(t), self.locale.temperature_symbol())).unwrap_or_else(|| "-".to_owned());
+                    line.push(colored_text(wmo_code_label, wmo_code_color));
+                    line.push(colored_text(format!("{} / {}", temperature(day.temperature_2m_max), temperature(day.temperature_2m_min)), None));
+                    line.push(colored_text(format!("💧 {}", day.precipitation_sum.map(|p| self.locale.format_precipitation(p)).unwrap_or_else(|| "-".to_owned())), Some(self.palette.precipitation())));
+                }
+                None if !leg.failed => line.push(colored_text(translate(self.language, "Fetching data...").to_owned(), None)),
+                None if leg.location.is_none() => line.push(colored_text(translate(self.language, "No matching location found").to_owned(), None)),
+                None => line.push(colored_text(translate(self.language, "No forecast for this date").to_owned(), None)),
+            }
+            lines.push(line);
+        }
+        print_centered_lines(renderer, lines, rows, cols);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{parse_weather_data, PAST_DAYS};
+    use crate::render::tests::{render_snapshot, state_with_forecast};
+    use crate::state::View;
+
+    #[test]
+    fn parses_legs_with_dates() {
+        let today = NaiveDate::from_ymd_opt(2023, 11, 19).unwrap();
+        let legs = parse_trip("Rome 12.6, Florence 14.6., Venice 2024-06-16, Rio de Janeiro 20.11, Nowhere, 31.2", today);
+        let legs: Vec<(&str, String)> = legs.iter().map(|leg| (leg.query.as_str(), leg.date.to_string())).collect();
+        assert_eq!(
+            legs,
+            vec![
+                ("Rome", "2024-06-12".to_owned()),
+                ("Florence", "2024-06-14".to_owned()),
+                ("Venice", "2024-06-16".to_owned()),
+                ("Rio de Janeiro", "2023-11-20".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_the_forecast_for_each_leg_of_a_trip() {
+        let mut forecast = parse_weather_data(include_bytes!("../fixtures/forecast_with_synthetic_series.json").to_vec(), PAST_DAYS).unwrap();
+        let today = NaiveDate::from_ymd_opt(2023, 11, 19).unwrap();
+        let mut trip_legs = parse_trip("Rome 20.11, Florence 22.11, Atlantis 23.11, Venice 24.11, Naples 12.12", today);
+        trip_legs[0].location = Some((41.89, 12.48, "Rome, Italy".to_owned()));
+        trip_legs[0].day = forecast.daily.remove(&1);
+        trip_legs[1].location = Some((43.77, 11.25, "Florence, Italy".to_owned()));
+        trip_legs[1].day = forecast.daily.remove(&3);
+        trip_legs[2].failed = true;
+        trip_legs[4].location = Some((40.85, 14.27, "Naples, Italy".to_owned()));
+        trip_legs[4].failed = true;
+        let state = State {
+            view: View::Trip,
+            trip_legs,
+            ..state_with_forecast()
+        };
+        let expected = [
+            "",
+            "",
+            "",
+            "",
+            "    Mon 20 Rome, Italy     RAIN SHOWERS (SLIGHT)      6.7°C / 2.3°C 💧 5.4mm",
+            "    Wed 22 Florence, Italy RAIN SHOWERS (MODERATE)    8.6°C / 2.4°C 💧 29.4mm",
+            "    Thu 23 Atlantis        No matching location found",
+            "    Fri 24 Venice          Fetching data...",
+            "    Tue 12 Naples, Italy   No forecast for this date",
+            "",
+            "",
+            "Press <ENTER> to reload, <Ctrl-w> to enter a new location",
+        ];
+        assert_eq!(render_snapshot(&state, 12, 80, 10), expected.join("\n"));
+    }
+}
@@ -0,0 +1,55 @@
+use unicode_width::UnicodeWidthChar;
+
+// symbols that unicode leaves narrow by default but most terminals draw as a two cell emoji
+const WIDE_SYMBOLS: &[char] = &['☀', '☁', '☂', '❄', '⛈', '⛰', '🌤', '🌫', '🌦', '🌧', '🌨'];
+
+pub fn char_width(character: char) -> usize {
+    if WIDE_SYMBOLS.contains(&character) {
+        2
+    } else {
+        character.width().unwrap_or(0)
+    }
+}
+
+// how many terminal cells the text takes up, for centering and aligning
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+// the text broken into lines of at most the width on the spaces between words, a word too long for a line gets one of its own
+pub fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if display_width(line) + 1 + display_width(word) <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_owned()),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_text_in_terminal_cells() {
+        assert_eq!(display_width("Vienna, Austria"), 15);
+        assert_eq!(display_width("💧 26%"), 6);
+        assert_eq!(display_width("☀ 9h 10m"), 9);
+        assert_eq!(display_width("↑  11.1kph"), 10);
+        assert_eq!(display_width("東京"), 4);
+        assert_eq!(display_width("Zu\u{308}rich"), 6);
+    }
+
+    #[test]
+    fn wraps_on_word_boundaries() {
+        assert_eq!(wrap_words("14:00, 9.2 degrees, mainly clear.", 20), vec!["14:00, 9.2 degrees,", "mainly clear."]);
+        assert_eq!(wrap_words("a  b", 80), vec!["a b"]);
+        assert_eq!(wrap_words("Vienna, Austria", 3), vec!["Vienna,", "Austria"]);
+        assert!(wrap_words("", 80).is_empty());
+    }
+}
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    None,
+    Mild,
+    Severe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WmoCode {
+    ClearSky = 0,
+    MainlyClear = 1,
+    PartlyCloudy = 2,
+    Overcast = 3,
+    Fog = 45,
+    DepositingRimeFog = 48,
+    LightDrizzle = 51,
+    ModerateDrizzle = 53,
+    DenseDrizzle = 55,
+    LightFreezingDrizzle = 56,
+    DenseFreezingDrizzle = 57,
+    SlightRain = 61,
+    ModerateRain = 63,
+    HeavyRain = 65,
+    LightFreezingRain = 66,
+    HeavyFreezingRain = 67,
+    SlightSnow = 71,
+    ModerateSnow = 73,
+    HeavySnow = 75,
+    SnowGrains = 77,
+    SlightRainShowers = 80,
+    ModerateRainShowers = 81,
+    ViolentRainShowers = 82,
+    SlightSnowShowers = 85,
+    HeavySnowShowers = 86,
+    Thunderstorm = 95,
+    ThunderstormWithSlightHail = 96,
+    ThunderstormWithHeavyHail = 99,
+}
+
+impl TryFrom<usize> for WmoCode {
+    type Error = String;
+    fn try_from(wmo_code: usize) -> Result<Self, Self::Error> {
+        match wmo_code {
+            0 => Ok(WmoCode::ClearSky),
+            1 => Ok(WmoCode::MainlyClear),
+            2 => Ok(WmoCode::PartlyCloudy),
+            3 => Ok(WmoCode::Overcast),
+            45 => Ok(WmoCode::Fog),
+            48 => Ok(WmoCode::DepositingRimeFog),
+            51 => Ok(WmoCode::LightDrizzle),
+            53 => Ok(WmoCode::ModerateDrizzle),
+            55 => Ok(WmoCode::DenseDrizzle),
+            56 => Ok(WmoCode::LightFreezingDrizzle),
+            57 => Ok(WmoCode::DenseFreezingDrizzle),
+            61 => Ok(WmoCode::SlightRain),
+            63 => Ok(WmoCode::ModerateRain),
+            65 => Ok(WmoCode::HeavyRain),
+            66 => Ok(WmoCode::LightFreezingRain),
+            67 => Ok(WmoCode::HeavyFreezingRain),
+            71 => Ok(WmoCode::SlightSnow),
+            73 => Ok(WmoCode::ModerateSnow),
+            75 => Ok(WmoCode::HeavySnow),
+            77 => Ok(WmoCode::SnowGrains),
+            80 => Ok(WmoCode::SlightRainShowers),
+            81 => Ok(WmoCode::ModerateRainShowers),
+            82 => Ok(WmoCode::ViolentRainShowers),
+            85 => Ok(WmoCode::SlightSnowShowers),
+            86 => Ok(WmoCode::HeavySnowShowers),
+            95 => Ok(WmoCode::Thunderstorm),
+            96 => Ok(WmoCode::ThunderstormWithSlightHail),
+            99 => Ok(WmoCode::ThunderstormWithHeavyHail),
+            _ => Err(format!("Unknown WMO code: {}", wmo_code)),
+        }
+    }
+}
+
+impl WmoCode {
+    pub fn description(&self) -> &'static str {
+        match self {
+            WmoCode::ClearSky => "CLEAR SKY",
+            WmoCode::MainlyClear => "MAINLY CLEAR",
+            WmoCode::PartlyCloudy => "PARTLY CLOUDY",
+            WmoCode::Overcast => "OVERCAST",
+            WmoCode::Fog => "FOG",
+            WmoCode::DepositingRimeFog => "FOG",
+            WmoCode::LightDrizzle => "LIGHT DRIZZLE",
+            WmoCode::ModerateDrizzle => "MODERATE DRIZZLE",
+            WmoCode::DenseDrizzle => "DENSE DRIZZLE",
+            WmoCode::LightFreezingDrizzle => "FREEZING DRIZZLE (LIGHT)",
+            WmoCode::DenseFreezingDrizzle => "FREEZING DRIZZLE (DENSE)",
+            WmoCode::SlightRain => "SLIGHT RAIN",
+            WmoCode::ModerateRain => "MODERATE RAIN",
+            WmoCode::HeavyRain => "HEAVY RAIN",
+            WmoCode::LightFreezingRain => "FREEZING RAIN (LIGHT)",
+            WmoCode::HeavyFreezingRain => "FREEZING RAIN (HEAVY)",
+            WmoCode::SlightSnow => "SLIGHT SNOW",
+            WmoCode::ModerateSnow => "MODERATE SNOW",
+            WmoCode::HeavySnow => "HEAVY SNOW",
+            WmoCode::SnowGrains => "SNOW GRAINS",
+            WmoCode::SlightRainShowers => "RAIN SHOWERS (SLIGHT)",
+            WmoCode::ModerateRainShowers => "RAIN SHOWERS (MODERATE)",
+            WmoCode::ViolentRainShowers => "RAIN SHOWERS (VIOLENT)",
+            WmoCode::SlightSnowShowers => "SNOW SHOWERS (SLIGHT)",
+            WmoCode::HeavySnowShowers => "SNOW SHOWERS (HEAVY)",
+            WmoCode::Thunderstorm => "THUNDERSTORM",
+            WmoCode::ThunderstormWithSlightHail => "THUNDERSTORM (SLIGHT HAIL)",
+            WmoCode::ThunderstormWithHeavyHail => "THUNDERSTORM (HEAVY HAIL)",
+        }
+    }
+    pub fn severity(&self) -> Severity {
+        match self {
+            WmoCode::ClearSky
+            | WmoCode::MainlyClear
+            | WmoCode::PartlyCloudy
+            | WmoCode::Overcast => Severity::None,
+            WmoCode::Fog
+            | WmoCode::DepositingRimeFog
+            | WmoCode::LightDrizzle
+            | WmoCode::ModerateDrizzle
+            | WmoCode::LightFreezingDrizzle
+            | WmoCode::SlightRain
+            | WmoCode::ModerateRain
+            | WmoCode::LightFreezingRain
+            | WmoCode::SlightSnow
+            | WmoCode::SlightRainShowers
+            | WmoCode::ModerateRainShowers
+            | WmoCode::SlightSnowShowers => Severity::Mild,
+            WmoCode::DenseDrizzle
+            | WmoCode::DenseFreezingDrizzle
+            | WmoCode::HeavyRain
+            | WmoCode::HeavyFreezingRain
+            | WmoCode::ModerateSnow
+            | WmoCode::HeavySnow
+            | WmoCode::SnowGrains
+            | WmoCode::ViolentRainShowers
+            | WmoCode::HeavySnowShowers
+            | WmoCode::Thunderstorm
+            | WmoCode::ThunderstormWithSlightHail
+            | WmoCode::ThunderstormWithHeavyHail => Severity::Severe,
+        }
+    }
+    pub fn icon(&self) -> &'static str {
+        match self {
+            WmoCode::ClearSky => "☀",
+            WmoCode::MainlyClear => "🌤",
+            WmoCode::PartlyCloudy => "⛅",
+            WmoCode::Overcast => "☁",
+            WmoCode::Fog => "🌫",
+            WmoCode::DepositingRimeFog => "🌫",
+            WmoCode::LightDrizzle => "🌦",
+            WmoCode::ModerateDrizzle => "🌦",
+            WmoCode::DenseDrizzle => "🌧",
+            WmoCode::LightFreezingDrizzle => "🧊",
+            WmoCode::DenseFreezingDrizzle => "🧊",
+            WmoCode::SlightRain => "🌦",
+            WmoCode::ModerateRain => "🌧",
+            WmoCode::HeavyRain => "🌧",
+            WmoCode::LightFreezingRain => "🧊",
+            WmoCode::HeavyFreezingRain => "🧊",
+            WmoCode::SlightSnow => "🌨",
+            WmoCode::ModerateSnow => "🌨",
+            WmoCode::HeavySnow => "❄",
+            WmoCode::SnowGrains => "🌨",
+            WmoCode::SlightRainShowers => "🌦",
+            WmoCode::ModerateRainShowers => "🌧",
+            WmoCode::ViolentRainShowers => "🌧",
+            WmoCode::SlightSnowShowers => "🌨",
+            WmoCode::HeavySnowShowers => "❄",
+            WmoCode::Thunderstorm => "⛈",
+            WmoCode::ThunderstormWithSlightHail => "⛈",
+            WmoCode::ThunderstormWithHeavyHail => "⛈",
+        }
+    }
+}
+
+const MODERATE_CAPE: f64 = 1000.0; // J/kg, enough energy for thunderstorms to form
+const HIGH_CAPE: f64 = 2500.0; // strong thunderstorms are likely once they do
+
+// a thunderstorm risk from the convective available potential energy, before the forecast calls it a thunderstorm
+pub fn lightning_risk(cape: Option<f64>, wmo_code: Option<WmoCode>) -> Severity {
+    let already_thunderstorm = matches!(wmo_code, Some(WmoCode::Thunderstorm | WmoCode::ThunderstormWithSlightHail | WmoCode::ThunderstormWithHeavyHail));
+    match cape {
+        _ if already_thunderstorm => Severity::None,
+        Some(cape) if cape >= HIGH_CAPE => Severity::Severe,
+        Some(cape) if cape >= MODERATE_CAPE => Severity::Mild,
+        _ => Severity::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WMO_CODES: &[usize] = &[0, 1, 2, 3, 45, 48, 51, 53, 55, 56, 57, 61, 63, 65, 66, 67, 71, 73, 75, 77, 80, 81, 82, 85, 86, 95, 96, 99];
+
+    #[test]
+    fn converts_every_documented_wmo_code() {
+        for wmo_code in WMO_CODES {
+            assert_eq!(WmoCode::try_from(*wmo_code).map(|w| w as usize), Ok(*wmo_code));
+        }
+        assert!(WmoCode::try_from(4).is_err());
+        assert!(WmoCode::try_from(100).is_err());
+    }
+
+    #[test]
+    fn descriptions_are_distinct() {
+        let mut descriptions: Vec<&str> = WMO_CODES
+            .iter()
+            .filter(|wmo_code| **wmo_code != 48) // 45 and 48 are both fog
+            .map(|wmo_code| WmoCode::try_from(*wmo_code).unwrap().description())
+            .collect();
+        let count = descriptions.len();
+        descriptions.sort();
+        descriptions.dedup();
+        assert_eq!(descriptions.len(), count);
+        assert_eq!(WmoCode::DenseDrizzle.description(), "DENSE DRIZZLE");
+    }
+
+    #[test]
+    fn severity_follows_intensity() {
+        assert_eq!(WmoCode::Overcast.severity(), Severity::None);
+        assert_eq!(WmoCode::SlightRain.severity(), Severity::Mild);
+        assert_eq!(WmoCode::HeavyRain.severity(), Severity::Severe);
+        assert_eq!(WmoCode::ThunderstormWithHeavyHail.severity(), Severity::Severe);
+    }
+
+    #[test]
+    fn high_cape_is_a_lightning_risk() {
+        assert_eq!(lightning_risk(Some(300.0), Some(WmoCode::Overcast)), Severity::None);
+        assert_eq!(lightning_risk(Some(1200.0), Some(WmoCode::PartlyCloudy)), Severity::Mild);
+        assert_eq!(lightning_risk(Some(3000.0), None), Severity::Severe);
+        assert_eq!(lightning_risk(Some(3000.0), Some(WmoCode::Thunderstorm)), Severity::None);
+        assert_eq!(lightning_risk(None, Some(WmoCode::ClearSky)), Severity::None);
+    }
+}
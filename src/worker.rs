@@ -0,0 +1,129 @@
+use zellij_tile::prelude::*;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::air_quality::{parse_air_quality, AIR_QUALITY_REQUEST_ID};
+use crate::alerts::{parse_meteoalarm_alerts, parse_meteoalarm_request_id, parse_nws_alerts, NWS_ALERTS_REQUEST_ID};
+use crate::aurora::{parse_kp_forecast, AURORA_REQUEST_ID};
+use crate::aviation::{parse_metar, parse_taf};
+use crate::climate::{parse_climate_normals, CLIMATE_REQUEST_ID};
+use crate::error::{RequestKind, WeatherPalError};
+use crate::map::parse_temperature_grid;
+use crate::marine::parse_marine_data;
+use crate::parse::{parse_elevation, parse_lat_lon_and_location, parse_location_suggestions, parse_weather_data, PAST_DAYS};
+use crate::team::{parse_current_weather, TEAM_REQUEST_ID};
+
+pub const PARSE_WORKER_NAME: &str = "parse";
+pub const WEATHER_PARSED_MESSAGE: &str = "weather_parsed";
+pub const GEOCODE_PARSED_MESSAGE: &str = "geocode_parsed";
+pub const ELEVATION_PARSED_MESSAGE: &str = "elevation_parsed";
+pub const SUGGESTIONS_PARSED_MESSAGE: &str = "suggestions_parsed";
+pub const TYPEAHEAD_PARSED_MESSAGE: &str = "typeahead_parsed";
+pub const ALERTS_PARSED_MESSAGE: &str = "alerts_parsed";
+pub const AURORA_PARSED_MESSAGE: &str = "aurora_parsed";
+pub const CLIMATE_PARSED_MESSAGE: &str = "climate_parsed";
+pub const COMPARE_PARSED_MESSAGE: &str = "compare_parsed";
+pub const MAP_PARSED_MESSAGE: &str = "map_parsed";
+pub const MARINE_PARSED_MESSAGE: &str = "marine_parsed";
+pub const AIR_QUALITY_PARSED_MESSAGE: &str = "air_quality_parsed";
+pub const METAR_PARSED_MESSAGE: &str = "metar_parsed";
+pub const METAR_UPDATED_MESSAGE: &str = "metar_updated"; // on refreshes, without looking up the location again
+pub const TAF_PARSED_MESSAGE: &str = "taf_parsed";
+pub const FAVORITE_PARSED_MESSAGE: &str = "favorite_parsed";
+pub const FAVORITE_REQUEST_PREFIX: &str = "favorite:"; // followed by the index of the favorite
+pub const TRIP_LOCATION_PARSED_MESSAGE: &str = "trip_location_parsed";
+pub const TRIP_FORECAST_PARSED_MESSAGE: &str = "trip_forecast_parsed";
+pub const TRIP_GEOCODE_PREFIX: &str = "trip_geocode:"; // followed by the index of the leg
+pub const TRIP_FORECAST_PREFIX: &str = "trip_forecast:";
+pub const TEAM_LOCATION_PARSED_MESSAGE: &str = "team_location_parsed";
+pub const TEAM_PARSED_MESSAGE: &str = "team_parsed";
+pub const TEAM_GEOCODE_PREFIX: &str = "team_geocode:"; // followed by the index of the colleague
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct ParseWorker;
+
+impl<'de> ZellijWorker<'de> for ParseWorker {
+    fn on_message(&mut self, message: String, payload: String) {
+        let (reply, serialized) = match message.as_str() {
+            "weather" => (WEATHER_PARSED_MESSAGE, serde_json::to_string(&parse_weather_data(payload.into_bytes(), PAST_DAYS))),
+            "geocode" => (GEOCODE_PARSED_MESSAGE, serde_json::to_string(&parse_lat_lon_and_location(payload.into_bytes(), false))),
+            "geocode_prefer_largest" => (GEOCODE_PARSED_MESSAGE, serde_json::to_string(&parse_lat_lon_and_location(payload.into_bytes(), true))),
+            "elevation" => (ELEVATION_PARSED_MESSAGE, serde_json::to_string(&parse_elevation(payload.into_bytes()))),
+            "suggestions" => (SUGGESTIONS_PARSED_MESSAGE, serde_json::to_string(&parse_location_suggestions(payload.into_bytes()))),
+            "typeahead" => (TYPEAHEAD_PARSED_MESSAGE, serde_json::to_string(&parse_location_suggestions(payload.into_bytes()))),
+            "compare" => (COMPARE_PARSED_MESSAGE, serde_json::to_string(&parse_weather_data(payload.into_bytes(), PAST_DAYS))),
+            "marine" => (MARINE_PARSED_MESSAGE, serde_json::to_string(&parse_marine_data(payload.into_bytes(), PAST_DAYS))),
+            AIR_QUALITY_REQUEST_ID => (AIR_QUALITY_PARSED_MESSAGE, serde_json::to_string(&parse_air_quality(payload.into_bytes(), PAST_DAYS))),
+            "metar" => (METAR_PARSED_MESSAGE, serde_json::to_string(&parse_metar(payload.into_bytes()))),
+            "metar_update" => (METAR_UPDATED_MESSAGE, serde_json::to_string(&parse_metar(payload.into_bytes()))),
+            "taf" => (TAF_PARSED_MESSAGE, serde_json::to_string(&parse_taf(payload.into_bytes()))),
+            "map" => (MAP_PARSED_MESSAGE, serde_json::to_string(&parse_temperature_grid(payload.into_bytes()))),
+            AURORA_REQUEST_ID => (AURORA_PARSED_MESSAGE, serde_json::to_string(&parse_kp_forecast(payload.into_bytes()))),
+            CLIMATE_REQUEST_ID => (CLIMATE_PARSED_MESSAGE, serde_json::to_string(&parse_climate_normals(payload.into_bytes()))),
+            TEAM_REQUEST_ID => (TEAM_PARSED_MESSAGE, serde_json::to_string(&parse_current_weather(payload.into_bytes()))),
+            NWS_ALERTS_REQUEST_ID => (ALERTS_PARSED_MESSAGE, serde_json::to_string(&parse_nws_alerts(payload.into_bytes()))),
+            other => {
+                if let Some(index) = indexed(other, FAVORITE_REQUEST_PREFIX) {
+                    (FAVORITE_PARSED_MESSAGE, serde_json::to_string(&parse_lat_lon_and_location(payload.into_bytes(), false).map(|location| (index, location))))
+                } else if let Some(index) = indexed(other, TRIP_GEOCODE_PREFIX) {
+                    (TRIP_LOCATION_PARSED_MESSAGE, serde_json::to_string(&parse_lat_lon_and_location(payload.into_bytes(), false).map(|location| (index, location))))
+                } else if let Some(index) = indexed(other, TRIP_FORECAST_PREFIX) {
+                    // trip forecasts start today, without the past days of the main forecast
+                    (TRIP_FORECAST_PARSED_MESSAGE, serde_json::to_string(&parse_weather_data(payload.into_bytes(), 0).map(|forecast| (index, forecast))))
+                } else if let Some(index) = indexed(other, TEAM_GEOCODE_PREFIX) {
+                    // the index goes along with failures too, so the colleague can be marked as not found
+                    (TEAM_LOCATION_PARSED_MESSAGE, serde_json::to_string(&Ok::<_, WeatherPalError>((index, parse_lat_lon_and_location(payload.into_bytes(), false)))))
+                } else if let Some((latitude, longitude, place)) = parse_meteoalarm_request_id(other) {
+                    (ALERTS_PARSED_MESSAGE, serde_json::to_string(&parse_meteoalarm_alerts(payload.into_bytes(), latitude, longitude, place)))
+                } else {
+                    return;
+                }
+            }
+        };
+        if let Ok(serialized) = serialized {
+            post_message_to_plugin(PluginMessage::new_to_plugin(reply, &serialized));
+        }
+    }
+}
+
+fn indexed(request_id: &str, prefix: &str) -> Option<usize> {
+    request_id.strip_prefix(prefix).and_then(|index| index.parse::<usize>().ok())
+}
+
+pub fn parse_in_worker(request_id: &str, body: &[u8]) {
+    post_message_to(PluginMessage::new_to_worker(PARSE_WORKER_NAME, request_id, &String::from_utf8_lossy(body)));
+}
+
+pub type TeamLocation = (usize, Result<(f64, f64, String), WeatherPalError>); // the index of the colleague and where they are
+
+// the worker sends back what it parsed, or why it couldn't
+pub fn decode<T: DeserializeOwned>(payload: &str, kind: RequestKind) -> Result<T, WeatherPalError> {
+    serde_json::from_str(payload).unwrap_or_else(|e| Err(WeatherPalError::Parse(kind, e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Forecast;
+    use crate::wmo::WmoCode;
+
+    #[test]
+    fn parsed_forecast_survives_the_trip_from_the_worker() {
+        let parsed = parse_weather_data(include_bytes!("../fixtures/forecast_with_synthetic_series.json").to_vec(), PAST_DAYS);
+        let forecast = decode::<Forecast>(&serde_json::to_string(&parsed).unwrap(), RequestKind::Weather).unwrap();
+        assert_eq!(forecast.hourly.len(), parsed.unwrap().hourly.len());
+        assert_eq!(forecast.hourly[&0].temperature_2m, 2.7);
+        assert_eq!(forecast.hourly[&144].wmo_code, Some(WmoCode::Fog));
+        assert_eq!(forecast.daily[&3].precipitation_sum, Some(29.4));
+    }
+
+    #[test]
+    fn parse_errors_survive_the_trip_from_the_worker() {
+        let parsed = parse_lat_lon_and_location(include_bytes!("../fixtures/geocode_no_results.json").to_vec(), false);
+        assert_eq!(decode::<(f64, f64, String)>(&serde_json::to_string(&parsed).unwrap(), RequestKind::Geocode), Err(WeatherPalError::GeocodeEmpty));
+        assert!(matches!(decode::<Forecast>("not json", RequestKind::Weather), Err(WeatherPalError::Parse(RequestKind::Weather, _))));
+        let team_location: Result<TeamLocation, WeatherPalError> = Ok((2, parsed));
+        assert_eq!(decode::<TeamLocation>(&serde_json::to_string(&team_location).unwrap(), RequestKind::Geocode), Ok((2, Err(WeatherPalError::GeocodeEmpty))));
+    }
+}